@@ -0,0 +1,535 @@
+//! Alert rules (`[[alerts]]` in the config) and their actions.
+//!
+//! Rules are evaluated against the pattern store about once a second. A rule
+//! fires for a pattern that received lines since the previous evaluation and
+//! meets every condition the rule sets; it then stays quiet for that pattern
+//! until its cooldown has passed.
+
+use std::collections::{HashMap, VecDeque};
+use std::process::Stdio;
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Context, Result};
+use regex::Regex;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use tokio::sync::mpsc;
+
+use crate::config::AlertConfig;
+use crate::parse::Level;
+use crate::pattern::Pattern;
+
+/// Fired alerts kept for the alerts pane.
+const LOG_CAPACITY: usize = 200;
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(30);
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AlertActions {
+    pub bell: bool,
+    pub banner: bool,
+    pub command: Option<String>,
+    pub webhook: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct AlertRule {
+    pub name: String,
+    pattern: Option<Regex>,
+    min_level: Option<Level>,
+    source: Option<String>,
+    rate_above: Option<f64>,
+    spike: bool,
+    new_pattern: bool,
+    cooldown: Duration,
+    pub actions: AlertActions,
+}
+
+impl AlertRule {
+    pub fn compile(cfg: &AlertConfig) -> Result<AlertRule> {
+        let pattern = cfg
+            .pattern
+            .as_deref()
+            .map(Regex::new)
+            .transpose()
+            .with_context(|| format!("alert '{}': invalid pattern regex", cfg.name))?;
+        let min_level = match cfg.level.as_deref() {
+            Some(name) => match Level::from_name(name) {
+                Some(level) => Some(level),
+                None => bail!("alert '{}': unknown level '{}'", cfg.name, name),
+            },
+            None => None,
+        };
+        if let Some(ref url) = cfg.webhook {
+            if !url.starts_with("http://") && !url.starts_with("https://") {
+                bail!("alert '{}': webhook must be an http(s) URL", cfg.name);
+            }
+        }
+        Ok(AlertRule {
+            name: cfg.name.clone(),
+            pattern,
+            min_level,
+            source: cfg.source.clone(),
+            rate_above: cfg.rate_above,
+            spike: cfg.spike,
+            new_pattern: cfg.new_pattern,
+            cooldown: Duration::from_secs(cfg.cooldown_secs),
+            actions: AlertActions {
+                bell: cfg.bell,
+                banner: cfg.banner,
+                command: cfg.command.clone(),
+                webhook: cfg.webhook.clone(),
+            },
+        })
+    }
+
+    /// Conditions on the pattern itself (activity and cooldown are checked by the engine).
+    fn matches(&self, p: &Pattern, is_new: bool) -> bool {
+        if self.new_pattern && !is_new {
+            return false;
+        }
+        if self.spike && !p.spike {
+            return false;
+        }
+        if self.rate_above.is_some_and(|r| p.rate_1m() < r) {
+            return false;
+        }
+        if self.min_level.is_some_and(|l| p.level.severity() < l.severity()) {
+            return false;
+        }
+        if let Some(ref glob) = self.source {
+            if !p.sources.iter().any(|s| glob_match(glob, s)) {
+                return false;
+            }
+        }
+        if let Some(ref re) = self.pattern {
+            let sample = p.samples.back().map(String::as_str).unwrap_or("");
+            if !re.is_match(&p.canonical) && !re.is_match(sample) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// `*` matches any run of characters; everything else is literal.
+fn glob_match(glob: &str, s: &str) -> bool {
+    let parts: Vec<&str> = glob.split('*').collect();
+    if parts.len() == 1 {
+        return glob == s;
+    }
+    let (first, last) = (parts[0], parts[parts.len() - 1]);
+    if !s.starts_with(first) || !s[first.len()..].ends_with(last) {
+        return false;
+    }
+    let mut rest = &s[first.len()..s.len() - last.len()];
+    for part in &parts[1..parts.len() - 1] {
+        match rest.find(part) {
+            Some(i) => rest = &rest[i + part.len()..],
+            None => return false,
+        }
+    }
+    true
+}
+
+/// One fired alert, as listed in the alerts pane.
+#[derive(Debug, Clone)]
+pub struct FiredAlert {
+    id: u64,
+    pub at: chrono::DateTime<chrono::Local>,
+    pub rule: String,
+    pub pattern: String,
+    pub level: Level,
+    /// Actions taken, with command / webhook outcomes appended as they finish.
+    pub outcome: Vec<String>,
+}
+
+/// What the app itself should do for alerts fired in one evaluation.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Notification {
+    pub bell: bool,
+    pub banner: Option<String>,
+}
+
+pub struct AlertEngine {
+    rules: Vec<AlertRule>,
+    /// count_total of each pattern (by store index) at the last evaluation.
+    seen: Vec<u64>,
+    cooldowns: HashMap<(usize, String), Instant>,
+    pub log: VecDeque<FiredAlert>,
+    next_id: u64,
+    results_tx: mpsc::UnboundedSender<(u64, String)>,
+    results_rx: mpsc::UnboundedReceiver<(u64, String)>,
+    http: reqwest::Client,
+}
+
+/// Compile every `[[alerts]]` rule, failing on the first bad one.
+pub fn compile_rules(configs: &[AlertConfig]) -> Result<Vec<AlertRule>> {
+    configs.iter().map(AlertRule::compile).collect()
+}
+
+impl AlertEngine {
+    pub fn new(rules: Vec<AlertRule>) -> Self {
+        let (results_tx, results_rx) = mpsc::unbounded_channel();
+        AlertEngine {
+            rules,
+            seen: Vec::new(),
+            cooldowns: HashMap::new(),
+            log: VecDeque::new(),
+            next_id: 0,
+            results_tx,
+            results_rx,
+            http: reqwest::Client::new(),
+        }
+    }
+
+    pub fn from_config(configs: &[AlertConfig]) -> Result<Self> {
+        Ok(Self::new(compile_rules(configs)?))
+    }
+
+    /// Swap in new rules, keeping the log and what has been seen so far.
+    pub fn set_rules(&mut self, rules: Vec<AlertRule>) {
+        self.rules = rules;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Alerts fired since startup.
+    pub fn fired(&self) -> u64 {
+        self.next_id
+    }
+
+    /// Forget pattern state after the store was reset (the log is kept).
+    pub fn reset(&mut self) {
+        self.seen.clear();
+        self.cooldowns.clear();
+    }
+
+    /// Check every rule against patterns that saw activity since the last call.
+    pub fn evaluate(&mut self, patterns: &[Pattern], now: Instant) -> Notification {
+        let mut note = Notification::default();
+        if self.rules.is_empty() {
+            return note;
+        }
+        for (idx, p) in patterns.iter().enumerate() {
+            let is_new = idx >= self.seen.len();
+            let before = self.seen.get(idx).copied().unwrap_or(0);
+            // After `c` clears the counters a lower non-zero count is new activity too
+            let active = p.count_total > 0 && p.count_total != before;
+            if is_new {
+                self.seen.push(p.count_total);
+            } else {
+                self.seen[idx] = p.count_total;
+            }
+            if !active {
+                continue;
+            }
+            for rule_idx in 0..self.rules.len() {
+                if !self.rules[rule_idx].matches(p, is_new) {
+                    continue;
+                }
+                let key = (rule_idx, p.canonical.clone());
+                let cooldown = self.rules[rule_idx].cooldown;
+                if self
+                    .cooldowns
+                    .get(&key)
+                    .is_some_and(|&last| now.saturating_duration_since(last) < cooldown)
+                {
+                    continue;
+                }
+                self.cooldowns.insert(key, now);
+                self.fire(rule_idx, p, &mut note);
+            }
+        }
+        note
+    }
+
+    fn fire(&mut self, rule_idx: usize, p: &Pattern, note: &mut Notification) {
+        let rule = &self.rules[rule_idx];
+        let id = self.next_id;
+        self.next_id += 1;
+        let at = chrono::Local::now();
+        let mut outcome = Vec::new();
+
+        if rule.actions.bell {
+            note.bell = true;
+            outcome.push("bell".to_string());
+        }
+        if rule.actions.banner {
+            note.banner = Some(format!("{}: {}", rule.name, p.canonical));
+            outcome.push("banner".to_string());
+        }
+        if rule.actions.command.is_some() || rule.actions.webhook.is_some() {
+            let payload = payload(&rule.name, p, &at);
+            if let Some(ref cmd) = rule.actions.command {
+                outcome.push("command…".to_string());
+                tokio::spawn(run_command(
+                    cmd.clone(),
+                    rule.name.clone(),
+                    payload.to_string(),
+                    id,
+                    self.results_tx.clone(),
+                ));
+            }
+            if let Some(ref url) = rule.actions.webhook {
+                outcome.push("webhook…".to_string());
+                tokio::spawn(post_webhook(
+                    self.http.clone(),
+                    url.clone(),
+                    payload,
+                    id,
+                    self.results_tx.clone(),
+                ));
+            }
+        }
+
+        self.log.push_front(FiredAlert {
+            id,
+            at,
+            rule: rule.name.clone(),
+            pattern: p.canonical.clone(),
+            level: p.level,
+            outcome,
+        });
+        self.log.truncate(LOG_CAPACITY);
+    }
+
+    /// Wait (up to `timeout`) for running commands and webhooks to report back.
+    pub async fn settle(&mut self, timeout: Duration) {
+        let deadline = Instant::now() + timeout;
+        loop {
+            self.drain_results();
+            let pending = self
+                .log
+                .iter()
+                .any(|a| a.outcome.iter().any(|o| o.ends_with('…')));
+            if !pending || Instant::now() >= deadline {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    }
+
+    /// Fold finished command / webhook results into the log.
+    pub fn drain_results(&mut self) {
+        while let Ok((id, result)) = self.results_rx.try_recv() {
+            if let Some(alert) = self.log.iter_mut().find(|a| a.id == id) {
+                let kind = result.split(':').next().unwrap_or("");
+                let pending = format!("{}…", kind);
+                match alert.outcome.iter_mut().find(|o| **o == pending) {
+                    Some(slot) => *slot = result,
+                    None => alert.outcome.push(result),
+                }
+            }
+        }
+    }
+}
+
+/// JSON describing the pattern that triggered an alert (command stdin, webhook body).
+fn payload(rule: &str, p: &Pattern, at: &chrono::DateTime<chrono::Local>) -> serde_json::Value {
+    let mut sources: Vec<&str> = p.sources.iter().map(String::as_str).collect();
+    sources.sort_unstable();
+    serde_json::json!({
+        "alert": rule,
+        "fired_at": at.to_rfc3339(),
+        "id": p.id(),
+        "pattern": p.canonical,
+        "level": p.level.as_str(),
+        "count": p.count_total,
+        "rate_1m": p.rate_1m(),
+        "rate_5m": p.rate_5m(),
+        "spike": p.spike,
+        "sources": sources,
+        "sample": p.samples.back(),
+        "fields": p
+            .fields
+            .iter()
+            .map(|(k, v)| (k.clone(), serde_json::Value::String(v.clone())))
+            .collect::<serde_json::Map<_, _>>(),
+    })
+}
+
+async fn run_command(
+    cmd: String,
+    rule: String,
+    stdin_json: String,
+    id: u64,
+    results: mpsc::UnboundedSender<(u64, String)>,
+) {
+    let result = async {
+        let mut child = Command::new("sh")
+            .args(["-c", &cmd])
+            .env("LOGRADAR_ALERT", &rule)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .kill_on_drop(true)
+            .spawn()?;
+        if let Some(mut stdin) = child.stdin.take() {
+            // A command that ignores stdin may close it early; that's fine
+            let _ = stdin.write_all(stdin_json.as_bytes()).await;
+        }
+        match tokio::time::timeout(COMMAND_TIMEOUT, child.wait()).await {
+            Ok(status) => anyhow::Ok(status?),
+            Err(_) => bail!("timed out"),
+        }
+    }
+    .await;
+    let message = match result {
+        Ok(status) if status.success() => "command: ok".to_string(),
+        Ok(status) => format!("command: {}", status),
+        Err(e) => format!("command: {}", e),
+    };
+    let _ = results.send((id, message));
+}
+
+async fn post_webhook(
+    http: reqwest::Client,
+    url: String,
+    body: serde_json::Value,
+    id: u64,
+    results: mpsc::UnboundedSender<(u64, String)>,
+) {
+    let message = match http.post(&url).json(&body).timeout(WEBHOOK_TIMEOUT).send().await {
+        Ok(resp) if resp.status().is_success() => format!("webhook: {}", resp.status().as_u16()),
+        Ok(resp) => format!("webhook: HTTP {}", resp.status().as_u16()),
+        Err(e) => format!("webhook: {}", e),
+    };
+    let _ = results.send((id, message));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::LogEvent;
+    use crate::pattern::PatternStore;
+
+    fn rule(toml_str: &str) -> AlertRule {
+        let cfg: AlertConfig = toml::from_str(&format!("name = \"test\"\n{}", toml_str)).unwrap();
+        AlertRule::compile(&cfg).unwrap()
+    }
+
+    fn ingest(store: &mut PatternStore, normalized: &str, level: Level, source: &str, now: Instant) {
+        store.ingest_at(
+            &LogEvent {
+                level,
+                source: source.into(),
+                raw: normalized.into(),
+                normalized: normalized.into(),
+                fields: Vec::new(),
+            },
+            now,
+        );
+    }
+
+    #[test]
+    fn globs() {
+        assert!(glob_match("docker/*", "docker/web"));
+        assert!(glob_match("*/api*", "k8s/prod/api-7f9"));
+        assert!(glob_match("file/app.log", "file/app.log"));
+        assert!(!glob_match("docker/*", "k8s/web"));
+        assert!(!glob_match("a*b*c", "acb"));
+    }
+
+    #[test]
+    fn rule_conditions() {
+        let now = Instant::now();
+        let mut store = PatternStore::new();
+        ingest(&mut store, "db timeout after <NUM>ms", Level::Error, "docker/api", now);
+        ingest(&mut store, "GET /health <NUM>", Level::Info, "k8s/web", now);
+        let (err, info) = (&store.patterns()[0], &store.patterns()[1]);
+
+        let r = rule("pattern = \"timeout\"\nlevel = \"warn\"");
+        assert!(r.matches(err, false));
+        assert!(!r.matches(info, false));
+        let r = rule("source = \"k8s/*\"");
+        assert!(!r.matches(err, false) && r.matches(info, false));
+        let r = rule("new_pattern = true");
+        assert!(r.matches(err, true) && !r.matches(err, false));
+        let r = rule("rate_above = 2.0");
+        assert!(!r.matches(err, false));
+        let r = rule("spike = true");
+        assert!(!r.matches(err, false));
+
+        let bad: AlertConfig = toml::from_str("name = \"x\"\npattern = \"(\"").unwrap();
+        assert!(AlertRule::compile(&bad).is_err());
+        let bad: AlertConfig = toml::from_str("name = \"x\"\nlevel = \"loud\"").unwrap();
+        assert!(AlertRule::compile(&bad).is_err());
+    }
+
+    #[test]
+    fn fires_on_activity_with_cooldown() {
+        let start = Instant::now();
+        let mut store = PatternStore::new();
+        let mut engine = AlertEngine::new(vec![rule("level = \"error\"\nbell = true\ncooldown_secs = 30")]);
+
+        ingest(&mut store, "disk full", Level::Error, "file/a", start);
+        ingest(&mut store, "request ok", Level::Info, "file/a", start);
+        let note = engine.evaluate(store.patterns(), start);
+        assert!(note.bell);
+        assert_eq!(note.banner.as_deref(), Some("test: disk full"));
+        assert_eq!(engine.log.len(), 1);
+        assert_eq!(engine.log[0].outcome, vec!["bell", "banner"]);
+
+        // No new lines: nothing to alert on
+        let later = start + Duration::from_secs(60);
+        assert_eq!(engine.evaluate(store.patterns(), later), Notification::default());
+        // New lines inside the cooldown stay quiet, after it they fire again
+        ingest(&mut store, "disk full", Level::Error, "file/a", start + Duration::from_secs(10));
+        assert!(!engine.evaluate(store.patterns(), start + Duration::from_secs(10)).bell);
+        ingest(&mut store, "disk full", Level::Error, "file/a", later);
+        assert!(engine.evaluate(store.patterns(), later).bell);
+        assert_eq!(engine.log.len(), 2);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn command_and_webhook_receive_pattern_json() {
+        use crate::ingest::http::{serve, HttpRequest, HttpResponse};
+        use hyper::StatusCode;
+
+        let out = std::env::temp_dir().join(format!("logradar-alert-{}.json", std::process::id()));
+        let (body_tx, mut body_rx) = mpsc::unbounded_channel();
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let _ = serve(listener, move |req: HttpRequest| {
+                let body_tx = body_tx.clone();
+                async move {
+                    let _ = body_tx.send(req.body.clone());
+                    HttpResponse::empty(StatusCode::NO_CONTENT)
+                }
+            })
+            .await;
+        });
+
+        let mut engine = AlertEngine::new(vec![rule(&format!(
+            "banner = false\ncommand = \"cat > {}\"\nwebhook = \"http://{}/hook\"",
+            out.display(),
+            addr
+        ))]);
+        let mut store = PatternStore::new();
+        ingest(&mut store, "payment declined", Level::Warn, "otlp/checkout", Instant::now());
+        engine.evaluate(store.patterns(), Instant::now());
+        assert_eq!(engine.log[0].outcome, vec!["command…", "webhook…"]);
+
+        let body = tokio::time::timeout(Duration::from_secs(5), body_rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["alert"], "test");
+        assert_eq!(json["pattern"], "payment declined");
+        assert_eq!(json["sources"], serde_json::json!(["otlp/checkout"]));
+
+        engine.settle(Duration::from_secs(5)).await;
+        assert_eq!(engine.log[0].outcome, vec!["command: ok", "webhook: 204"]);
+        let written: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&out).unwrap()).unwrap();
+        assert_eq!(written["level"], "WARN");
+        let _ = std::fs::remove_file(&out);
+        server.abort();
+    }
+}
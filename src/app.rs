@@ -1,17 +1,35 @@
-use std::collections::{HashMap, HashSet, VecDeque};
-use std::time::Instant;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
 
 use tokio::sync::mpsc;
 use tokio::task::JoinHandle;
 
-use crate::discovery::DiscoveryResult;
-use crate::ingest::{self, SourceEvent, SourceInfo, SourceStatus};
-use crate::parse;
+use crate::alerts::AlertEngine;
+use crate::color::ColorDepth;
+use crate::config::{self, WorkspaceConfig, WorkspaceSource};
+use crate::discovery::{AzureContainerApp, DiscoveryResult};
+use crate::export::{self, ExportPrompt, ExportScope};
+use crate::ingest::azure::{AccessToken, ContainerAppRef};
+use crate::ingest::journald::JournalFilter;
+use crate::ingest::kubernetes::KubeSelector;
+use crate::ingest::syslog::SyslogListen;
+use crate::ingest::{self, Source, SourceEvent, SourceInfo, SourceStatus};
+use crate::metrics::{MetricsSnapshot, PatternMetric, SharedMetrics, SourceMetric};
+use crate::parse::{self, Level};
 use crate::pattern::PatternStore;
 use crate::profile::Profile;
+use crate::redact::Redactor;
+use crate::reload::ConfigWatcher;
 use crate::search::{self, SearchResult};
+use crate::session::{self, Recorder, Recording, ReplaySpeed, ReplayState, SessionAction};
 use crate::theme::Theme;
-use crate::tui::source_menu::SourceMenuState;
+use crate::tui::keymap::Keymap;
+use crate::tui::layout::LayoutState;
+use crate::tui::mouse::PaneLayout;
+use crate::tui::palette::PaletteState;
+use crate::tui::source_menu::{SourceMenuScreen, SourceMenuState};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum AppMode {
@@ -21,6 +39,25 @@ pub enum AppMode {
     Help,
     ProfilePicker,
     SourceMenu,
+    /// Typing an export path in the status bar.
+    Export,
+    /// Log of fired alerts.
+    Alerts,
+    /// Command palette (`:` / Ctrl-P).
+    Palette,
+}
+
+const TOAST_DURATION: Duration = Duration::from_secs(4);
+const METRICS_INTERVAL: Duration = Duration::from_secs(1);
+const ALERTS_INTERVAL: Duration = Duration::from_secs(1);
+const ALERT_BANNER_DURATION: Duration = Duration::from_secs(10);
+
+/// A short-lived message in the status bar.
+#[derive(Debug, Clone)]
+pub struct Toast {
+    pub message: String,
+    pub error: bool,
+    pub until: Instant,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -43,6 +80,8 @@ pub struct App {
     pub profiles: Vec<Profile>,
     pub profile_index: usize,
     pub theme_override: Option<Theme>,
+    /// `[themes.*]` from the config, alongside the built-ins.
+    pub custom_themes: Vec<Theme>,
     pub should_quit: bool,
     pub log_count: u64,
     pub show_normalized: bool,
@@ -60,16 +99,63 @@ pub struct App {
     pub active_source_filter: Option<String>,
     // Per-source event rates (source_id → rolling 1m timestamps)
     pub source_rates: HashMap<String, VecDeque<Instant>>,
+    // Per-source line totals, and lines received but not ingested
+    pub source_lines: HashMap<String, u64>,
+    pub dropped_paused: u64,
+    pub dropped_below_level: u64,
     // Collapsed provider groups in Sources pane
     pub collapsed_groups: HashSet<String>,
     // Cached Azure management access token (pre-fetched during discovery)
-    pub azure_token: Option<String>,
+    pub azure_token: Option<AccessToken>,
     // Whether to show the ASCII banner header
     pub show_banner: bool,
+    // Session recording (--record); dropped on the first write error
+    pub recorder: Option<Recorder>,
+    pub record_error: Option<String>,
+    // Active replay (--replay); its position is the clock while set
+    pub replay: Option<ReplayState>,
+    // Export path prompt (AppMode::Export)
+    pub export_prompt: Option<ExportPrompt>,
+    pub toast: Option<Toast>,
+    // Snapshot served by --metrics-listen, refreshed every METRICS_INTERVAL
+    pub metrics: Option<SharedMetrics>,
+    metrics_published: Option<Instant>,
+    // [[alerts]] rules, checked every ALERTS_INTERVAL
+    pub alerts: AlertEngine,
+    alerts_checked: Option<Instant>,
+    pub alert_banner: Option<Toast>,
+    // Set when an alert rings the bell; the main loop writes BEL after drawing
+    pub bell_pending: bool,
+    pub alerts_scroll: usize,
+    // Secret / PII masking from [redaction]; None when disabled
+    pub redactor: Option<Redactor>,
+    // Mouse capture (off with --no-mouse), pane sizes / zoom, and where the
+    // last frame drew each pane
+    pub mouse: bool,
+    pub layout_state: LayoutState,
+    pub layout: PaneLayout,
+    pub dragging: Option<usize>,
+    // Config file settings are saved to (the loaded one, else the global path)
+    pub config_path: Option<PathBuf>,
+    // Key → action bindings from [keys]
+    pub keymap: Keymap,
+    // Command palette (AppMode::Palette) and the level set with `:level`
+    pub palette: PaletteState,
+    pub level_override: Option<Level>,
+    // What the terminal can show (--color / NO_COLOR / TERM); themes are
+    // mapped down to it when drawn
+    pub color_depth: ColorDepth,
+    // Watches the config file for live reload; the last reload error
+    // stays in the status bar until the file is fixed
+    pub config_watcher: Option<ConfigWatcher>,
+    pub config_error: Option<String>,
+    // [workspaces] from the config, and how to start each running source
+    // again (by source id) for saving a workspace
+    pub workspaces: BTreeMap<String, WorkspaceConfig>,
+    pub source_specs: HashMap<String, WorkspaceSource>,
 }
 
 impl App {
-    #[allow(dead_code)]
     pub fn new(profile_name: Option<&str>) -> Self {
         Self::with_profiles(Profile::all_profiles(), profile_name)
     }
@@ -92,6 +178,7 @@ impl App {
             profiles,
             profile_index,
             theme_override: None,
+            custom_themes: Vec::new(),
             should_quit: false,
             log_count: 0,
             show_normalized: false,
@@ -104,9 +191,38 @@ impl App {
             needs_clear: false,
             active_source_filter: None,
             source_rates: HashMap::new(),
+            source_lines: HashMap::new(),
+            dropped_paused: 0,
+            dropped_below_level: 0,
             collapsed_groups: HashSet::new(),
             azure_token: None,
             show_banner: true,
+            recorder: None,
+            record_error: None,
+            replay: None,
+            export_prompt: None,
+            toast: None,
+            metrics: None,
+            metrics_published: None,
+            alerts: AlertEngine::new(Vec::new()),
+            alerts_checked: None,
+            alert_banner: None,
+            bell_pending: false,
+            alerts_scroll: 0,
+            redactor: None,
+            mouse: true,
+            layout_state: LayoutState::default(),
+            layout: PaneLayout::default(),
+            dragging: None,
+            config_path: None,
+            keymap: Keymap::default(),
+            palette: PaletteState::default(),
+            level_override: None,
+            color_depth: ColorDepth::default(),
+            config_watcher: None,
+            config_error: None,
+            workspaces: BTreeMap::new(),
+            source_specs: HashMap::new(),
         }
     }
 
@@ -114,6 +230,11 @@ impl App {
         &self.profiles[self.profile_index]
     }
 
+    /// Lines below this level are dropped: `:level` if set, else the profile's.
+    pub fn min_level(&self) -> Level {
+        self.level_override.unwrap_or(self.profile().min_level)
+    }
+
     pub fn theme(&self) -> &Theme {
         if let Some(ref t) = self.theme_override {
             t
@@ -123,30 +244,249 @@ impl App {
     }
 
     pub fn toggle_theme(&mut self) {
-        self.theme_override = Some(self.theme().next());
+        self.theme_override = Some(self.theme().next(&self.custom_themes));
+    }
+
+    /// The clock patterns and rates are measured against: wall time live,
+    /// the recorded time of the latest entry while replaying.
+    pub fn now(&self) -> Instant {
+        self.replay.as_ref().map_or_else(Instant::now, |r| r.now())
     }
 
     pub fn process_event(&mut self, event: SourceEvent) {
+        // Redact first so secrets are never recorded or stored
+        let event = match self.redactor {
+            Some(ref redactor) => redactor.redact_event(event),
+            None => event,
+        };
+        // Lines dropped while paused never reach the store, so leave them
+        // out of the recording too
+        let dropped = self.paused
+            && matches!(
+                event,
+                SourceEvent::Log { .. } | SourceEvent::Record { .. }
+            );
+        if self.recorder.is_some() && !dropped {
+            let now = self.now();
+            self.record(|rec| rec.event(now, &event));
+        }
         match event {
-            SourceEvent::Log { source, line } => self.process_log(source, line),
+            SourceEvent::Log { source, line } => self.process_log(source, line, None, Vec::new()),
+            SourceEvent::Record {
+                source,
+                line,
+                level,
+                fields,
+            } => self.process_log(source, line, level, fields),
             SourceEvent::Status { source, status } => self.update_source_status(&source, status),
+            SourceEvent::Attached { info } => self.attach_source(info),
+            SourceEvent::Replayed { at, event } => {
+                if let Some(ref mut replay) = self.replay {
+                    replay.position = at;
+                }
+                self.process_event(*event);
+            }
+            SourceEvent::ReplayAction { at, action } => {
+                if let Some(ref mut replay) = self.replay {
+                    replay.position = at;
+                }
+                self.run_action(action);
+            }
+            SourceEvent::ReplayTick { at } => {
+                if let Some(ref mut replay) = self.replay {
+                    replay.position = at;
+                }
+                let now = self.now();
+                self.store.tick_at(now);
+            }
+            SourceEvent::ReplayReset => {
+                self.store.reset();
+                self.alerts.reset();
+                self.source_rates.clear();
+                self.source_lines.clear();
+                self.log_count = 0;
+                if let Some(ref mut replay) = self.replay {
+                    replay.position = std::time::Duration::ZERO;
+                }
+            }
+        }
+    }
+
+    /// Per-frame housekeeping. While replaying, store ticks come from the
+    /// recording instead so they land at the same recorded instants.
+    pub fn tick(&mut self) {
+        if self.replay.is_none() {
+            let now = Instant::now();
+            self.record(|rec| rec.tick(now));
+            self.store.tick_at(now);
+        }
+        self.tick_source_rates();
+        self.check_alerts();
+        self.publish_metrics();
+    }
+
+    /// Evaluate alert rules at most once per interval and apply bell / banner actions.
+    fn check_alerts(&mut self) {
+        self.alerts.drain_results();
+        if self.alerts.is_empty() {
+            return;
+        }
+        let wall = Instant::now();
+        if self
+            .alerts_checked
+            .is_some_and(|t| wall.duration_since(t) < ALERTS_INTERVAL)
+        {
+            return;
+        }
+        self.alerts_checked = Some(wall);
+        let note = self.alerts.evaluate(self.store.patterns(), self.now());
+        self.bell_pending |= note.bell;
+        if let Some(message) = note.banner {
+            self.alert_banner = Some(Toast {
+                message,
+                error: true,
+                until: wall + ALERT_BANNER_DURATION,
+            });
+        }
+    }
+
+    /// Evaluate alert rules now, regardless of the interval (e.g. before exiting).
+    pub fn check_alerts_now(&mut self) {
+        self.alerts_checked = None;
+        self.check_alerts();
+    }
+
+    /// The alert banner to show, if it hasn't expired.
+    pub fn active_alert_banner(&self) -> Option<&Toast> {
+        self.alert_banner.as_ref().filter(|t| t.until > Instant::now())
+    }
+
+    pub fn toggle_alerts(&mut self) {
+        if self.mode == AppMode::Alerts {
+            self.mode = AppMode::Normal;
+        } else {
+            self.mode = AppMode::Alerts;
+            self.alerts_scroll = 0;
+            // Opening the log acknowledges the banner
+            self.alert_banner = None;
+        }
+        self.needs_clear = true;
+    }
+
+    /// Refresh the --metrics-listen snapshot, at most once per interval.
+    fn publish_metrics(&mut self) {
+        let Some(ref shared) = self.metrics else {
+            return;
+        };
+        let now = Instant::now();
+        if self
+            .metrics_published
+            .is_some_and(|t| now.duration_since(t) < METRICS_INTERVAL)
+        {
+            return;
+        }
+        self.metrics_published = Some(now);
+        let snapshot = MetricsSnapshot {
+            patterns: self
+                .store
+                .patterns()
+                .iter()
+                .map(|p| PatternMetric {
+                    id: p.id(),
+                    level: match p.level {
+                        Level::Unknown => "UNKNOWN",
+                        level => level.as_str(),
+                    },
+                    canonical: p.canonical.clone(),
+                    count: p.count_total,
+                    rate_1m: p.rate_1m(),
+                    spike: p.spike,
+                })
+                .collect(),
+            sources: self
+                .sources
+                .iter()
+                .map(|s| SourceMetric {
+                    id: s.id.clone(),
+                    kind: s.kind.clone(),
+                    lines: self.source_lines.get(&s.id).copied().unwrap_or(0),
+                    rate_1m: self.source_rate_1m(&s.id),
+                    up: s.status.is_active(),
+                })
+                .collect(),
+            lines: self.log_count,
+            dropped_paused: self.dropped_paused,
+            dropped_below_level: self.dropped_below_level,
+        };
+        *shared.lock().unwrap() = snapshot;
+    }
+
+    /// Reset patterns or clear counters, recording it so a replay does the same.
+    pub fn apply_action(&mut self, action: SessionAction) {
+        let now = self.now();
+        self.record(|rec| rec.action(now, action));
+        self.run_action(action);
+    }
+
+    fn run_action(&mut self, action: SessionAction) {
+        match action {
+            SessionAction::ResetPatterns => {
+                self.store.reset();
+                self.alerts.reset();
+            }
+            SessionAction::ClearCounters => {
+                let now = self.now();
+                self.store.clear_counters_at(now);
+            }
         }
+        self.needs_clear = true;
     }
 
-    fn process_log(&mut self, source: String, line: String) {
+    fn record(&mut self, write: impl FnOnce(&mut Recorder) -> anyhow::Result<()>) {
+        if let Some(ref mut rec) = self.recorder {
+            if let Err(e) = write(rec) {
+                self.record_error = Some(format!("recording stopped: {:#}", e));
+                self.recorder = None;
+            }
+        }
+    }
+
+    /// Register a source reported by a supervising task. Re-attaching a known
+    /// id (e.g. a pod that came back) just refreshes its status.
+    fn attach_source(&mut self, info: SourceInfo) {
+        if let Some(src) = self.sources.iter_mut().find(|s| s.id == info.id) {
+            src.status = info.status;
+        } else {
+            self.sources.push(info);
+        }
+    }
+
+    fn process_log(
+        &mut self,
+        source: String,
+        line: String,
+        level: Option<Level>,
+        fields: parse::Fields,
+    ) {
         if self.paused {
+            self.dropped_paused += 1;
             return;
         }
+        *self.source_lines.entry(source.clone()).or_default() += 1;
         // Track per-source rate
+        let now = self.now();
         self.source_rates
             .entry(source.clone())
             .or_default()
-            .push_back(Instant::now());
+            .push_back(now);
 
-        let log_event = parse::parse_line(&source, &line);
-        if log_event.level.severity() >= self.profile().min_level.severity() {
-            self.store.ingest(&log_event);
+        let mut log_event = parse::parse_record(&source, &line, level);
+        log_event.fields = fields;
+        if log_event.level.severity() >= self.min_level().severity() {
+            self.store.ingest_at(&log_event, now);
             self.log_count += 1;
+        } else {
+            self.dropped_below_level += 1;
         }
     }
 
@@ -157,11 +497,11 @@ impl App {
     }
 
     /// Abort and remove a source by id (kills child process via kill_on_drop).
-    #[allow(dead_code)]
     pub fn stop_source(&mut self, source_id: &str) {
         if let Some(handle) = self.handles.remove(source_id) {
             handle.abort();
         }
+        self.source_specs.remove(source_id);
         if let Some(src) = self.sources.iter_mut().find(|s| s.id == source_id) {
             src.status = SourceStatus::Stopped;
         }
@@ -169,9 +509,11 @@ impl App {
 
     /// Prune old timestamps from per-source rate windows. Called each tick.
     pub fn tick_source_rates(&mut self) {
-        let cutoff = Instant::now() - std::time::Duration::from_secs(60);
+        let Some(cutoff) = self.now().checked_sub(std::time::Duration::from_secs(60)) else {
+            return;
+        };
         for timestamps in self.source_rates.values_mut() {
-            while timestamps.front().map_or(false, |t| *t < cutoff) {
+            while timestamps.front().is_some_and(|t| *t < cutoff) {
                 timestamps.pop_front();
             }
         }
@@ -184,7 +526,7 @@ impl App {
             .map_or(0.0, |ts| ts.len() as f64)
     }
 
-    /// Get total 1-minute rate for a provider kind (docker, azure, command, file).
+    /// Get total 1-minute rate for a provider kind (docker, azure, kubernetes, journald, syslog, http, otlp, ssh:<host>, command, file).
     pub fn provider_rate_1m(&self, kind: &str) -> f64 {
         self.sources
             .iter()
@@ -202,16 +544,39 @@ impl App {
         }
     }
 
-    /// Provider ordering for the Sources pane.
+    /// Provider ordering for the Sources pane. `ssh` stands for one group per
+    /// remote host (`ssh:<host>` kinds), sorted by host.
     pub fn provider_order() -> &'static [&'static str] {
-        &["docker", "azure", "command", "file"]
+        &["docker", "azure", "kubernetes", "journald", "syslog", "http", "otlp", "ssh", "command", "file", "replay"]
+    }
+
+    /// Source group kinds in display order, with `ssh` expanded per host.
+    fn source_group_kinds(&self) -> Vec<String> {
+        let mut kinds = Vec::new();
+        for &kind in Self::provider_order() {
+            if kind == "ssh" {
+                let mut hosts: Vec<String> = self
+                    .sources
+                    .iter()
+                    .filter(|s| s.kind.starts_with("ssh:"))
+                    .map(|s| s.kind.clone())
+                    .collect();
+                hosts.sort();
+                hosts.dedup();
+                kinds.extend(hosts);
+            } else {
+                kinds.push(kind.to_string());
+            }
+        }
+        kinds
     }
 
     /// Build the visible rows in the sources pane: headers + items.
     /// Returns vec of (is_header, kind, Option<source_index>).
     pub fn visible_source_rows(&self) -> Vec<(bool, String, Option<usize>)> {
         let mut rows = Vec::new();
-        for &kind in Self::provider_order() {
+        for kind in self.source_group_kinds() {
+            let kind = kind.as_str();
             let sources_in_kind: Vec<usize> = self
                 .sources
                 .iter()
@@ -268,18 +633,47 @@ impl App {
         self.active_pane = match self.active_pane {
             Pane::Sources => Pane::Patterns,
             Pane::Patterns => Pane::Details,
-            Pane::Details => Pane::Sources,
+            Pane::Details if self.layout_state.show_sources => Pane::Sources,
+            Pane::Details => Pane::Patterns,
         };
     }
 
     pub fn prev_pane(&mut self) {
         self.active_pane = match self.active_pane {
             Pane::Sources => Pane::Details,
-            Pane::Patterns => Pane::Sources,
+            Pane::Patterns if self.layout_state.show_sources => Pane::Sources,
+            Pane::Patterns => Pane::Details,
             Pane::Details => Pane::Patterns,
         };
     }
 
+    /// Hide or show the Sources pane, moving focus off it when hidden.
+    pub fn toggle_sources_pane(&mut self) {
+        self.layout_state.show_sources = !self.layout_state.show_sources;
+        if !self.layout_state.show_sources && self.active_pane == Pane::Sources {
+            self.active_pane = Pane::Patterns;
+        }
+        self.needs_clear = true;
+    }
+
+    /// Save the current pane layout to `[layout]` in the config file.
+    pub fn save_layout(&mut self) {
+        let Some(path) = self.config_path.clone() else {
+            self.show_toast("No config directory to save the layout to".to_string(), true);
+            return;
+        };
+        match config::save_layout(&path, &self.layout_state.to_config()) {
+            Ok(()) => {
+                // Our own write isn't a change to reload
+                if let Some(ref mut watcher) = self.config_watcher {
+                    watcher.mark_seen();
+                }
+                self.show_toast(format!("Saved layout to {}", path.display()), false)
+            }
+            Err(e) => self.show_toast(format!("Saving layout failed: {:#}", e), true),
+        }
+    }
+
     pub fn move_up(&mut self) {
         match self.active_pane {
             Pane::Sources => {
@@ -324,6 +718,26 @@ impl App {
         }
     }
 
+    /// First row of the focused list, or the top of the Details pane.
+    pub fn move_top(&mut self) {
+        match self.active_pane {
+            Pane::Sources => self.selected_source = 0,
+            Pane::Patterns => self.selected_pattern = 0,
+            Pane::Details => self.detail_scroll = 0,
+        }
+    }
+
+    /// Last row of the focused list; Details has no end to scroll to.
+    pub fn move_bottom(&mut self) {
+        match self.active_pane {
+            Pane::Sources => {
+                self.selected_source = self.visible_source_rows().len().saturating_sub(1);
+            }
+            Pane::Patterns => self.selected_pattern = self.filtered_view.len().saturating_sub(1),
+            Pane::Details => {}
+        }
+    }
+
     /// Handle Enter on sources pane — toggle collapse on group headers,
     /// or set/clear source filter on individual sources.
     pub fn activate_selected_source(&mut self) {
@@ -351,6 +765,69 @@ impl App {
             .map(|sr| &self.store.patterns()[sr.index])
     }
 
+    pub fn show_toast(&mut self, message: String, error: bool) {
+        self.toast = Some(Toast {
+            message,
+            error,
+            until: Instant::now() + TOAST_DURATION,
+        });
+    }
+
+    /// The toast to show, if it hasn't expired.
+    pub fn active_toast(&self) -> Option<&Toast> {
+        self.toast.as_ref().filter(|t| t.until > Instant::now())
+    }
+
+    pub fn open_export(&mut self, scope: ExportScope) {
+        if scope == ExportScope::Samples && self.selected_pattern_data().is_none() {
+            self.show_toast("No pattern selected".to_string(), true);
+            return;
+        }
+        self.export_prompt = Some(ExportPrompt::new(scope));
+        self.mode = AppMode::Export;
+    }
+
+    /// Write the export described by the prompt and report the result.
+    pub fn run_export(&mut self) {
+        self.mode = AppMode::Normal;
+        let Some(prompt) = self.export_prompt.take() else {
+            return;
+        };
+        let path = prompt.path.trim().to_string();
+        if path.is_empty() {
+            return;
+        }
+        let format = prompt.effective_format();
+        let (contents, what) = match prompt.scope {
+            ExportScope::View => {
+                let patterns: Vec<&crate::pattern::Pattern> = self
+                    .filtered_view
+                    .iter()
+                    .map(|sr| &self.store.patterns()[sr.index])
+                    .collect();
+                let n = patterns.len();
+                (
+                    export::render_patterns(format, &patterns),
+                    format!("{} pattern{}", n, if n == 1 { "" } else { "s" }),
+                )
+            }
+            ExportScope::Samples => match self.selected_pattern_data() {
+                Some(p) => (
+                    export::render_samples(format, p),
+                    format!("{} samples", p.samples.len()),
+                ),
+                None => return,
+            },
+        };
+        match export::write(&path, &contents) {
+            Ok(()) => self.show_toast(
+                format!("Exported {} as {} to {}", what, format.label(), path),
+                false,
+            ),
+            Err(e) => self.show_toast(format!("Export failed: {:#}", e), true),
+        }
+    }
+
     pub fn enter_search(&mut self) {
         self.mode = AppMode::Search;
         self.search_query.clear();
@@ -382,67 +859,125 @@ impl App {
         self.tx = Some(tx);
     }
 
-    pub fn add_docker_source(&mut self, container: String) {
-        if let Some(tx) = self.tx.clone() {
-            let (info, handle) = ingest::spawn_docker(container, tx);
-            let id = info.id.clone();
-            self.sources.push(info);
-            self.handles.insert(id, handle);
+    /// Track a newly spawned source. Recorded as an attach so a replay shows
+    /// the same Sources pane.
+    fn register_source(&mut self, info: SourceInfo, handle: JoinHandle<()>) {
+        if self.recorder.is_some() {
+            let now = self.now();
+            let event = SourceEvent::Attached { info: info.clone() };
+            self.record(|rec| rec.event(now, &event));
         }
+        let id = info.id.clone();
+        self.sources.push(info);
+        self.handles.insert(id, handle);
     }
 
-    pub fn add_file_source(&mut self, path: String) {
+    /// Play a recording back; its recorded sources appear as they attach.
+    pub fn add_replay_source(&mut self, path: &str, recording: Recording, speed: ReplaySpeed) {
         if let Some(tx) = self.tx.clone() {
-            let (info, handle) = ingest::spawn_file(path, tx);
-            let id = info.id.clone();
-            self.sources.push(info);
-            self.handles.insert(id, handle);
+            let total = recording.duration();
+            let (info, handle, control) = session::spawn_replay(path, recording, speed, tx);
+            self.register_source(info, handle);
+            self.replay = Some(ReplayState::new(control, total, speed));
         }
     }
 
-    pub fn add_command_source(&mut self, cmd: String) {
+    /// Start a source and track it in the Sources pane.
+    pub fn add_source(&mut self, source: Source) {
         if let Some(tx) = self.tx.clone() {
-            let name = cmd
-                .split_whitespace()
-                .next()
-                .unwrap_or("cmd")
-                .to_string();
-            let (info, handle) = ingest::spawn_command(name, cmd, tx);
-            let id = info.id.clone();
-            self.sources.push(info);
-            self.handles.insert(id, handle);
+            let spec = WorkspaceSource::from_source(&source);
+            let (info, handle) = source.spawn(tx);
+            self.source_specs.insert(info.id.clone(), spec);
+            self.register_source(info, handle);
         }
     }
 
+    pub fn add_docker_source(&mut self, container: String) {
+        self.add_source(Source::Docker(container));
+    }
+
+    pub fn add_file_source(&mut self, path: String) {
+        self.add_source(Source::File(path));
+    }
+
+    pub fn add_ssh_source(&mut self, target: ingest::ssh::SshTarget) {
+        self.add_source(Source::Ssh(target));
+    }
+
+    pub fn add_command_source(&mut self, cmd: String) {
+        self.add_source(Source::Command(cmd));
+    }
+
+    /// Follow every replica of `revision` (the newest active revision if None).
+    /// `system_logs` notes that the app's system logs were started alongside,
+    /// so a saved workspace brings them back.
     pub fn add_azure_source(
         &mut self,
         app_name: String,
         resource_group: String,
         subscription_id: String,
+        revision: Option<String>,
+        system_logs: bool,
     ) {
         if let Some(tx) = self.tx.clone() {
-            let (info, handle) = ingest::spawn_azure_containerapp(
-                app_name,
-                resource_group,
+            let app = ContainerAppRef {
                 subscription_id,
+                resource_group,
+                name: app_name,
+            };
+            let spec = WorkspaceSource::azure(&app, revision.clone(), system_logs);
+            let (info, handle) = ingest::azure::spawn_azure_containerapp(
+                app,
+                revision,
                 self.azure_token.clone(),
                 tx,
             );
-            let id = info.id.clone();
-            self.sources.push(info);
-            self.handles.insert(id, handle);
+            self.source_specs.insert(info.id.clone(), spec);
+            self.register_source(info, handle);
         }
     }
 
+    pub fn add_azure_system_source(&mut self, app: ContainerAppRef) {
+        if let Some(tx) = self.tx.clone() {
+            let (info, handle) =
+                ingest::azure::spawn_azure_system_logs(app, self.azure_token.clone(), tx);
+            self.register_source(info, handle);
+        }
+    }
+
+    pub fn add_kubernetes_source(&mut self, selector: KubeSelector) {
+        self.add_source(Source::Kubernetes(selector));
+    }
+
+    pub fn add_journald_source(&mut self, filter: JournalFilter) {
+        self.add_source(Source::Journald(filter));
+    }
+
+    pub fn add_syslog_source(&mut self, listen: SyslogListen) {
+        self.add_source(Source::Syslog(listen));
+    }
+
+    pub fn add_http_source(&mut self, addr: SocketAddr) {
+        self.add_source(Source::Http(addr));
+    }
+
+    pub fn add_otlp_source(&mut self, addr: SocketAddr) {
+        self.add_source(Source::Otlp(addr));
+    }
+
     pub fn open_source_menu(&mut self) {
         self.source_menu.reset();
         self.source_menu.docker_loading = true;
         self.source_menu.azure_loading = true;
+        self.source_menu.kube_loading = true;
+        self.source_menu.journald_loading = true;
         self.mode = AppMode::SourceMenu;
-        // Pre-fetch both Docker and Azure discovery in parallel
+        // Pre-fetch all discovery providers in parallel
         if let Some(dtx) = self.discovery_tx.clone() {
             crate::discovery::discover_docker(dtx.clone());
-            crate::discovery::discover_azure(dtx);
+            crate::discovery::discover_azure(dtx.clone());
+            crate::discovery::discover_kubernetes(dtx.clone());
+            crate::discovery::discover_journald(dtx);
         }
     }
 
@@ -469,9 +1004,38 @@ impl App {
             DiscoveryResult::AzureToken(Ok(token)) => {
                 self.azure_token = Some(token);
             }
+            DiscoveryResult::AzureRevisions(app_ref, result) => {
+                // Ignore results for an app the picker has since moved away from
+                let current = self.source_menu.azure_revision_app.as_ref().map(|a| a.app_ref());
+                if current == Some(app_ref) {
+                    self.source_menu.azure_revisions_loading = false;
+                    match result {
+                        Ok(revisions) => self.source_menu.azure_revisions = revisions,
+                        Err(e) => self.source_menu.azure_revisions_error = Some(e),
+                    }
+                }
+            }
             DiscoveryResult::AzureToken(Err(_)) => {
                 // Token pre-fetch failed; will fall back to az CLI for log streaming
             }
+            DiscoveryResult::Kubernetes(Ok(targets)) => {
+                self.source_menu.kube_targets = targets;
+                self.source_menu.kube_loading = false;
+                self.source_menu.kube_error = None;
+            }
+            DiscoveryResult::Kubernetes(Err(e)) => {
+                self.source_menu.kube_loading = false;
+                self.source_menu.kube_error = Some(e);
+            }
+            DiscoveryResult::Journald(Ok(units)) => {
+                self.source_menu.journald_units = units;
+                self.source_menu.journald_loading = false;
+                self.source_menu.journald_error = None;
+            }
+            DiscoveryResult::Journald(Err(e)) => {
+                self.source_menu.journald_loading = false;
+                self.source_menu.journald_error = Some(e);
+            }
         }
     }
 
@@ -485,15 +1049,87 @@ impl App {
     }
 
     pub fn spawn_selected_azure_sources(&mut self) {
+        let visible = self.source_menu.visible_azure_apps();
+        let apps: Vec<AzureContainerApp> = self
+            .source_menu
+            .selected
+            .iter()
+            .filter_map(|&idx| visible.get(idx).map(|a| (*a).clone()))
+            .collect();
+        for a in apps {
+            self.add_azure_app(a, None);
+        }
+    }
+
+    /// Add an app's replicas, plus its system logs when that option is on.
+    fn add_azure_app(&mut self, app: AzureContainerApp, revision: Option<String>) {
+        let system_logs = self.source_menu.azure_system_logs;
+        if system_logs {
+            self.add_azure_system_source(app.app_ref());
+        }
+        self.add_azure_source(
+            app.name,
+            app.resource_group,
+            app.subscription_id,
+            revision,
+            system_logs,
+        );
+    }
+
+    /// Open the revision picker for the Azure app under the cursor.
+    pub fn open_azure_revisions(&mut self) {
+        let Some(app) = self
+            .source_menu
+            .visible_azure_apps()
+            .get(self.source_menu.discovery_cursor)
+            .map(|a| (*a).clone())
+        else {
+            return;
+        };
+        self.source_menu.screen = SourceMenuScreen::AzureRevisions;
+        self.source_menu.revision_cursor = 0;
+        self.source_menu.azure_revisions.clear();
+        self.source_menu.azure_revisions_loading = true;
+        self.source_menu.azure_revisions_error = None;
+        self.source_menu.azure_revision_app = Some(app.clone());
+        if let Some(dtx) = self.discovery_tx.clone() {
+            crate::discovery::discover_azure_revisions(app, self.azure_token.clone(), dtx);
+        }
+    }
+
+    pub fn spawn_selected_azure_revision(&mut self) {
+        let menu = &self.source_menu;
+        let (Some(app), Some(rev)) = (
+            menu.azure_revision_app.clone(),
+            menu.azure_revisions.get(menu.revision_cursor).cloned(),
+        ) else {
+            return;
+        };
+        self.add_azure_app(app, Some(rev.name));
+    }
+
+    pub fn spawn_selected_kubernetes_sources(&mut self) {
         let selected: Vec<usize> = self.source_menu.selected.iter().copied().collect();
         for idx in selected {
-            if let Some(a) = self.source_menu.azure_apps.get(idx) {
-                self.add_azure_source(
-                    a.name.clone(),
-                    a.resource_group.clone(),
-                    a.subscription_id.clone(),
-                );
+            if let Some(t) = self.source_menu.kube_targets.get(idx) {
+                self.add_kubernetes_source(t.to_selector());
             }
         }
     }
+
+    /// All selected units are followed by a single journalctl process.
+    pub fn spawn_selected_journald_sources(&mut self) {
+        let mut selected: Vec<usize> = self.source_menu.selected.iter().copied().collect();
+        selected.sort_unstable();
+        let units: Vec<String> = selected
+            .into_iter()
+            .filter_map(|idx| self.source_menu.journald_units.get(idx).cloned())
+            .collect();
+        if !units.is_empty() {
+            self.add_journald_source(JournalFilter {
+                units,
+                ..Default::default()
+            });
+        }
+    }
 }
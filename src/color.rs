@@ -0,0 +1,214 @@
+//! Terminal color support: what the terminal can show (`COLORTERM`, `TERM`,
+//! `NO_COLOR` or `--color`) and mapping truecolor theme colors down to the
+//! nearest 256 or 16 ANSI colors.
+
+use ratatui::style::Color;
+
+/// `--color`: how many colors to draw with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum ColorChoice {
+    /// Detect from `NO_COLOR`, `COLORTERM` and `TERM`.
+    #[default]
+    Auto,
+    /// Truecolor, even with `NO_COLOR` set.
+    Always,
+    /// The mono theme in plain ANSI grays.
+    Never,
+    /// The 256-color palette.
+    #[value(name = "256")]
+    Ansi256,
+    /// The 16 basic ANSI colors.
+    #[value(name = "16")]
+    Ansi16,
+}
+
+/// Colors the terminal can show.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColorDepth {
+    #[default]
+    TrueColor,
+    Ansi256,
+    Ansi16,
+    /// `NO_COLOR`: the mono theme only.
+    NoColor,
+}
+
+impl ColorDepth {
+    pub fn resolve(choice: ColorChoice) -> ColorDepth {
+        match choice {
+            ColorChoice::Auto => Self::detect(),
+            ColorChoice::Always => ColorDepth::TrueColor,
+            ColorChoice::Never => ColorDepth::NoColor,
+            ColorChoice::Ansi256 => ColorDepth::Ansi256,
+            ColorChoice::Ansi16 => ColorDepth::Ansi16,
+        }
+    }
+
+    /// Read the depth from the environment.
+    pub fn detect() -> ColorDepth {
+        let var = |name| std::env::var(name).ok();
+        Self::from_env(var("NO_COLOR").as_deref(), var("COLORTERM").as_deref(), var("TERM").as_deref())
+    }
+
+    /// `NO_COLOR` (any non-empty value) wins, then `COLORTERM=truecolor` /
+    /// `24bit`, then `TERM`: `*-direct` is truecolor, `*256color*` is 256,
+    /// old and plain terminals get 16.
+    fn from_env(no_color: Option<&str>, colorterm: Option<&str>, term: Option<&str>) -> ColorDepth {
+        if no_color.is_some_and(|v| !v.is_empty()) {
+            return ColorDepth::NoColor;
+        }
+        if matches!(colorterm, Some("truecolor" | "24bit")) {
+            return ColorDepth::TrueColor;
+        }
+        let term = term.unwrap_or("");
+        if term == "dumb" {
+            ColorDepth::NoColor
+        } else if term.ends_with("-direct") {
+            ColorDepth::TrueColor
+        } else if term.contains("256color") {
+            ColorDepth::Ansi256
+        } else if matches!(term, "" | "linux" | "ansi" | "xterm" | "screen" | "tmux" | "cygwin")
+            || term.starts_with("vt")
+            || term.ends_with("-16color")
+        {
+            ColorDepth::Ansi16
+        } else {
+            ColorDepth::Ansi256
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ColorDepth::TrueColor => "truecolor",
+            ColorDepth::Ansi256 => "256",
+            ColorDepth::Ansi16 => "16",
+            ColorDepth::NoColor => "none",
+        }
+    }
+
+    /// `color` as close as this depth can show it. Named ANSI colors and
+    /// `Reset` pass through unchanged.
+    pub fn downsample(self, color: Color) -> Color {
+        match (self, color) {
+            (ColorDepth::TrueColor, _) => color,
+            (ColorDepth::Ansi256, Color::Rgb(r, g, b)) => Color::Indexed(nearest_256(r, g, b)),
+            (ColorDepth::Ansi16 | ColorDepth::NoColor, Color::Rgb(r, g, b)) => nearest_16(r, g, b),
+            (ColorDepth::Ansi16 | ColorDepth::NoColor, Color::Indexed(i)) => {
+                let (r, g, b) = indexed_rgb(i);
+                nearest_16(r, g, b)
+            }
+            _ => color,
+        }
+    }
+}
+
+/// The 16 ANSI colors and their usual (xterm) values.
+const ANSI_16: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0, 0, 0)),
+    (Color::Red, (205, 0, 0)),
+    (Color::Green, (0, 205, 0)),
+    (Color::Yellow, (205, 205, 0)),
+    (Color::Blue, (0, 0, 238)),
+    (Color::Magenta, (205, 0, 205)),
+    (Color::Cyan, (0, 205, 205)),
+    (Color::Gray, (229, 229, 229)),
+    (Color::DarkGray, (127, 127, 127)),
+    (Color::LightRed, (255, 0, 0)),
+    (Color::LightGreen, (0, 255, 0)),
+    (Color::LightYellow, (255, 255, 0)),
+    (Color::LightBlue, (92, 92, 255)),
+    (Color::LightMagenta, (255, 0, 255)),
+    (Color::LightCyan, (0, 255, 255)),
+    (Color::White, (255, 255, 255)),
+];
+
+/// Channel values of the 6x6x6 cube in the 256-color palette.
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+fn distance((r1, g1, b1): (u8, u8, u8), (r2, g2, b2): (u8, u8, u8)) -> u32 {
+    let d = |a: u8, b: u8| (a as i32 - b as i32).pow(2) as u32;
+    d(r1, r2) + d(g1, g2) + d(b1, b2)
+}
+
+fn nearest_16(r: u8, g: u8, b: u8) -> Color {
+    ANSI_16
+        .iter()
+        .min_by_key(|(_, rgb)| distance(*rgb, (r, g, b)))
+        .map(|(color, _)| *color)
+        .unwrap_or(Color::Reset)
+}
+
+/// The closer of the nearest cube color (16–231) and gray (232–255).
+fn nearest_256(r: u8, g: u8, b: u8) -> u8 {
+    let level = |v: u8| {
+        (0..6)
+            .min_by_key(|&i| (CUBE_LEVELS[i] as i32 - v as i32).abs())
+            .unwrap_or(0)
+    };
+    let (ri, gi, bi) = (level(r), level(g), level(b));
+    let cube = (16 + 36 * ri + 6 * gi + bi) as u8;
+
+    let average = (r as u32 + g as u32 + b as u32) / 3;
+    let gray = (average.saturating_sub(8) / 10).min(23) as u8;
+    let gray_value = 8 + 10 * gray;
+
+    let cube_rgb = (CUBE_LEVELS[ri], CUBE_LEVELS[gi], CUBE_LEVELS[bi]);
+    if distance((gray_value, gray_value, gray_value), (r, g, b)) < distance(cube_rgb, (r, g, b)) {
+        232 + gray
+    } else {
+        cube
+    }
+}
+
+/// The usual value of a 256-color palette entry.
+fn indexed_rgb(i: u8) -> (u8, u8, u8) {
+    match i {
+        0..=15 => ANSI_16[i as usize].1,
+        16..=231 => {
+            let i = (i - 16) as usize;
+            (CUBE_LEVELS[i / 36], CUBE_LEVELS[i / 6 % 6], CUBE_LEVELS[i % 6])
+        }
+        _ => {
+            let v = 8 + 10 * (i - 232);
+            (v, v, v)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn depth_comes_from_no_color_colorterm_and_term() {
+        let detect = ColorDepth::from_env;
+        assert_eq!(detect(Some("1"), Some("truecolor"), Some("xterm-256color")), ColorDepth::NoColor);
+        // An empty NO_COLOR doesn't count
+        assert_eq!(detect(Some(""), Some("truecolor"), None), ColorDepth::TrueColor);
+        assert_eq!(detect(None, Some("24bit"), Some("screen")), ColorDepth::TrueColor);
+        assert_eq!(detect(None, None, Some("tmux-256color")), ColorDepth::Ansi256);
+        assert_eq!(detect(None, None, Some("xterm-direct")), ColorDepth::TrueColor);
+        assert_eq!(detect(None, None, Some("linux")), ColorDepth::Ansi16);
+        assert_eq!(detect(None, None, Some("vt100")), ColorDepth::Ansi16);
+        assert_eq!(detect(None, None, Some("dumb")), ColorDepth::NoColor);
+        assert_eq!(detect(None, None, Some("alacritty")), ColorDepth::Ansi256);
+    }
+
+    #[test]
+    fn downsample_picks_the_nearest_palette_color() {
+        let d256 = ColorDepth::Ansi256;
+        assert_eq!(d256.downsample(Color::Rgb(255, 0, 0)), Color::Indexed(196));
+        assert_eq!(d256.downsample(Color::Rgb(0, 220, 255)), Color::Indexed(45));
+        // Near-grays land on the gray ramp
+        assert_eq!(d256.downsample(Color::Rgb(30, 30, 31)), Color::Indexed(234));
+        assert_eq!(d256.downsample(Color::Indexed(100)), Color::Indexed(100));
+
+        let d16 = ColorDepth::Ansi16;
+        assert_eq!(d16.downsample(Color::Rgb(255, 85, 85)), Color::LightRed);
+        assert_eq!(d16.downsample(Color::Rgb(0, 200, 80)), Color::Green);
+        assert_eq!(d16.downsample(Color::Rgb(120, 120, 120)), Color::DarkGray);
+        assert_eq!(d16.downsample(Color::Indexed(196)), Color::LightRed);
+        assert_eq!(d16.downsample(Color::Reset), Color::Reset);
+        assert_eq!(ColorDepth::TrueColor.downsample(Color::Rgb(1, 2, 3)), Color::Rgb(1, 2, 3));
+    }
+}
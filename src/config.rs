@@ -1,6 +1,6 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-use anyhow::Result;
+use anyhow::{bail, Context, Result};
 use serde::Deserialize;
 
 use crate::parse::Level;
@@ -13,6 +13,197 @@ pub struct Config {
     pub default_profile: Option<String>,
     #[serde(default)]
     pub profiles: std::collections::HashMap<String, ProfileConfig>,
+    #[serde(default)]
+    pub alerts: Vec<AlertConfig>,
+    #[serde(default)]
+    pub redaction: RedactionConfig,
+    #[serde(default)]
+    pub layout: LayoutConfig,
+    #[serde(default)]
+    pub keys: KeysConfig,
+    #[serde(default)]
+    pub themes: std::collections::BTreeMap<String, ThemeConfig>,
+    #[serde(default)]
+    pub workspaces: std::collections::BTreeMap<String, WorkspaceConfig>,
+}
+
+/// `[workspaces.<name>]`: sources to start together, plus the profile and
+/// theme to use with them.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WorkspaceConfig {
+    #[serde(default)]
+    pub profile: Option<String>,
+    #[serde(default)]
+    pub theme: Option<String>,
+    #[serde(default)]
+    pub sources: Vec<WorkspaceSource>,
+}
+
+/// One workspace source. Exactly one kind key is set, with the value its
+/// CLI flag takes (e.g. `{ docker = "api" }`, `{ k8s = "prod/app=api" }`),
+/// or `azure` with the app's resource group and subscription.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WorkspaceSource {
+    pub docker: Option<String>,
+    pub cmd: Option<String>,
+    pub file: Option<String>,
+    pub ssh: Option<String>,
+    pub k8s: Option<String>,
+    /// Units to follow; empty follows the whole journal.
+    pub journald: Option<Vec<String>>,
+    /// journald: minimum priority, e.g. `warning`.
+    pub priority: Option<String>,
+    /// journald: start from this time, e.g. `-1h`.
+    pub since: Option<String>,
+    pub syslog: Option<String>,
+    pub http: Option<String>,
+    pub otlp: Option<String>,
+    /// Azure Container App name.
+    pub azure: Option<String>,
+    pub resource_group: Option<String>,
+    pub subscription: Option<String>,
+    /// Azure: follow this revision instead of the newest active one.
+    pub revision: Option<String>,
+    /// Azure: also follow the app's system logs.
+    #[serde(default)]
+    pub system_logs: bool,
+}
+
+/// `[themes.<name>]`: a built-in theme to start from plus color overrides,
+/// e.g. `accent = "#ff8800"`, `text = "light_gray"` or `border = 238`.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ThemeConfig {
+    /// Built-in theme to start from; defaults to the built-in of the same
+    /// name, else matrix.
+    #[serde(default)]
+    pub inherit: Option<String>,
+    #[serde(flatten)]
+    pub colors: std::collections::BTreeMap<String, toml::Value>,
+}
+
+/// `[keys]`: a preset plus per-action overrides, e.g. `reset_patterns = "R"`
+/// or `move_down = ["down", "ctrl-n"]`.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct KeysConfig {
+    /// `default`, `vim` or `emacs`.
+    #[serde(default)]
+    pub preset: Option<String>,
+    #[serde(flatten)]
+    pub bindings: std::collections::BTreeMap<String, KeyList>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum KeyList {
+    One(String),
+    Many(Vec<String>),
+}
+
+/// `[layout]`: pane sizes and arrangement, written back by `W` in the TUI.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct LayoutConfig {
+    /// Relative sizes of the Sources, Patterns and Details panes.
+    #[serde(default = "default_sources_size")]
+    pub sources: u16,
+    #[serde(default = "default_patterns_size")]
+    pub patterns: u16,
+    #[serde(default = "default_details_size")]
+    pub details: u16,
+    #[serde(default = "default_true")]
+    pub show_sources: bool,
+    #[serde(default)]
+    pub orientation: Orientation,
+    /// With `orientation = "auto"`, stack the panes below this many columns.
+    #[serde(default = "default_stack_below")]
+    pub stack_below: u16,
+}
+
+impl Default for LayoutConfig {
+    fn default() -> Self {
+        LayoutConfig {
+            sources: default_sources_size(),
+            patterns: default_patterns_size(),
+            details: default_details_size(),
+            show_sources: true,
+            orientation: Orientation::Auto,
+            stack_below: default_stack_below(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Orientation {
+    /// Side by side, stacked when the terminal is narrower than `stack_below`.
+    #[default]
+    Auto,
+    Horizontal,
+    Vertical,
+}
+
+impl Orientation {
+    pub fn name(self) -> &'static str {
+        match self {
+            Orientation::Auto => "auto",
+            Orientation::Horizontal => "horizontal",
+            Orientation::Vertical => "vertical",
+        }
+    }
+}
+
+fn default_sources_size() -> u16 {
+    18
+}
+
+fn default_patterns_size() -> u16 {
+    52
+}
+
+fn default_details_size() -> u16 {
+    30
+}
+
+fn default_stack_below() -> u16 {
+    100
+}
+
+/// `[redaction]`: secret / PII masking, on by default.
+#[derive(Debug, Clone, Deserialize)]
+pub struct RedactionConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Built-in detectors to turn off (e.g. `["email"]`).
+    #[serde(default)]
+    pub disable: Vec<String>,
+    /// Extra `[[redaction.rules]]`, run after the built-ins.
+    #[serde(default)]
+    pub rules: Vec<RedactionRule>,
+}
+
+impl Default for RedactionConfig {
+    fn default() -> Self {
+        RedactionConfig {
+            enabled: true,
+            disable: Vec::new(),
+            rules: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RedactionRule {
+    #[serde(default)]
+    pub name: Option<String>,
+    pub pattern: String,
+    /// May refer to capture groups, e.g. `"${1}<REDACTED>"`.
+    #[serde(default = "default_replacement")]
+    pub replacement: String,
+}
+
+fn default_replacement() -> String {
+    "<REDACTED>".into()
 }
 
 #[derive(Debug, Deserialize)]
@@ -25,6 +216,50 @@ pub struct ProfileConfig {
     pub highlights: Vec<String>,
 }
 
+/// One `[[alerts]]` rule. Every condition that is set must hold for a
+/// pattern that received lines since the last check.
+#[derive(Debug, Clone, Deserialize)]
+pub struct AlertConfig {
+    pub name: String,
+    /// Regex over the pattern text or its latest sample.
+    #[serde(default)]
+    pub pattern: Option<String>,
+    /// Minimum level.
+    #[serde(default)]
+    pub level: Option<String>,
+    /// Source id, `*` matches anything (e.g. `docker/*`).
+    #[serde(default)]
+    pub source: Option<String>,
+    /// Fire while the pattern's 1-minute rate is at least this.
+    #[serde(default)]
+    pub rate_above: Option<f64>,
+    #[serde(default)]
+    pub spike: bool,
+    /// Only fire for patterns seen for the first time.
+    #[serde(default)]
+    pub new_pattern: bool,
+    #[serde(default = "default_cooldown_secs")]
+    pub cooldown_secs: u64,
+    #[serde(default)]
+    pub bell: bool,
+    #[serde(default = "default_true")]
+    pub banner: bool,
+    /// Shell command; receives the pattern as JSON on stdin.
+    #[serde(default)]
+    pub command: Option<String>,
+    /// URL the pattern JSON is POSTed to.
+    #[serde(default)]
+    pub webhook: Option<String>,
+}
+
+fn default_cooldown_secs() -> u64 {
+    60
+}
+
+fn default_true() -> bool {
+    true
+}
+
 fn default_min_level() -> String {
     "INFO".into()
 }
@@ -34,40 +269,64 @@ fn default_theme_name() -> String {
 }
 
 impl Config {
+    /// Every layer merged: see [`Config::layer_files`] and [`LayeredConfig`].
     pub fn load(explicit_path: Option<&str>) -> Result<Config> {
+        LayeredConfig::load(&Self::layer_files(explicit_path), std::env::vars())?.config()
+    }
+
+    /// The config files `load` merges, lowest precedence first: system,
+    /// user, project (./logradar.toml), then `--config`. Only the explicit
+    /// file has to exist.
+    pub fn layer_files(explicit_path: Option<&str>) -> Vec<LayerFile> {
+        let mut files = Vec::new();
+        if cfg!(unix) {
+            files.push(LayerFile::new(ConfigLayer::System, "/etc/logradar/config.toml"));
+        }
+        if let Some(path) = Self::global_path() {
+            files.push(LayerFile::new(ConfigLayer::User, path));
+        }
+        files.push(LayerFile::new(ConfigLayer::Project, "logradar.toml"));
         if let Some(path) = explicit_path {
-            let content = std::fs::read_to_string(path)?;
-            let config: Config = toml::from_str(&content)?;
-            return Ok(config);
+            files.push(LayerFile::new(ConfigLayer::Explicit, path));
         }
+        files
+    }
 
-        // Try ./logradar.toml
+    /// The file settings are saved to: the explicit path, else
+    /// ./logradar.toml, else ~/.config/logradar/config.toml, whichever
+    /// exists first.
+    pub fn locate(explicit_path: Option<&str>) -> Option<PathBuf> {
+        if let Some(path) = explicit_path {
+            return Some(PathBuf::from(path));
+        }
         let local = PathBuf::from("logradar.toml");
         if local.exists() {
-            let content = std::fs::read_to_string(&local)?;
-            let config: Config = toml::from_str(&content)?;
-            return Ok(config);
-        }
-
-        // Try ~/.config/logradar/config.toml
-        if let Some(config_dir) = dirs::config_dir() {
-            let global = config_dir.join("logradar").join("config.toml");
-            if global.exists() {
-                let content = std::fs::read_to_string(&global)?;
-                let config: Config = toml::from_str(&content)?;
-                return Ok(config);
-            }
+            return Some(local);
         }
+        Self::global_path().filter(|p| p.exists())
+    }
+
+    /// ~/.config/logradar/config.toml, where settings are saved when no
+    /// config file exists yet.
+    pub fn global_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("logradar").join("config.toml"))
+    }
 
-        Ok(Config::default())
+    /// The `[themes.*]` tables, checked for unknown keys and bad colors.
+    pub fn custom_themes(&self) -> Result<Vec<Theme>> {
+        self.themes
+            .iter()
+            .map(|(name, cfg)| Theme::from_config(name, cfg))
+            .collect()
     }
 
-    pub fn into_profiles(self) -> Vec<Profile> {
+    /// Profiles with their themes looked up among the built-ins and `themes`.
+    pub fn into_profiles(self, themes: &[Theme]) -> Vec<Profile> {
         let mut profiles = Profile::all_profiles();
 
         for (name, pc) in self.profiles {
             let level = parse_level(&pc.min_level);
-            let theme = Theme::by_name(&pc.theme).unwrap_or_else(Theme::matrix);
+            let theme = Theme::by_name(&pc.theme, themes).unwrap_or_else(Theme::matrix);
             // Check if this overrides a built-in profile
             if let Some(existing) = profiles.iter_mut().find(|p| p.name == name) {
                 existing.min_level = level;
@@ -87,6 +346,325 @@ impl Config {
     }
 }
 
+/// Write `layout` into the `[layout]` table of the config file at `path`,
+/// creating the file if needed. Comments and other settings are kept.
+pub fn save_layout(path: &Path, layout: &LayoutConfig) -> Result<()> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e).with_context(|| format!("reading {}", path.display())),
+    };
+    let mut doc: toml_edit::DocumentMut = content
+        .parse()
+        .with_context(|| format!("parsing {}", path.display()))?;
+    if !doc.contains_table("layout") {
+        doc["layout"] = toml_edit::table();
+    }
+    let table = &mut doc["layout"];
+    table["sources"] = toml_edit::value(layout.sources as i64);
+    table["patterns"] = toml_edit::value(layout.patterns as i64);
+    table["details"] = toml_edit::value(layout.details as i64);
+    table["show_sources"] = toml_edit::value(layout.show_sources);
+    table["orientation"] = toml_edit::value(layout.orientation.name());
+    table["stack_below"] = toml_edit::value(layout.stack_below as i64);
+
+    if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(path, doc.to_string()).with_context(|| format!("writing {}", path.display()))?;
+    Ok(())
+}
+
+/// Write `workspace` as `[workspaces.<name>]` in the config file at `path`,
+/// replacing a workspace of that name. Comments and other settings are kept.
+pub fn save_workspace(path: &Path, name: &str, workspace: &WorkspaceConfig) -> Result<()> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e).with_context(|| format!("reading {}", path.display())),
+    };
+    let mut doc: toml_edit::DocumentMut = content
+        .parse()
+        .with_context(|| format!("parsing {}", path.display()))?;
+    // An inline `workspaces = { ... }` becomes a table, keeping its entries
+    if let Some(item) = doc.get_mut("workspaces").filter(|item| item.is_inline_table()) {
+        *item = toml_edit::Item::Table(std::mem::take(item).into_table().unwrap_or_default());
+    }
+    if !doc.contains_table("workspaces") {
+        let mut workspaces = toml_edit::Table::new();
+        workspaces.set_implicit(true);
+        doc["workspaces"] = toml_edit::Item::Table(workspaces);
+    }
+
+    let mut table = toml_edit::Table::new();
+    if let Some(ref profile) = workspace.profile {
+        table["profile"] = toml_edit::value(profile.as_str());
+    }
+    if let Some(ref theme) = workspace.theme {
+        table["theme"] = toml_edit::value(theme.as_str());
+    }
+    let mut sources = toml_edit::Array::new();
+    for source in &workspace.sources {
+        let mut entry = toml_edit::InlineTable::new();
+        let strings = [
+            ("docker", &source.docker),
+            ("cmd", &source.cmd),
+            ("file", &source.file),
+            ("ssh", &source.ssh),
+            ("k8s", &source.k8s),
+            ("syslog", &source.syslog),
+            ("http", &source.http),
+            ("otlp", &source.otlp),
+            ("azure", &source.azure),
+            ("resource_group", &source.resource_group),
+            ("subscription", &source.subscription),
+            ("revision", &source.revision),
+        ];
+        for (key, value) in strings {
+            if let Some(value) = value {
+                entry.insert(key, value.as_str().into());
+            }
+        }
+        if let Some(ref units) = source.journald {
+            entry.insert("journald", units.iter().map(String::as_str).collect::<toml_edit::Array>().into());
+        }
+        for (key, value) in [("priority", &source.priority), ("since", &source.since)] {
+            if let Some(value) = value {
+                entry.insert(key, value.as_str().into());
+            }
+        }
+        if source.system_logs {
+            entry.insert("system_logs", true.into());
+        }
+        // One source per line
+        let mut value = toml_edit::Value::InlineTable(entry);
+        value.decor_mut().set_prefix("\n    ");
+        sources.push_formatted(value);
+    }
+    sources.set_trailing("\n");
+    sources.set_trailing_comma(true);
+    table["sources"] = toml_edit::value(sources);
+    doc["workspaces"][name] = toml_edit::Item::Table(table);
+
+    if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(path, doc.to_string()).with_context(|| format!("writing {}", path.display()))?;
+    Ok(())
+}
+
+/// Where a config value comes from, lowest precedence first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigLayer {
+    /// /etc/logradar/config.toml
+    System,
+    /// ~/.config/logradar/config.toml
+    User,
+    /// ./logradar.toml
+    Project,
+    /// `--config`
+    Explicit,
+    /// `LOGRADAR_*` variables
+    Env,
+}
+
+impl ConfigLayer {
+    pub fn name(self) -> &'static str {
+        match self {
+            ConfigLayer::System => "system",
+            ConfigLayer::User => "user",
+            ConfigLayer::Project => "project",
+            ConfigLayer::Explicit => "--config",
+            ConfigLayer::Env => "env",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LayerFile {
+    pub layer: ConfigLayer,
+    pub path: PathBuf,
+}
+
+impl LayerFile {
+    pub fn new(layer: ConfigLayer, path: impl Into<PathBuf>) -> LayerFile {
+        LayerFile {
+            layer,
+            path: path.into(),
+        }
+    }
+
+    /// Parse the file on its own. A missing file is `None`, except for
+    /// `--config`, which has to exist.
+    pub fn read(&self) -> Result<Option<toml::Table>> {
+        let content = match std::fs::read_to_string(&self.path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound && self.layer != ConfigLayer::Explicit => {
+                return Ok(None);
+            }
+            Err(e) => return Err(e).with_context(|| format!("reading {}", self.path.display())),
+        };
+        let parse = || format!("parsing {}", self.path.display());
+        // Checked as a whole config first so errors point into this file
+        toml::from_str::<Config>(&content).with_context(parse)?;
+        Ok(Some(toml::from_str(&content).with_context(parse)?))
+    }
+}
+
+/// Top-level `Config` keys; other `LOGRADAR_*` variables (`LOGRADAR_SSH`,
+/// ...) are not config.
+const CONFIG_KEYS: &[&str] = &[
+    "default_profile",
+    "profiles",
+    "alerts",
+    "redaction",
+    "layout",
+    "keys",
+    "themes",
+    "workspaces",
+];
+
+/// All config layers merged into one table, remembering which layer set
+/// each value. Tables merge key by key; anything else, arrays included, is
+/// replaced by the higher layer.
+#[derive(Debug, Default)]
+pub struct LayeredConfig {
+    pub table: toml::Table,
+    /// The files that were found and read.
+    pub files: Vec<LayerFile>,
+    origins: std::collections::BTreeMap<Vec<String>, String>,
+}
+
+impl LayeredConfig {
+    /// Merge `files` (lowest precedence first), then the `LOGRADAR_*`
+    /// variables in `env`.
+    pub fn load(files: &[LayerFile], env: impl IntoIterator<Item = (String, String)>) -> Result<LayeredConfig> {
+        let mut layered = LayeredConfig::default();
+        for file in files {
+            if let Some(table) = file.read()? {
+                let origin = format!("{} ({})", file.layer.name(), file.path.display());
+                layered.merge(&mut Vec::new(), table, &origin);
+                layered.files.push(file.clone());
+            }
+        }
+        let mut vars: Vec<(String, String)> = env
+            .into_iter()
+            .filter(|(name, _)| name.starts_with("LOGRADAR_"))
+            .collect();
+        vars.sort();
+        for (name, raw) in vars {
+            let Some(path) = env_key(&name)? else {
+                continue;
+            };
+            // TOML values (numbers, booleans, arrays) as such, anything else as a string
+            let value = toml::from_str::<toml::Table>(&format!("v = {}", raw))
+                .ok()
+                .and_then(|mut t| t.remove("v"))
+                .unwrap_or(toml::Value::String(raw));
+            let (last, parents) = path.split_last().expect("env_key returns a key");
+            let mut table = toml::Table::new();
+            table.insert(last.clone(), value);
+            for key in parents.iter().rev() {
+                let mut parent = toml::Table::new();
+                parent.insert(key.clone(), toml::Value::Table(table));
+                table = parent;
+            }
+            layered.merge(&mut Vec::new(), table, &format!("env ({})", name));
+        }
+        Ok(layered)
+    }
+
+    fn merge(&mut self, path: &mut Vec<String>, table: toml::Table, origin: &str) {
+        for (key, value) in table {
+            path.push(key);
+            let mut target = &mut self.table;
+            for key in &path[..path.len() - 1] {
+                target = match target.get_mut(key) {
+                    Some(toml::Value::Table(t)) => t,
+                    _ => unreachable!("parents are merged as tables first"),
+                };
+            }
+            let key = path.last().cloned().unwrap_or_default();
+            match value {
+                toml::Value::Table(inner) => {
+                    if !matches!(target.get(&key), Some(toml::Value::Table(_))) {
+                        target.insert(key, toml::Value::Table(toml::Table::new()));
+                        self.origins.remove(path);
+                    }
+                    self.merge(path, inner, origin);
+                }
+                value => {
+                    target.insert(key, value);
+                    self.origins.retain(|p, _| !p.starts_with(path));
+                    self.origins.insert(path.clone(), origin.to_string());
+                }
+            }
+            path.pop();
+        }
+    }
+
+    /// The layer that set the value at `path`, e.g. `["layout", "details"]`.
+    pub fn origin(&self, path: &[&str]) -> Option<&str> {
+        let path: Vec<String> = path.iter().map(|k| k.to_string()).collect();
+        self.origins.get(&path).map(String::as_str)
+    }
+
+    pub fn config(&self) -> Result<Config> {
+        toml::Value::Table(self.table.clone())
+            .try_into()
+            .context("merging config layers")
+    }
+
+    /// The merged config as TOML, each value commented with its origin.
+    pub fn show(&self) -> String {
+        let Ok(mut doc) = toml::to_string(&self.table)
+            .unwrap_or_default()
+            .parse::<toml_edit::DocumentMut>()
+        else {
+            return String::new();
+        };
+        self.annotate(&mut Vec::new(), doc.as_table_mut());
+        doc.to_string()
+    }
+
+    fn annotate(&self, path: &mut Vec<String>, table: &mut toml_edit::Table) {
+        for (key, item) in table.iter_mut() {
+            path.push(key.get().to_string());
+            let origin = self.origins.get(path.as_slice());
+            match item {
+                toml_edit::Item::Value(value) => {
+                    if let Some(origin) = origin {
+                        value.decor_mut().set_suffix(format!("  # {}", origin));
+                    }
+                }
+                toml_edit::Item::Table(inner) => self.annotate(path, inner),
+                toml_edit::Item::ArrayOfTables(array) => {
+                    if let (Some(origin), Some(first)) = (origin, array.iter_mut().next()) {
+                        first.decor_mut().set_prefix(format!("\n# {}\n", origin));
+                    }
+                }
+                toml_edit::Item::None => {}
+            }
+            path.pop();
+        }
+    }
+}
+
+/// The config key a `LOGRADAR_*` variable sets: the rest of the name in
+/// lower case, with `__` between nested keys (`LOGRADAR_LAYOUT__DETAILS`
+/// is `layout.details`). `None` when it names no config key.
+fn env_key(name: &str) -> Result<Option<Vec<String>>> {
+    let rest = name.trim_start_matches("LOGRADAR_").to_lowercase();
+    let path: Vec<String> = rest.split("__").map(String::from).collect();
+    if !CONFIG_KEYS.contains(&path[0].as_str()) {
+        return Ok(None);
+    }
+    if path.iter().any(String::is_empty) {
+        bail!("{}: empty key between '__'", name);
+    }
+    Ok(Some(path))
+}
+
 fn parse_level(s: &str) -> Level {
     match s.to_ascii_uppercase().as_str() {
         "TRACE" => Level::Trace,
@@ -139,7 +717,7 @@ theme = "color"
 highlights = ["custom"]
 "#;
         let cfg: Config = toml::from_str(toml_str).unwrap();
-        let profiles = cfg.into_profiles();
+        let profiles = cfg.into_profiles(&[]);
         // 3 built-in + 1 custom
         assert_eq!(profiles.len(), 4);
         let custom = profiles.iter().find(|p| p.name == "myapp").unwrap();
@@ -156,7 +734,7 @@ theme = "mono"
 highlights = ["critical"]
 "#;
         let cfg: Config = toml::from_str(toml_str).unwrap();
-        let profiles = cfg.into_profiles();
+        let profiles = cfg.into_profiles(&[]);
         // Should still be 3 (override, not add)
         assert_eq!(profiles.len(), 3);
         let default = profiles.iter().find(|p| p.name == "default").unwrap();
@@ -175,9 +753,159 @@ highlights = ["critical"]
         assert_eq!(parse_level("garbage"), Level::Info); // fallback
     }
 
+    #[test]
+    fn parse_alerts() {
+        let toml_str = r#"
+[[alerts]]
+name = "panics"
+pattern = "panic|segfault"
+level = "error"
+webhook = "http://hooks.local/alert"
+
+[[alerts]]
+name = "new-errors"
+new_pattern = true
+source = "docker/*"
+bell = true
+banner = false
+cooldown_secs = 5
+"#;
+        let cfg: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(cfg.alerts.len(), 2);
+        assert_eq!(cfg.alerts[0].pattern.as_deref(), Some("panic|segfault"));
+        assert!(cfg.alerts[0].banner);
+        assert_eq!(cfg.alerts[0].cooldown_secs, 60);
+        assert!(cfg.alerts[1].new_pattern && cfg.alerts[1].bell && !cfg.alerts[1].banner);
+        assert_eq!(cfg.alerts[1].cooldown_secs, 5);
+    }
+
+    #[test]
+    fn parse_redaction() {
+        let cfg: Config = toml::from_str("").unwrap();
+        assert!(cfg.redaction.enabled);
+
+        let toml_str = r#"
+[redaction]
+disable = ["email"]
+
+[[redaction.rules]]
+name = "session"
+pattern = "sess_[a-z0-9]+"
+"#;
+        let cfg: Config = toml::from_str(toml_str).unwrap();
+        assert!(cfg.redaction.enabled);
+        assert_eq!(cfg.redaction.disable, vec!["email"]);
+        assert_eq!(cfg.redaction.rules[0].replacement, "<REDACTED>");
+    }
+
+    #[test]
+    fn save_layout_keeps_the_rest_of_the_file() {
+        let path = std::env::temp_dir().join(format!("logradar-layout-{}.toml", std::process::id()));
+        std::fs::write(&path, "# my settings\ndefault_profile = \"ops\"\n").unwrap();
+        let layout = LayoutConfig {
+            sources: 10,
+            patterns: 45,
+            details: 45,
+            show_sources: false,
+            orientation: Orientation::Vertical,
+            stack_below: 80,
+        };
+        save_layout(&path, &layout).unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert!(content.starts_with("# my settings\n"));
+        let cfg: Config = toml::from_str(&content).unwrap();
+        assert_eq!(cfg.default_profile.as_deref(), Some("ops"));
+        assert_eq!(cfg.layout, layout);
+
+        let cfg: Config = toml::from_str("[layout]\ndetails = 40").unwrap();
+        assert_eq!(cfg.layout.details, 40);
+        assert_eq!(cfg.layout.orientation, Orientation::Auto);
+    }
+
+    #[test]
+    fn save_workspace_keeps_inline_workspaces() {
+        let path = std::env::temp_dir().join(format!("logradar-workspaces-{}.toml", std::process::id()));
+        std::fs::write(&path, "workspaces = { api = { profile = \"ops\", sources = [{ docker = \"api\" }] } }\n")
+            .unwrap();
+        let web = WorkspaceConfig {
+            sources: vec![WorkspaceSource {
+                docker: Some("web".into()),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        save_workspace(&path, "web", &web).unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        let cfg: Config = toml::from_str(&content).unwrap();
+        assert_eq!(cfg.workspaces["api"].profile.as_deref(), Some("ops"));
+        assert_eq!(cfg.workspaces["api"].sources[0].docker.as_deref(), Some("api"));
+        assert_eq!(cfg.workspaces["web"], web);
+    }
+
+    #[test]
+    fn layers_merge_with_origins() {
+        let dir = std::env::temp_dir().join(format!("logradar-layers-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let user = dir.join("user.toml");
+        let project = dir.join("project.toml");
+        std::fs::write(
+            &user,
+            "default_profile = \"mine\"\n[profiles.mine]\nmin_level = \"DEBUG\"\nhighlights = [\"oom\"]\n[[alerts]]\nname = \"errors\"\nlevel = \"error\"\n",
+        )
+        .unwrap();
+        std::fs::write(&project, "[profiles.mine]\nhighlights = [\"timeout\"]\n[layout]\ndetails = 40\n").unwrap();
+        let files = [
+            LayerFile::new(ConfigLayer::System, dir.join("missing.toml")),
+            LayerFile::new(ConfigLayer::User, &user),
+            LayerFile::new(ConfigLayer::Project, &project),
+        ];
+        let env = [
+            ("LOGRADAR_LAYOUT__SOURCES", "30"),
+            ("LOGRADAR_DEFAULT_PROFILE", "ops"),
+            ("LOGRADAR_SSH", "my-ssh"),
+            ("HOME", "/root"),
+        ]
+        .map(|(k, v)| (k.to_string(), v.to_string()));
+        let layered = LayeredConfig::load(&files, env).unwrap();
+        std::fs::remove_dir_all(&dir).ok();
+
+        assert_eq!(layered.files.len(), 2);
+        let cfg = layered.config().unwrap();
+        assert_eq!(cfg.default_profile.as_deref(), Some("ops"));
+        // Tables merge, arrays are replaced
+        let mine = &cfg.profiles["mine"];
+        assert_eq!(mine.min_level, "DEBUG");
+        assert_eq!(mine.highlights, vec!["timeout"]);
+        assert_eq!(cfg.alerts.len(), 1);
+        assert_eq!((cfg.layout.details, cfg.layout.sources), (40, 30));
+
+        assert!(layered.origin(&["profiles", "mine", "min_level"]).unwrap().starts_with("user ("));
+        assert!(layered.origin(&["profiles", "mine", "highlights"]).unwrap().starts_with("project ("));
+        assert_eq!(layered.origin(&["default_profile"]), Some("env (LOGRADAR_DEFAULT_PROFILE)"));
+        assert!(!layered.table.contains_key("ssh"));
+        let shown = layered.show();
+        assert!(shown.contains("sources = 30  # env (LOGRADAR_LAYOUT__SOURCES)"), "{}", shown);
+        assert!(shown.contains("[[alerts]]"));
+
+        // An explicit --config has to exist
+        let explicit = LayerFile::new(ConfigLayer::Explicit, dir.join("nope.toml"));
+        assert!(LayeredConfig::load(&[explicit], Vec::new()).is_err());
+    }
+
     #[test]
     fn load_returns_default_when_no_file() {
-        let cfg = Config::load(None).unwrap();
+        let dir = std::env::temp_dir().join(format!("logradar-no-config-{}", std::process::id()));
+        let files = [
+            LayerFile::new(ConfigLayer::User, dir.join("config.toml")),
+            LayerFile::new(ConfigLayer::Project, dir.join("logradar.toml")),
+        ];
+        let layered = LayeredConfig::load(&files, Vec::new()).unwrap();
+        assert!(layered.files.is_empty());
+        let cfg = layered.config().unwrap();
         assert!(cfg.default_profile.is_none());
         assert!(cfg.profiles.is_empty());
     }
@@ -1,5 +1,15 @@
+use std::sync::Arc;
+
 use tokio::process::Command;
 use tokio::sync::mpsc;
+use tokio::task::JoinSet;
+
+use crate::ingest::azure::{
+    az_bin, fetch_management_token, list_revisions, parse_revisions, AccessToken, AzureClient,
+    AzureRevision, ContainerAppRef,
+};
+use crate::ingest::journald::journalctl_bin;
+use crate::ingest::kubernetes::{kubectl_bin, KubeSelector};
 
 #[allow(dead_code)]
 #[derive(Debug, Clone)]
@@ -16,14 +26,71 @@ pub struct AzureContainerApp {
     pub name: String,
     pub resource_group: String,
     pub subscription_id: String,
+    pub subscription_name: String,
     pub provisioning_state: String,
 }
 
+impl AzureContainerApp {
+    /// The app's subscription, resource group and name; names alone repeat
+    /// across resource groups and subscriptions.
+    pub fn app_ref(&self) -> ContainerAppRef {
+        ContainerAppRef {
+            subscription_id: self.subscription_id.clone(),
+            resource_group: self.resource_group.clone(),
+            name: self.name.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum KubeTargetKind {
+    Namespace,
+    Deployment,
+    Pod,
+}
+
+#[derive(Debug, Clone)]
+pub struct KubeTarget {
+    pub kind: KubeTargetKind,
+    pub namespace: String,
+    pub name: String,
+    /// Label selector for deployments (from `spec.selector.matchLabels`).
+    pub selector: Option<String>,
+    /// Pod phase, or ready/desired replicas for deployments.
+    pub status: String,
+}
+
+impl KubeTarget {
+    pub fn to_selector(&self) -> KubeSelector {
+        match self.kind {
+            KubeTargetKind::Namespace => KubeSelector {
+                namespace: self.namespace.clone(),
+                labels: None,
+                pod: None,
+            },
+            KubeTargetKind::Deployment => KubeSelector {
+                namespace: self.namespace.clone(),
+                labels: self.selector.clone(),
+                pod: None,
+            },
+            KubeTargetKind::Pod => KubeSelector {
+                namespace: self.namespace.clone(),
+                labels: None,
+                pod: Some(self.name.clone()),
+            },
+        }
+    }
+}
+
 #[derive(Debug)]
 pub enum DiscoveryResult {
     Docker(Result<Vec<DockerContainer>, String>),
     Azure(Result<Vec<AzureContainerApp>, String>),
-    AzureToken(Result<String, String>),
+    AzureToken(Result<AccessToken, String>),
+    /// Revisions of one app, for the revision picker.
+    AzureRevisions(ContainerAppRef, Result<Vec<AzureRevision>, String>),
+    Kubernetes(Result<Vec<KubeTarget>, String>),
+    Journald(Result<Vec<String>, String>),
 }
 
 pub fn discover_docker(tx: mpsc::Sender<DiscoveryResult>) {
@@ -68,20 +135,143 @@ async fn run_docker_discovery() -> Result<Vec<DockerContainer>, String> {
 }
 
 pub fn discover_azure(tx: mpsc::Sender<DiscoveryResult>) {
-    let tx2 = tx.clone();
-    // Fetch app list and access token in parallel
+    // The token is needed for the REST listing, so it is fetched first and
+    // shared with log streaming afterwards.
     tokio::spawn(async move {
-        let result = run_azure_discovery().await;
+        let token = fetch_azure_token().await;
+        let _ = tx.send(DiscoveryResult::AzureToken(token.clone())).await;
+        let result = run_azure_discovery(token.ok()).await;
         let _ = tx.send(DiscoveryResult::Azure(result)).await;
     });
-    tokio::spawn(async move {
-        let result = fetch_azure_token().await;
-        let _ = tx2.send(DiscoveryResult::AzureToken(result)).await;
+}
+
+/// List apps in every enabled subscription over REST, falling back to
+/// `az containerapp list` (default subscription only) without a token.
+async fn run_azure_discovery(token: Option<AccessToken>) -> Result<Vec<AzureContainerApp>, String> {
+    if let Some(token) = token {
+        let client = AzureClient::new(Some(token));
+        if let Ok(apps) = list_apps_all_subscriptions(client).await {
+            return Ok(apps);
+        }
+    }
+    run_azure_cli_discovery().await
+}
+
+async fn list_apps_all_subscriptions(client: AzureClient) -> anyhow::Result<Vec<AzureContainerApp>> {
+    let subs_json = client
+        .get_json("/subscriptions?api-version=2022-12-01")
+        .await?;
+    let subscriptions = parse_subscriptions(&subs_json);
+
+    // Query subscriptions concurrently; one that fails (e.g. no access to
+    // Microsoft.App) just contributes no apps.
+    let client = Arc::new(client);
+    let mut tasks = JoinSet::new();
+    for (id, name) in subscriptions {
+        let client = client.clone();
+        tasks.spawn(async move {
+            let mut apps = Vec::new();
+            let mut next = Some(format!(
+                "/subscriptions/{}/providers/Microsoft.App/containerApps",
+                id
+            ));
+            while let Some(path) = next.take() {
+                let Ok(page) = client.get_json(&path).await else {
+                    break;
+                };
+                apps.extend(parse_container_apps(&page, &name));
+                next = page
+                    .get("nextLink")
+                    .and_then(|v| v.as_str())
+                    .map(str::to_string);
+            }
+            apps
+        });
+    }
+    let mut apps = Vec::new();
+    while let Some(result) = tasks.join_next().await {
+        apps.extend(result.unwrap_or_default());
+    }
+    apps.sort_by(|a, b| {
+        (&a.subscription_name, &a.resource_group, &a.name)
+            .cmp(&(&b.subscription_name, &b.resource_group, &b.name))
     });
+    Ok(apps)
+}
+
+/// Enabled subscriptions as `(id, display name)`.
+fn parse_subscriptions(json: &serde_json::Value) -> Vec<(String, String)> {
+    json.get("value")
+        .and_then(|v| v.as_array())
+        .map(|items| {
+            items
+                .iter()
+                .filter(|s| s.get("state").and_then(|v| v.as_str()).unwrap_or("Enabled") == "Enabled")
+                .filter_map(|s| {
+                    let id = s.get("subscriptionId")?.as_str()?.to_string();
+                    let name = s
+                        .get("displayName")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or(&id)
+                        .to_string();
+                    Some((id, name))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Parse container apps from a REST page (`{"value": [...]}`) or
+/// `az containerapp list` output (a bare array).
+fn parse_container_apps(json: &serde_json::Value, subscription_name: &str) -> Vec<AzureContainerApp> {
+    let items = json
+        .get("value")
+        .and_then(|v| v.as_array())
+        .or_else(|| json.as_array());
+    items
+        .map(|items| items.iter().filter_map(|item| parse_container_app(item, subscription_name)).collect())
+        .unwrap_or_default()
+}
+
+fn parse_container_app(item: &serde_json::Value, subscription_name: &str) -> Option<AzureContainerApp> {
+    let name = item.get("name")?.as_str()?.to_string();
+    // Resource id: /subscriptions/{sub}/resourceGroups/{rg}/providers/...
+    let id_parts: Vec<&str> = item
+        .get("id")
+        .and_then(|v| v.as_str())
+        .unwrap_or("")
+        .split('/')
+        .collect();
+    let sub = id_parts.get(2).map(|s| s.to_string()).unwrap_or_default();
+    // The CLI adds resourceGroup; REST responses only have it in the id
+    let rg = item
+        .get("resourceGroup")
+        .and_then(|v| v.as_str())
+        .or_else(|| id_parts.get(4).copied())
+        .unwrap_or("")
+        .to_string();
+    let state = item
+        .get("properties")
+        .and_then(|p| p.get("provisioningState"))
+        .and_then(|v| v.as_str())
+        .unwrap_or("Unknown")
+        .to_string();
+    let subscription_name = if subscription_name.is_empty() {
+        sub.clone()
+    } else {
+        subscription_name.to_string()
+    };
+    Some(AzureContainerApp {
+        name,
+        resource_group: rg,
+        subscription_id: sub,
+        subscription_name,
+        provisioning_state: state,
+    })
 }
 
-async fn run_azure_discovery() -> Result<Vec<AzureContainerApp>, String> {
-    let output = Command::new("az")
+async fn run_azure_cli_discovery() -> Result<Vec<AzureContainerApp>, String> {
+    let output = Command::new(az_bin())
         .args(["containerapp", "list", "-o", "json"])
         .output()
         .await
@@ -96,73 +286,325 @@ async fn run_azure_discovery() -> Result<Vec<AzureContainerApp>, String> {
     let json: serde_json::Value =
         serde_json::from_str(&stdout).map_err(|e| format!("Failed to parse JSON: {}", e))?;
 
-    let apps = json
-        .as_array()
-        .unwrap_or(&vec![])
-        .iter()
-        .filter_map(|item| {
-            let name = item.get("name")?.as_str()?.to_string();
-            let rg = item
-                .get("resourceGroup")
-                .and_then(|v| v.as_str())
-                .unwrap_or("")
-                .to_string();
-            // Parse subscription ID from resource id:
-            // /subscriptions/{sub}/resourceGroups/{rg}/providers/...
-            let sub = item
-                .get("id")
-                .and_then(|v| v.as_str())
-                .and_then(|id| {
-                    let parts: Vec<&str> = id.split('/').collect();
-                    // /subscriptions/{sub}/...  → parts[2]
-                    parts.get(2).map(|s| s.to_string())
-                })
-                .unwrap_or_default();
-            let state = item
-                .get("properties")
-                .and_then(|p| p.get("provisioningState"))
-                .and_then(|v| v.as_str())
-                .unwrap_or("Unknown")
-                .to_string();
-            Some(AzureContainerApp {
-                name,
-                resource_group: rg,
-                subscription_id: sub,
-                provisioning_state: state,
-            })
-        })
-        .collect();
+    Ok(parse_container_apps(&json, ""))
+}
 
-    Ok(apps)
+pub fn discover_azure_revisions(
+    app: AzureContainerApp,
+    token: Option<AccessToken>,
+    tx: mpsc::Sender<DiscoveryResult>,
+) {
+    tokio::spawn(async move {
+        let result = run_azure_revision_discovery(&app, token).await;
+        let _ = tx.send(DiscoveryResult::AzureRevisions(app.app_ref(), result)).await;
+    });
 }
 
-/// Fetch Azure management access token (runs `az account get-access-token`).
-/// This is called in parallel with discovery so the token is ready when
-/// log streaming starts — avoids a second az CLI startup.
-async fn fetch_azure_token() -> Result<String, String> {
-    let output = Command::new("az")
+async fn run_azure_revision_discovery(
+    app: &AzureContainerApp,
+    token: Option<AccessToken>,
+) -> Result<Vec<AzureRevision>, String> {
+    // REST first (no Python startup), az CLI as the fallback
+    if !app.subscription_id.is_empty() {
+        let client = AzureClient::new(token);
+        if let Ok(revisions) = list_revisions(&client, &app.app_ref()).await {
+            return Ok(revisions);
+        }
+    }
+
+    let output = Command::new(az_bin())
         .args([
-            "account",
-            "get-access-token",
-            "--resource",
-            "https://management.azure.com/",
+            "containerapp",
+            "revision",
+            "list",
+            "-n",
+            &app.name,
+            "-g",
+            &app.resource_group,
             "-o",
             "json",
         ])
         .output()
         .await
-        .map_err(|e| format!("az token: {}", e))?;
+        .map_err(|e| format!("az CLI not found: {}", e))?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("az token failed: {}", stderr.trim()));
+        return Err(format!("az containerapp revision list failed: {}", stderr.trim()));
     }
 
     let json: serde_json::Value = serde_json::from_slice(&output.stdout)
-        .map_err(|e| format!("token parse: {}", e))?;
+        .map_err(|e| format!("Failed to parse JSON: {}", e))?;
+    Ok(parse_revisions(&json))
+}
 
-    json.get("accessToken")
-        .and_then(|v| v.as_str())
-        .map(|s| s.to_string())
-        .ok_or_else(|| "no accessToken in response".to_string())
+/// Fetch Azure management access token (runs `az account get-access-token`).
+/// This is called in parallel with discovery so the token is ready when
+/// log streaming starts — avoids a second az CLI startup.
+async fn fetch_azure_token() -> Result<AccessToken, String> {
+    fetch_management_token(&az_bin())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+pub fn discover_kubernetes(tx: mpsc::Sender<DiscoveryResult>) {
+    tokio::spawn(async move {
+        let result = run_kubernetes_discovery(&kubectl_bin()).await;
+        let _ = tx.send(DiscoveryResult::Kubernetes(result)).await;
+    });
+}
+
+async fn run_kubernetes_discovery(kubectl: &str) -> Result<Vec<KubeTarget>, String> {
+    let output = Command::new(kubectl)
+        .args(["get", "deployments,pods", "--all-namespaces", "-o", "json"])
+        .output()
+        .await
+        .map_err(|e| format!("kubectl not found: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("kubectl get failed: {}", stderr.trim()));
+    }
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout)
+        .map_err(|e| format!("Failed to parse JSON: {}", e))?;
+    Ok(parse_kube_targets(&json))
+}
+
+/// Build the discovery list from a `kubectl get deployments,pods -o json`
+/// List: one entry per namespace, followed by its deployments and pods.
+pub fn parse_kube_targets(json: &serde_json::Value) -> Vec<KubeTarget> {
+    let mut targets = Vec::new();
+    let mut namespaces = std::collections::BTreeSet::new();
+
+    let items = json.get("items").and_then(|v| v.as_array());
+    for item in items.into_iter().flatten() {
+        let kind = item.get("kind").and_then(|v| v.as_str()).unwrap_or("");
+        let Some(name) = item.pointer("/metadata/name").and_then(|v| v.as_str()) else {
+            continue;
+        };
+        let namespace = item
+            .pointer("/metadata/namespace")
+            .and_then(|v| v.as_str())
+            .unwrap_or("default")
+            .to_string();
+        namespaces.insert(namespace.clone());
+
+        match kind {
+            "Deployment" => {
+                let selector = item
+                    .pointer("/spec/selector/matchLabels")
+                    .and_then(|v| v.as_object())
+                    .map(|labels| {
+                        labels
+                            .iter()
+                            .filter_map(|(k, v)| v.as_str().map(|v| format!("{}={}", k, v)))
+                            .collect::<Vec<_>>()
+                            .join(",")
+                    })
+                    .filter(|s| !s.is_empty());
+                let ready = item
+                    .pointer("/status/readyReplicas")
+                    .and_then(|v| v.as_u64())
+                    .unwrap_or(0);
+                let desired = item
+                    .pointer("/spec/replicas")
+                    .and_then(|v| v.as_u64())
+                    .unwrap_or(0);
+                targets.push(KubeTarget {
+                    kind: KubeTargetKind::Deployment,
+                    namespace,
+                    name: name.to_string(),
+                    selector,
+                    status: format!("{}/{}", ready, desired),
+                });
+            }
+            "Pod" => {
+                let phase = item
+                    .pointer("/status/phase")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("Unknown");
+                targets.push(KubeTarget {
+                    kind: KubeTargetKind::Pod,
+                    namespace,
+                    name: name.to_string(),
+                    selector: None,
+                    status: phase.to_string(),
+                });
+            }
+            _ => {}
+        }
+    }
+
+    // Deployments without a matchLabels selector can't be followed
+    targets.retain(|t| t.kind != KubeTargetKind::Deployment || t.selector.is_some());
+
+    for ns in namespaces {
+        targets.push(KubeTarget {
+            kind: KubeTargetKind::Namespace,
+            namespace: ns,
+            name: String::new(),
+            selector: None,
+            status: String::new(),
+        });
+    }
+
+    targets.sort_by(|a, b| {
+        a.namespace
+            .cmp(&b.namespace)
+            .then(a.kind.cmp(&b.kind))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    targets
+}
+
+pub fn discover_journald(tx: mpsc::Sender<DiscoveryResult>) {
+    tokio::spawn(async move {
+        let result = run_journald_discovery(&journalctl_bin()).await;
+        let _ = tx.send(DiscoveryResult::Journald(result)).await;
+    });
+}
+
+/// List the systemd units that have entries in the journal.
+async fn run_journald_discovery(journalctl: &str) -> Result<Vec<String>, String> {
+    let output = Command::new(journalctl)
+        .args(["-F", "_SYSTEMD_UNIT"])
+        .output()
+        .await
+        .map_err(|e| format!("journalctl not found: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("journalctl -F failed: {}", stderr.trim()));
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut units: Vec<String> = stdout
+        .lines()
+        .map(|l| l.trim())
+        .filter(|l| !l.is_empty())
+        .map(|l| l.to_string())
+        .collect();
+    units.sort();
+    units.dedup();
+    Ok(units)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn revisions_for_a_same_named_app_elsewhere_are_ignored() {
+        let app = |resource_group: &str| AzureContainerApp {
+            name: "api".into(),
+            resource_group: resource_group.into(),
+            subscription_id: "sub-1".into(),
+            subscription_name: "prod".into(),
+            provisioning_state: "Succeeded".into(),
+        };
+        let revision = AzureRevision {
+            name: "api--v2".into(),
+            active: true,
+            traffic_weight: 100,
+            replicas: 1,
+            created: String::new(),
+            running_state: "Running".into(),
+        };
+        let mut state = crate::app::App::new(None);
+        state.source_menu.azure_revision_app = Some(app("rg-a"));
+        state.source_menu.azure_revisions_loading = true;
+
+        state.handle_discovery_result(DiscoveryResult::AzureRevisions(
+            app("rg-b").app_ref(),
+            Ok(vec![revision.clone()]),
+        ));
+        assert!(state.source_menu.azure_revisions.is_empty());
+        assert!(state.source_menu.azure_revisions_loading);
+
+        state.handle_discovery_result(DiscoveryResult::AzureRevisions(app("rg-a").app_ref(), Ok(vec![revision])));
+        assert_eq!(state.source_menu.azure_revisions.len(), 1);
+    }
+
+    #[test]
+    fn azure_apps_from_rest_and_cli() {
+        let rest = serde_json::json!({"value": [{
+            "id": "/subscriptions/sub-a/resourceGroups/rg-web/providers/Microsoft.App/containerApps/web",
+            "name": "web",
+            "properties": {"provisioningState": "Succeeded"}
+        }]});
+        let apps = parse_container_apps(&rest, "Production");
+        assert_eq!(apps.len(), 1);
+        assert_eq!(apps[0].resource_group, "rg-web");
+        assert_eq!(apps[0].subscription_id, "sub-a");
+        assert_eq!(apps[0].subscription_name, "Production");
+
+        let cli = serde_json::json!([{
+            "id": "/subscriptions/sub-b/resourceGroups/x/providers/Microsoft.App/containerApps/api",
+            "name": "api",
+            "resourceGroup": "rg-api"
+        }]);
+        let apps = parse_container_apps(&cli, "");
+        assert_eq!(apps[0].resource_group, "rg-api");
+        // Without a display name the subscription id is shown
+        assert_eq!(apps[0].subscription_name, "sub-b");
+        assert_eq!(apps[0].provisioning_state, "Unknown");
+    }
+
+    #[test]
+    fn enabled_subscriptions_only() {
+        let json = serde_json::json!({"value": [
+            {"subscriptionId": "a", "displayName": "Prod", "state": "Enabled"},
+            {"subscriptionId": "b", "displayName": "Old", "state": "Disabled"},
+            {"subscriptionId": "c"}
+        ]});
+        assert_eq!(
+            parse_subscriptions(&json),
+            vec![("a".to_string(), "Prod".to_string()), ("c".to_string(), "c".to_string())]
+        );
+    }
+
+    #[test]
+    fn kube_targets_grouped_by_namespace() {
+        let json = serde_json::json!({
+            "kind": "List",
+            "items": [
+                {
+                    "kind": "Pod",
+                    "metadata": {"name": "api-7f9c-x2", "namespace": "prod"},
+                    "status": {"phase": "Running"}
+                },
+                {
+                    "kind": "Deployment",
+                    "metadata": {"name": "api", "namespace": "prod"},
+                    "spec": {"replicas": 3, "selector": {"matchLabels": {"app": "api"}}},
+                    "status": {"readyReplicas": 2}
+                },
+                {
+                    "kind": "Pod",
+                    "metadata": {"name": "coredns-1", "namespace": "kube-system"},
+                    "status": {"phase": "Running"}
+                }
+            ]
+        });
+        let targets = parse_kube_targets(&json);
+        let summary: Vec<(KubeTargetKind, &str, &str)> = targets
+            .iter()
+            .map(|t| (t.kind, t.namespace.as_str(), t.name.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (KubeTargetKind::Namespace, "kube-system", ""),
+                (KubeTargetKind::Pod, "kube-system", "coredns-1"),
+                (KubeTargetKind::Namespace, "prod", ""),
+                (KubeTargetKind::Deployment, "prod", "api"),
+                (KubeTargetKind::Pod, "prod", "api-7f9c-x2"),
+            ]
+        );
+        let deploy = &targets[3];
+        assert_eq!(deploy.status, "2/3");
+        assert_eq!(deploy.to_selector().labels.as_deref(), Some("app=api"));
+        assert_eq!(targets[4].to_selector().pod.as_deref(), Some("api-7f9c-x2"));
+    }
 }
@@ -0,0 +1,323 @@
+//! Export the pattern view or one pattern's samples to JSON, CSV or Markdown.
+
+use std::fmt::Write as _;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+
+use crate::pattern::Pattern;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Json,
+    Csv,
+    Markdown,
+}
+
+impl ExportFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Json => "json",
+            ExportFormat::Csv => "csv",
+            ExportFormat::Markdown => "md",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ExportFormat::Json => "JSON",
+            ExportFormat::Csv => "CSV",
+            ExportFormat::Markdown => "Markdown",
+        }
+    }
+
+    pub fn next(self) -> ExportFormat {
+        match self {
+            ExportFormat::Json => ExportFormat::Csv,
+            ExportFormat::Csv => ExportFormat::Markdown,
+            ExportFormat::Markdown => ExportFormat::Json,
+        }
+    }
+
+    /// `json`, `csv`, `md` or `markdown`, in any case.
+    pub fn from_name(name: &str) -> Option<ExportFormat> {
+        match name.to_ascii_lowercase().as_str() {
+            "json" => Some(ExportFormat::Json),
+            "csv" => Some(ExportFormat::Csv),
+            "md" | "markdown" => Some(ExportFormat::Markdown),
+            _ => None,
+        }
+    }
+
+    /// Infer the format from a file extension.
+    pub fn from_path(path: &str) -> Option<ExportFormat> {
+        Self::from_name(Path::new(path).extension()?.to_str()?)
+    }
+}
+
+/// What an export covers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportScope {
+    /// Every pattern in the current (searched / source-filtered) view.
+    View,
+    /// The selected pattern's samples.
+    Samples,
+}
+
+/// State of the export path prompt.
+#[derive(Debug, Clone)]
+pub struct ExportPrompt {
+    pub scope: ExportScope,
+    pub format: ExportFormat,
+    pub path: String,
+}
+
+impl ExportPrompt {
+    pub fn new(scope: ExportScope) -> Self {
+        let format = ExportFormat::Json;
+        let stem = match scope {
+            ExportScope::View => "patterns",
+            ExportScope::Samples => "samples",
+        };
+        let path = format!(
+            "logradar-{}-{}.{}",
+            stem,
+            chrono::Local::now().format("%Y%m%d-%H%M%S"),
+            format.extension()
+        );
+        ExportPrompt {
+            scope,
+            format,
+            path,
+        }
+    }
+
+    /// Switch to the next format, swapping the path's extension to match.
+    pub fn cycle_format(&mut self) {
+        self.format = self.format.next();
+        let path = Path::new(&self.path);
+        if ExportFormat::from_path(&self.path).is_some() {
+            self.path = path
+                .with_extension(self.format.extension())
+                .display()
+                .to_string();
+        }
+    }
+
+    /// The format actually written: a recognised extension wins.
+    pub fn effective_format(&self) -> ExportFormat {
+        ExportFormat::from_path(&self.path).unwrap_or(self.format)
+    }
+}
+
+fn sorted_sources(p: &Pattern) -> Vec<&str> {
+    let mut sources: Vec<&str> = p.sources.iter().map(String::as_str).collect();
+    sources.sort_unstable();
+    sources
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+/// Table cells can't hold pipes or newlines.
+fn md_cell(s: &str) -> String {
+    s.replace('|', "\\|").replace(['\n', '\r'], " ")
+}
+
+/// A fence longer than any backtick run in the samples.
+fn md_fence(samples: &[&str]) -> String {
+    let longest = samples
+        .iter()
+        .flat_map(|s| s.split(|c| c != '`'))
+        .map(str::len)
+        .max()
+        .unwrap_or(0);
+    "`".repeat(longest.max(2) + 1)
+}
+
+/// Render the given patterns (in view order).
+pub fn render_patterns(format: ExportFormat, patterns: &[&Pattern]) -> String {
+    match format {
+        ExportFormat::Json => {
+            // Same shape as the library's PatternSnapshot
+            serde_json::to_string_pretty(patterns).unwrap_or_default() + "\n"
+        }
+        ExportFormat::Csv => {
+            let mut out =
+                String::from("pattern,level,count,rate_1m,rate_5m,trend,spike,sources,samples\n");
+            for p in patterns {
+                let samples: Vec<&str> = p.samples.iter().map(String::as_str).collect();
+                let _ = writeln!(
+                    out,
+                    "{},{},{},{},{},{},{},{},{}",
+                    csv_field(&p.canonical),
+                    p.level.as_str(),
+                    p.count_total,
+                    p.rate_1m(),
+                    p.rate_5m(),
+                    p.trend.symbol(),
+                    p.spike,
+                    csv_field(&sorted_sources(p).join(";")),
+                    csv_field(&samples.join("\n")),
+                );
+            }
+            out
+        }
+        ExportFormat::Markdown => {
+            let mut out = String::from(
+                "| Pattern | Level | Count | /1m | /5m | Trend | Spike | Sources |\n\
+                 |---|---|---:|---:|---:|---|---|---|\n",
+            );
+            for p in patterns {
+                let _ = writeln!(
+                    out,
+                    "| `{}` | {} | {} | {} | {} | {} | {} | {} |",
+                    md_cell(&p.canonical).replace('`', "'"),
+                    p.level.as_str(),
+                    p.count_total,
+                    p.rate_1m(),
+                    p.rate_5m(),
+                    p.trend.symbol(),
+                    if p.spike { "yes" } else { "" },
+                    md_cell(&sorted_sources(p).join(", ")),
+                );
+            }
+            for (i, p) in patterns.iter().enumerate() {
+                let _ = write!(out, "\n### {}. {}\n\n", i + 1, md_cell(&p.canonical));
+                out.push_str(&render_samples(ExportFormat::Markdown, p));
+            }
+            out
+        }
+    }
+}
+
+/// Render one pattern's samples, oldest first.
+pub fn render_samples(format: ExportFormat, p: &Pattern) -> String {
+    let samples: Vec<&str> = p.samples.iter().map(String::as_str).collect();
+    match format {
+        ExportFormat::Json => {
+            let value = serde_json::json!({
+                "pattern": p.canonical,
+                "level": p.level.as_str(),
+                "samples": samples,
+            });
+            serde_json::to_string_pretty(&value).unwrap_or_default() + "\n"
+        }
+        ExportFormat::Csv => {
+            let mut out = String::from("sample\n");
+            for s in &samples {
+                out.push_str(&csv_field(s));
+                out.push('\n');
+            }
+            out
+        }
+        ExportFormat::Markdown => {
+            let fence = md_fence(&samples);
+            let mut out = format!("{}text\n", fence);
+            for s in &samples {
+                out.push_str(s);
+                out.push('\n');
+            }
+            out.push_str(&fence);
+            out.push('\n');
+            out
+        }
+    }
+}
+
+pub fn write(path: &str, contents: &str) -> Result<()> {
+    std::fs::write(path, contents).with_context(|| format!("writing {}", path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::{Level, LogEvent};
+    use crate::pattern::PatternStore;
+
+    fn store() -> PatternStore {
+        let mut store = PatternStore::new();
+        for (normalized, raw, level, source) in [
+            ("GET /api/<NUM> <NUM>", "GET /api/1 200", Level::Info, "docker/web"),
+            ("GET /api/<NUM> <NUM>", "GET /api/2 200", Level::Info, "docker/api"),
+            ("payment failed: \"card, declined\"", "payment failed: \"card, declined\"\nretry=1", Level::Error, "otlp/checkout"),
+        ] {
+            store.ingest(&LogEvent {
+                level,
+                source: source.into(),
+                raw: raw.into(),
+                normalized: normalized.into(),
+                fields: Vec::new(),
+            });
+        }
+        store
+    }
+
+    #[test]
+    fn formats_from_path_and_cycle() {
+        assert_eq!(ExportFormat::from_path("out.CSV"), Some(ExportFormat::Csv));
+        assert_eq!(ExportFormat::from_path("notes.markdown"), Some(ExportFormat::Markdown));
+        assert_eq!(ExportFormat::from_path("out"), None);
+
+        let mut prompt = ExportPrompt::new(ExportScope::View);
+        assert!(prompt.path.ends_with(".json"));
+        prompt.cycle_format();
+        assert_eq!(prompt.format, ExportFormat::Csv);
+        assert!(prompt.path.starts_with("logradar-patterns-") && prompt.path.ends_with(".csv"));
+        // An unrecognised extension is left alone and the selected format is used
+        prompt.path = "report.txt".into();
+        prompt.cycle_format();
+        assert_eq!(prompt.path, "report.txt");
+        assert_eq!(prompt.effective_format(), ExportFormat::Markdown);
+    }
+
+    #[test]
+    fn json_export() {
+        let store = store();
+        let patterns: Vec<&Pattern> = store.patterns().iter().collect();
+        let json: serde_json::Value =
+            serde_json::from_str(&render_patterns(ExportFormat::Json, &patterns)).unwrap();
+        assert_eq!(json[0]["id"], patterns[0].id());
+        assert_eq!(json[0]["pattern"], "GET /api/<NUM> <NUM>");
+        assert_eq!(json[0]["trend"], "stable");
+        assert_eq!(json[0]["count"], 2);
+        assert_eq!(json[0]["sources"], serde_json::json!(["docker/api", "docker/web"]));
+        assert_eq!(json[1]["level"], "ERROR");
+        assert_eq!(json[1]["samples"].as_array().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn csv_export_quotes_fields() {
+        let store = store();
+        let patterns: Vec<&Pattern> = store.patterns().iter().collect();
+        let csv = render_patterns(ExportFormat::Csv, &patterns);
+        let mut lines = csv.lines();
+        assert_eq!(
+            lines.next().unwrap(),
+            "pattern,level,count,rate_1m,rate_5m,trend,spike,sources,samples"
+        );
+        // Multi-line samples stay inside one quoted field
+        assert!(lines.next().unwrap().starts_with("GET /api/<NUM> <NUM>,INFO,2,2,0.4,"));
+        assert!(csv.contains(",false,docker/api;docker/web,\"GET /api/1 200\nGET /api/2 200\"\n"));
+        assert!(csv.contains("\"payment failed: \"\"card, declined\"\"\",ERROR,1,"));
+
+        let samples = render_samples(ExportFormat::Csv, &store.patterns()[1]);
+        assert_eq!(samples, "sample\n\"payment failed: \"\"card, declined\"\"\nretry=1\"\n");
+    }
+
+    #[test]
+    fn markdown_export() {
+        let store = store();
+        let patterns: Vec<&Pattern> = store.patterns().iter().collect();
+        let md = render_patterns(ExportFormat::Markdown, &patterns);
+        assert!(md.starts_with("| Pattern | Level |"));
+        assert!(md.contains("| `GET /api/<NUM> <NUM>` | INFO | 2 |"));
+        assert!(md.contains("### 2. payment failed"));
+        assert!(md.contains("```text\nGET /api/1 200\nGET /api/2 200\n```\n"));
+    }
+}
@@ -0,0 +1,987 @@
+use std::collections::HashMap;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::Result;
+use reqwest::{Method, StatusCode};
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::process::Command;
+use tokio::sync::{mpsc, Mutex};
+use tokio::task::{AbortHandle, JoinSet};
+
+use super::{SourceEvent, SourceInfo, SourceStatus};
+
+const MANAGEMENT_URL: &str = "https://management.azure.com";
+const API_VERSION: &str = "api-version=2024-03-01";
+
+/// Refresh the management token this long before it actually expires.
+const REFRESH_MARGIN: Duration = Duration::from_secs(300);
+
+/// The az binary to invoke. `LOGRADAR_AZ` overrides it so a stub can hand out
+/// tokens in tests.
+pub fn az_bin() -> String {
+    std::env::var("LOGRADAR_AZ").unwrap_or_else(|_| "az".to_string())
+}
+
+/// A bearer token held only in memory (never passed on a command line).
+#[derive(Clone)]
+pub struct AccessToken {
+    secret: String,
+    expires_at: Option<SystemTime>,
+}
+
+impl std::fmt::Debug for AccessToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AccessToken")
+            .field("expires_at", &self.expires_at)
+            .finish_non_exhaustive()
+    }
+}
+
+impl AccessToken {
+    pub fn new(secret: impl Into<String>, expires_at: Option<SystemTime>) -> AccessToken {
+        AccessToken {
+            secret: secret.into(),
+            expires_at,
+        }
+    }
+
+    /// Parse `az account get-access-token -o json` output. `expires_on` is
+    /// unix seconds (a number or a numeric string depending on the az version).
+    pub fn from_cli_json(json: &serde_json::Value) -> Result<AccessToken> {
+        let secret = json
+            .get("accessToken")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("no accessToken in response"))?;
+        let expires_at = json
+            .get("expires_on")
+            .and_then(|v| v.as_u64().or_else(|| v.as_str()?.parse().ok()))
+            .map(|secs| UNIX_EPOCH + Duration::from_secs(secs));
+        Ok(AccessToken::new(secret, expires_at))
+    }
+
+    fn is_fresh(&self, now: SystemTime) -> bool {
+        match self.expires_at {
+            Some(expires_at) => now + REFRESH_MARGIN < expires_at,
+            None => true,
+        }
+    }
+}
+
+/// Fetch a management API token from the az CLI's login session.
+pub async fn fetch_management_token(az: &str) -> Result<AccessToken> {
+    let output = Command::new(az)
+        .args([
+            "account",
+            "get-access-token",
+            "--resource",
+            "https://management.azure.com/",
+            "-o",
+            "json",
+        ])
+        .output()
+        .await
+        .map_err(|e| anyhow::anyhow!("az token: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow::anyhow!("az token failed: {}", stderr.trim()));
+    }
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout)
+        .map_err(|e| anyhow::anyhow!("token parse: {}", e))?;
+    AccessToken::from_cli_json(&json)
+}
+
+/// Identifies one container app.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContainerAppRef {
+    pub subscription_id: String,
+    pub resource_group: String,
+    pub name: String,
+}
+
+impl ContainerAppRef {
+    fn resource_path(&self) -> String {
+        format!(
+            "/subscriptions/{}/resourceGroups/{}/providers/Microsoft.App/containerApps/{}",
+            self.subscription_id, self.resource_group, self.name
+        )
+    }
+}
+
+/// In-process client for the Azure management API and the Container Apps log
+/// stream. The management token is refreshed from the az CLI when it is about
+/// to expire or is rejected.
+pub struct AzureClient {
+    http: reqwest::Client,
+    management_url: String,
+    az: String,
+    token: Mutex<Option<AccessToken>>,
+}
+
+impl AzureClient {
+    pub fn new(token: Option<AccessToken>) -> AzureClient {
+        AzureClient::with_endpoint(MANAGEMENT_URL, &az_bin(), token)
+    }
+
+    fn with_endpoint(management_url: &str, az: &str, token: Option<AccessToken>) -> AzureClient {
+        AzureClient {
+            http: reqwest::Client::new(),
+            management_url: management_url.trim_end_matches('/').to_string(),
+            az: az.to_string(),
+            token: Mutex::new(token),
+        }
+    }
+
+    async fn bearer(&self, force_refresh: bool) -> Result<String> {
+        let mut token = self.token.lock().await;
+        if let Some(ref t) = *token {
+            if !force_refresh && t.is_fresh(SystemTime::now()) {
+                return Ok(t.secret.clone());
+            }
+        }
+        let fresh = fetch_management_token(&self.az).await?;
+        let secret = fresh.secret.clone();
+        *token = Some(fresh);
+        Ok(secret)
+    }
+
+    /// Call the management API, refreshing the token once if it is rejected.
+    /// `path` may carry its own `api-version`, or be an absolute `nextLink`
+    /// on the management host (the token is never sent anywhere else).
+    async fn management(&self, method: Method, path: &str) -> Result<serde_json::Value> {
+        let path = self.management_path(path)?;
+        let url = if path.contains("api-version=") {
+            format!("{}{}", self.management_url, path)
+        } else {
+            let sep = if path.contains('?') { '&' } else { '?' };
+            format!("{}{}{}{}", self.management_url, path, sep, API_VERSION)
+        };
+        let mut refreshed = false;
+        loop {
+            let mut req = self
+                .http
+                .request(method.clone(), &url)
+                .bearer_auth(self.bearer(refreshed).await?);
+            if method == Method::POST {
+                req = req.body(Vec::new());
+            }
+            let resp = req.send().await?;
+            if resp.status() == StatusCode::UNAUTHORIZED && !refreshed {
+                refreshed = true;
+                continue;
+            }
+            let status = resp.status();
+            if !status.is_success() {
+                return Err(anyhow::anyhow!("{} {} failed: {}", method, path, status));
+            }
+            return Ok(resp.json().await?);
+        }
+    }
+
+    /// `path` relative to the management host. An absolute URL has to be
+    /// on that host: `https://management.azure.com.evil.com/` or
+    /// `https://management.azure.com@evil.com/` are refused.
+    fn management_path<'a>(&self, path: &'a str) -> Result<&'a str> {
+        match path.strip_prefix(&self.management_url) {
+            Some(rest) if rest.is_empty() || rest.starts_with('/') => Ok(rest),
+            None if path.starts_with('/') => Ok(path),
+            _ => Err(anyhow::anyhow!("refusing to send token to {}", path)),
+        }
+    }
+
+    pub async fn get_json(&self, path: &str) -> Result<serde_json::Value> {
+        self.management(Method::GET, path).await
+    }
+
+    pub async fn post_json(&self, path: &str) -> Result<serde_json::Value> {
+        self.management(Method::POST, path).await
+    }
+}
+
+fn json_str<'a>(json: &'a serde_json::Value, pointer: &str, what: &str) -> Result<&'a str> {
+    json.pointer(pointer)
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow::anyhow!("no {}", what))
+}
+
+/// Scheme and host of the log stream endpoint, taken from the app's
+/// `eventStreamEndpoint` or, failing that, its environment's default domain.
+async fn logstream_origin(client: &AzureClient, app_json: &serde_json::Value) -> Result<String> {
+    if let Some(endpoint) = app_json
+        .pointer("/properties/eventStreamEndpoint")
+        .and_then(|v| v.as_str())
+    {
+        if let Some(idx) = endpoint.find("/subscriptions/") {
+            return Ok(endpoint[..idx].to_string());
+        }
+    }
+    let env_id = json_str(app_json, "/properties/managedEnvironmentId", "managedEnvironmentId")?;
+    let env_json = client.get_json(env_id).await?;
+    let domain = json_str(&env_json, "/properties/defaultDomain", "defaultDomain")?;
+    Ok(format!("https://{}", domain))
+}
+
+/// Forward a text body line by line until it ends or the receiver goes away.
+async fn stream_lines(
+    mut resp: reqwest::Response,
+    source_id: &str,
+    tx: &mpsc::Sender<SourceEvent>,
+) -> Result<()> {
+    let mut buf: Vec<u8> = Vec::new();
+    while let Some(chunk) = resp.chunk().await? {
+        buf.extend_from_slice(&chunk);
+        while let Some(pos) = buf.iter().position(|&b| b == b'\n') {
+            let raw: Vec<u8> = buf.drain(..=pos).collect();
+            let line = String::from_utf8_lossy(&raw[..pos])
+                .trim_end_matches('\r')
+                .to_string();
+            if tx
+                .send(SourceEvent::Log {
+                    source: source_id.to_string(),
+                    line,
+                })
+                .await
+                .is_err()
+            {
+                return Ok(());
+            }
+        }
+    }
+    if !buf.is_empty() {
+        let _ = tx
+            .send(SourceEvent::Log {
+                source: source_id.to_string(),
+                line: String::from_utf8_lossy(&buf).into_owned(),
+            })
+            .await;
+    }
+    Ok(())
+}
+
+/// One revision of a container app, as listed by the management API (or
+/// `az containerapp revision list`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AzureRevision {
+    pub name: String,
+    pub active: bool,
+    pub traffic_weight: u32,
+    pub replicas: u32,
+    pub created: String,
+    pub running_state: String,
+}
+
+/// Parse a revision list (`{"value": [...]}` from REST or a bare array from
+/// the CLI), active revisions first and newest first within each group.
+pub fn parse_revisions(json: &serde_json::Value) -> Vec<AzureRevision> {
+    let items = json
+        .get("value")
+        .and_then(|v| v.as_array())
+        .or_else(|| json.as_array());
+    let mut revisions: Vec<AzureRevision> = items
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| {
+                    let name = item.get("name")?.as_str()?.to_string();
+                    let props = item.get("properties");
+                    let prop = |key: &str| props.and_then(|p| p.get(key));
+                    Some(AzureRevision {
+                        name,
+                        active: prop("active").and_then(|v| v.as_bool()).unwrap_or(false),
+                        traffic_weight: prop("trafficWeight")
+                            .and_then(|v| v.as_u64())
+                            .unwrap_or(0) as u32,
+                        replicas: prop("replicas").and_then(|v| v.as_u64()).unwrap_or(0) as u32,
+                        created: prop("createdTime")
+                            .and_then(|v| v.as_str())
+                            .unwrap_or_default()
+                            .to_string(),
+                        running_state: prop("runningState")
+                            .and_then(|v| v.as_str())
+                            .unwrap_or("Unknown")
+                            .to_string(),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    // ISO 8601 timestamps sort chronologically as strings
+    revisions.sort_by(|a, b| b.active.cmp(&a.active).then_with(|| b.created.cmp(&a.created)));
+    revisions
+}
+
+fn parse_replica_names(json: &serde_json::Value) -> Vec<String> {
+    json.get("value")
+        .and_then(|v| v.as_array())
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| item.get("name")?.as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// List an app's revisions over REST.
+pub async fn list_revisions(client: &AzureClient, app: &ContainerAppRef) -> Result<Vec<AzureRevision>> {
+    let json = client
+        .get_json(&format!("{}/revisions", app.resource_path()))
+        .await?;
+    Ok(parse_revisions(&json))
+}
+
+/// How often the replica list is re-checked for scale-out / scale-in.
+const POLL_INTERVAL: Duration = Duration::from_secs(15);
+
+pub fn spawn_azure_containerapp(
+    app: ContainerAppRef,
+    revision: Option<String>,
+    token: Option<AccessToken>,
+    tx: mpsc::Sender<SourceEvent>,
+) -> (SourceInfo, tokio::task::JoinHandle<()>) {
+    let id = format!("azure/{}", app.name);
+    let info = SourceInfo {
+        id: id.clone(),
+        kind: "azure".into(),
+        status: SourceStatus::Starting,
+    };
+    let handle = tokio::spawn(async move {
+        let _ = run_azure_containerapp(&app, revision.as_deref(), token, &id, tx).await;
+    });
+    (info, handle)
+}
+
+async fn run_azure_containerapp(
+    app: &ContainerAppRef,
+    revision: Option<&str>,
+    token: Option<AccessToken>,
+    source_id: &str,
+    tx: mpsc::Sender<SourceEvent>,
+) -> Result<()> {
+    // Talk to the REST API directly when we know the subscription
+    if !app.subscription_id.is_empty() {
+        let client = AzureClient::new(token);
+        match run_azure_replicas(&client, app, revision, source_id, &tx, POLL_INTERVAL).await {
+            Ok(()) => return Ok(()),
+            Err(_) => {
+                // Native path failed — fall through to az CLI
+            }
+        }
+    }
+
+    // Fallback: use az CLI (slower, and only follows a single replica)
+    run_azure_cli(&app.name, &app.resource_group, "console", revision, source_id, tx).await
+}
+
+/// Follow every replica of one revision (the newest active one unless given),
+/// attaching `azure/<app>/<replica>` sources as replicas appear and stopping
+/// them as they go away. Avoids the ~3-5s Python startup of the az CLI.
+///
+/// Returns an error only if the initial lookups fail, so the caller can fall
+/// back to the CLI; later failures are reported as the supervisor's status.
+async fn run_azure_replicas(
+    client: &AzureClient,
+    app: &ContainerAppRef,
+    revision: Option<&str>,
+    source_id: &str,
+    tx: &mpsc::Sender<SourceEvent>,
+    poll_interval: Duration,
+) -> Result<()> {
+    let base = app.resource_path();
+    let app_json = client.get_json(&base).await?;
+    let origin = logstream_origin(client, &app_json).await?;
+    let revision = match revision {
+        Some(r) => r.to_string(),
+        None => list_revisions(client, app)
+            .await?
+            .into_iter()
+            .next()
+            .map(|r| r.name)
+            .ok_or_else(|| anyhow::anyhow!("no revisions"))?,
+    };
+    let replicas_path = format!("{}/revisions/{}/replicas", base, revision);
+    let mut replicas = parse_replica_names(&client.get_json(&replicas_path).await?);
+
+    let _ = tx.send(SourceEvent::Status {
+        source: source_id.to_string(),
+        status: SourceStatus::Running,
+    }).await;
+
+    // Dropping the JoinSet aborts every follower when the supervisor is aborted
+    let mut tasks = JoinSet::new();
+    let mut followers: HashMap<String, AbortHandle> = HashMap::new();
+    let stream_base = format!(
+        "{}/subscriptions/{}/resourceGroups/{}/containerApps/{}/revisions/{}/replicas",
+        origin, app.subscription_id, app.resource_group, app.name, revision
+    );
+
+    loop {
+        if let Err(e) = reconcile_replicas(
+            client,
+            app,
+            &stream_base,
+            &replicas,
+            &mut followers,
+            &mut tasks,
+            tx,
+        )
+        .await
+        {
+            let _ = tx.send(SourceEvent::Status {
+                source: source_id.to_string(),
+                status: SourceStatus::Error(format!("azure: {}", e)),
+            }).await;
+        }
+        while tasks.try_join_next().is_some() {}
+        if tx.is_closed() {
+            break;
+        }
+        tokio::time::sleep(poll_interval).await;
+
+        match client.get_json(&replicas_path).await {
+            Ok(json) => {
+                replicas = parse_replica_names(&json);
+                let _ = tx.send(SourceEvent::Status {
+                    source: source_id.to_string(),
+                    status: SourceStatus::Running,
+                }).await;
+            }
+            Err(e) => {
+                let _ = tx.send(SourceEvent::Status {
+                    source: source_id.to_string(),
+                    status: SourceStatus::Error(format!("azure: {}", e)),
+                }).await;
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn reconcile_replicas(
+    client: &AzureClient,
+    app: &ContainerAppRef,
+    stream_base: &str,
+    replicas: &[String],
+    followers: &mut HashMap<String, AbortHandle>,
+    tasks: &mut JoinSet<()>,
+    tx: &mpsc::Sender<SourceEvent>,
+) -> Result<()> {
+    let replica_id = |replica: &str| format!("azure/{}/{}", app.name, replica);
+
+    // Replicas that scaled in
+    let gone: Vec<String> = followers
+        .keys()
+        .filter(|r| !replicas.contains(r))
+        .cloned()
+        .collect();
+    for replica in gone {
+        if let Some(handle) = followers.remove(&replica) {
+            handle.abort();
+        }
+        let _ = tx.send(SourceEvent::Status {
+            source: replica_id(&replica),
+            status: SourceStatus::Stopped,
+        }).await;
+    }
+
+    let pending: Vec<&String> = replicas
+        .iter()
+        .filter(|r| followers.get(*r).is_none_or(|h| h.is_finished()))
+        .collect();
+    if pending.is_empty() {
+        return Ok(());
+    }
+
+    // Log stream tokens are short-lived, so fetch one per round of new followers
+    let auth = client
+        .post_json(&format!("{}/getAuthToken", app.resource_path()))
+        .await?;
+    let log_token = json_str(&auth, "/properties/token", "log stream token")?.to_string();
+
+    for replica in pending {
+        // A stream that ended while its replica is still listed is resumed
+        // without replaying the tail.
+        let tail_lines = if followers.contains_key(replica) {
+            0
+        } else {
+            let _ = tx.send(SourceEvent::Attached {
+                info: SourceInfo {
+                    id: replica_id(replica),
+                    kind: "azure".into(),
+                    status: SourceStatus::Starting,
+                },
+            }).await;
+            100
+        };
+        let url = format!(
+            "{}/{}/logstream?follow=true&tailLines={}&output=text",
+            stream_base, replica, tail_lines
+        );
+        let handle = tasks.spawn(follow_replica(
+            client.http.clone(),
+            url,
+            log_token.clone(),
+            replica_id(replica),
+            tx.clone(),
+        ));
+        followers.insert(replica.clone(), handle);
+    }
+    Ok(())
+}
+
+async fn follow_replica(
+    http: reqwest::Client,
+    url: String,
+    log_token: String,
+    source_id: String,
+    tx: mpsc::Sender<SourceEvent>,
+) {
+    let resp = match http.get(&url).bearer_auth(log_token).send().await {
+        Ok(resp) if resp.status().is_success() => resp,
+        Ok(resp) => {
+            let _ = tx.send(SourceEvent::Status {
+                source: source_id,
+                status: SourceStatus::Error(format!("log stream: {}", resp.status())),
+            }).await;
+            return;
+        }
+        Err(e) => {
+            let _ = tx.send(SourceEvent::Status {
+                source: source_id,
+                status: SourceStatus::Error(format!("log stream: {}", e)),
+            }).await;
+            return;
+        }
+    };
+
+    let _ = tx.send(SourceEvent::Status {
+        source: source_id.clone(),
+        status: SourceStatus::Running,
+    }).await;
+    let _ = stream_lines(resp, &source_id, &tx).await;
+}
+
+/// System logs (revision provisioning, scaling, probe failures, ...) for an
+/// app, as a source of their own next to its replicas.
+pub fn spawn_azure_system_logs(
+    app: ContainerAppRef,
+    token: Option<AccessToken>,
+    tx: mpsc::Sender<SourceEvent>,
+) -> (SourceInfo, tokio::task::JoinHandle<()>) {
+    let id = format!("azure/{}/system", app.name);
+    let info = SourceInfo {
+        id: id.clone(),
+        kind: "azure".into(),
+        status: SourceStatus::Starting,
+    };
+    let handle = tokio::spawn(async move {
+        if !app.subscription_id.is_empty() {
+            let client = AzureClient::new(token);
+            if run_azure_eventstream(&client, &app, &id, &tx).await.is_ok() {
+                return;
+            }
+        }
+        let _ = run_azure_cli(&app.name, &app.resource_group, "system", None, &id, tx).await;
+    });
+    (info, handle)
+}
+
+/// Follow the app's `eventStreamEndpoint`, which carries its system logs.
+async fn run_azure_eventstream(
+    client: &AzureClient,
+    app: &ContainerAppRef,
+    source_id: &str,
+    tx: &mpsc::Sender<SourceEvent>,
+) -> Result<()> {
+    let base = app.resource_path();
+    let app_json = client.get_json(&base).await?;
+    let endpoint =
+        json_str(&app_json, "/properties/eventStreamEndpoint", "eventStreamEndpoint")?.to_string();
+    let auth = client.post_json(&format!("{}/getAuthToken", base)).await?;
+    let log_token = json_str(&auth, "/properties/token", "log stream token")?.to_string();
+
+    let sep = if endpoint.contains('?') { '&' } else { '?' };
+    let url = format!("{}{}follow=true&tailLines=100&output=text", endpoint, sep);
+    let resp = client.http.get(&url).bearer_auth(log_token).send().await?;
+    if !resp.status().is_success() {
+        return Err(anyhow::anyhow!("event stream failed: {}", resp.status()));
+    }
+
+    let _ = tx.send(SourceEvent::Status {
+        source: source_id.to_string(),
+        status: SourceStatus::Running,
+    }).await;
+
+    stream_lines(resp, source_id, tx).await?;
+
+    let _ = tx.send(SourceEvent::Status {
+        source: source_id.to_string(),
+        status: SourceStatus::Stopped,
+    }).await;
+    Ok(())
+}
+
+/// Fallback: use az CLI for log streaming (slower due to Python startup).
+async fn run_azure_cli(
+    app_name: &str,
+    resource_group: &str,
+    log_type: &str,
+    revision: Option<&str>,
+    source_id: &str,
+    tx: mpsc::Sender<SourceEvent>,
+) -> Result<()> {
+    let mut args = vec![
+        "containerapp", "logs", "show", "-n", app_name, "-g", resource_group, "--type", log_type,
+        "--follow",
+    ];
+    if let Some(revision) = revision {
+        args.extend(["--revision", revision]);
+    }
+    let result = Command::new(az_bin())
+        .args(&args)
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::null())
+        .kill_on_drop(true)
+        .spawn();
+
+    let mut child = match result {
+        Ok(child) => child,
+        Err(e) => {
+            let _ = tx.send(SourceEvent::Status {
+                source: source_id.to_string(),
+                status: SourceStatus::Error(format!("az: {}", e)),
+            }).await;
+            return Err(e.into());
+        }
+    };
+
+    if let Some(stdout) = child.stdout.take() {
+        let reader = BufReader::new(stdout);
+        let mut lines = reader.lines();
+        let mut first = true;
+        while let Some(line) = lines.next_line().await? {
+            if first {
+                let _ = tx.send(SourceEvent::Status {
+                    source: source_id.to_string(),
+                    status: SourceStatus::Running,
+                }).await;
+                first = false;
+            }
+            if tx
+                .send(SourceEvent::Log {
+                    source: source_id.to_string(),
+                    line,
+                })
+                .await
+                .is_err()
+            {
+                break;
+            }
+        }
+    }
+
+    let _ = tx.send(SourceEvent::Status {
+        source: source_id.to_string(),
+        status: SourceStatus::Stopped,
+    }).await;
+
+    let _ = child.wait().await;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ingest::http::{serve, HttpRequest, HttpResponse};
+    use hyper::StatusCode as HttpStatus;
+    use std::net::SocketAddr;
+    use std::path::PathBuf;
+    use std::sync::{Arc, Mutex as StdMutex};
+
+    #[test]
+    fn token_stays_on_the_management_host() {
+        let client = AzureClient::with_endpoint(MANAGEMENT_URL, "az", None);
+        let next = "https://management.azure.com/subscriptions?$skiptoken=x";
+        assert_eq!(client.management_path(next).unwrap(), "/subscriptions?$skiptoken=x");
+        assert_eq!(client.management_path("/subscriptions").unwrap(), "/subscriptions");
+        for url in [
+            "https://management.azure.com.evil.com/subscriptions",
+            "https://management.azure.com@evil.com/subscriptions",
+            "https://evil.com/subscriptions",
+        ] {
+            assert!(client.management_path(url).is_err(), "{}", url);
+        }
+    }
+
+    #[test]
+    fn parse_cli_token() {
+        let json = serde_json::json!({"accessToken": "abc", "expires_on": 1_900_000_000});
+        let token = AccessToken::from_cli_json(&json).unwrap();
+        assert_eq!(token.expires_at, Some(UNIX_EPOCH + Duration::from_secs(1_900_000_000)));
+        // Older az versions emit expires_on as a string
+        let json = serde_json::json!({"accessToken": "abc", "expires_on": "1900000000"});
+        assert!(AccessToken::from_cli_json(&json).unwrap().expires_at.is_some());
+        assert!(AccessToken::from_cli_json(&serde_json::json!({})).is_err());
+        // The secret never shows up in debug output
+        assert!(!format!("{:?}", token).contains("abc"));
+    }
+
+    #[test]
+    fn token_freshness() {
+        let now = SystemTime::now();
+        assert!(AccessToken::new("t", None).is_fresh(now));
+        assert!(AccessToken::new("t", Some(now + Duration::from_secs(3600))).is_fresh(now));
+        // Inside the refresh margin counts as expired
+        assert!(!AccessToken::new("t", Some(now + Duration::from_secs(60))).is_fresh(now));
+    }
+
+    const APP: &str = "/subscriptions/sub1/resourceGroups/rg1/providers/Microsoft.App/containerApps/web";
+
+    fn app_ref() -> ContainerAppRef {
+        ContainerAppRef {
+            subscription_id: "sub1".into(),
+            resource_group: "rg1".into(),
+            name: "web".into(),
+        }
+    }
+
+    fn json(body: serde_json::Value) -> HttpResponse {
+        HttpResponse {
+            status: HttpStatus::OK,
+            content_type: "application/json",
+            body: body.to_string().into_bytes(),
+        }
+    }
+
+    fn revisions_json() -> serde_json::Value {
+        serde_json::json!({"value": [
+            {"name": "web--old", "properties": {"active": false, "createdTime": "2026-03-01T10:00:00Z", "replicas": 0}},
+            {"name": "web--r1", "properties": {"active": true, "createdTime": "2026-02-01T10:00:00Z", "replicas": 1, "trafficWeight": 100, "runningState": "Running"}},
+            {"name": "web--r0", "properties": {"active": true, "createdTime": "2026-01-01T10:00:00Z", "replicas": 1}}
+        ]})
+    }
+
+    /// Mimics the management endpoints plus the log stream host. `replicas`
+    /// can be changed while the test runs to simulate scale-out.
+    async fn mock_azure(
+        req: HttpRequest,
+        origin: String,
+        replicas: Arc<StdMutex<Vec<String>>>,
+    ) -> HttpResponse {
+        let auth = req.header("authorization").unwrap_or_default().to_string();
+        if req.path.ends_with("/eventstream") {
+            if auth != "Bearer logtok" {
+                return HttpResponse::empty(HttpStatus::UNAUTHORIZED);
+            }
+            return HttpResponse::text(HttpStatus::OK, "Replica web--r1-aaa scaled up\n");
+        }
+        if let Some(rest) = req.path.strip_suffix("/logstream") {
+            if auth != "Bearer logtok" {
+                return HttpResponse::empty(HttpStatus::UNAUTHORIZED);
+            }
+            let replica = rest.rsplit('/').next().unwrap_or_default();
+            return HttpResponse::text(
+                HttpStatus::OK,
+                format!("hello from {}\r\nsecond line\nno newline", replica),
+            );
+        }
+        if auth != "Bearer mgmt-2" {
+            return HttpResponse::empty(HttpStatus::UNAUTHORIZED);
+        }
+        let path = req.path.as_str();
+        match (req.method.as_str(), path.strip_prefix(APP)) {
+            ("GET", Some("")) => json(serde_json::json!({
+                "properties": {
+                    "eventStreamEndpoint": format!(
+                        "{}/subscriptions/sub1/resourceGroups/rg1/containerApps/web/eventstream",
+                        origin
+                    )
+                }
+            })),
+            ("GET", Some("/revisions")) => json(revisions_json()),
+            ("GET", Some("/revisions/web--r1/replicas")) => {
+                let names = replicas.lock().unwrap().clone();
+                let value: Vec<serde_json::Value> = names
+                    .iter()
+                    .map(|n| serde_json::json!({"name": n}))
+                    .collect();
+                json(serde_json::json!({"value": value}))
+            }
+            ("POST", Some("/getAuthToken")) => {
+                json(serde_json::json!({"properties": {"token": "logtok"}}))
+            }
+            _ => HttpResponse::empty(HttpStatus::NOT_FOUND),
+        }
+    }
+
+    async fn start_mock(replicas: Vec<&str>) -> (SocketAddr, Arc<StdMutex<Vec<String>>>) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let origin = format!("http://{}", addr);
+        let replicas = Arc::new(StdMutex::new(
+            replicas.into_iter().map(str::to_string).collect::<Vec<_>>(),
+        ));
+        let shared = replicas.clone();
+        tokio::spawn(serve(listener, move |req| {
+            mock_azure(req, origin.clone(), shared.clone())
+        }));
+        (addr, replicas)
+    }
+
+    /// A stub az that hands out `mgmt-2` and counts how often it was asked.
+    #[cfg(unix)]
+    fn stub_az(name: &str) -> (PathBuf, PathBuf) {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join(format!("logradar-az-{}-{}", name, std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let calls = dir.join("calls");
+        let stub = dir.join("az");
+        std::fs::write(
+            &stub,
+            format!(
+                "#!/bin/sh\necho x >> '{}'\necho '{{\"accessToken\":\"mgmt-2\",\"expires_on\":4000000000}}'\n",
+                calls.display()
+            ),
+        )
+        .unwrap();
+        std::fs::set_permissions(&stub, std::fs::Permissions::from_mode(0o755)).unwrap();
+        (stub, calls)
+    }
+
+    fn call_count(calls: &PathBuf) -> usize {
+        std::fs::read_to_string(calls).map(|s| s.lines().count()).unwrap_or(0)
+    }
+
+    #[test]
+    fn revisions_sorted_active_first() {
+        let revisions = parse_revisions(&revisions_json());
+        let names: Vec<&str> = revisions.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, vec!["web--r1", "web--r0", "web--old"]);
+        assert_eq!(revisions[0].traffic_weight, 100);
+        assert_eq!(revisions[0].running_state, "Running");
+        assert_eq!(revisions[2].running_state, "Unknown");
+        // The CLI returns a bare array
+        let cli = serde_json::json!([{"name": "a", "properties": {"active": true}}]);
+        assert_eq!(parse_revisions(&cli).len(), 1);
+    }
+
+    /// Wait until `want` has been seen, collecting events along the way.
+    async fn collect_until(
+        rx: &mut mpsc::Receiver<SourceEvent>,
+        seen: &mut Vec<(String, String)>,
+        attached: &mut Vec<String>,
+        want: (&str, &str),
+    ) {
+        let deadline = tokio::time::Instant::now() + Duration::from_secs(5);
+        while !seen.iter().any(|(s, l)| s == want.0 && l == want.1) {
+            let ev = tokio::time::timeout_at(deadline, rx.recv())
+                .await
+                .expect("timed out waiting for log line")
+                .unwrap();
+            match ev {
+                SourceEvent::Log { source, line } => seen.push((source, line)),
+                SourceEvent::Attached { info } => attached.push(info.id),
+                _ => {}
+            }
+        }
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn follows_all_replicas_and_scale_out() {
+        let (addr, replicas) = start_mock(vec!["web--r1-aaa"]).await;
+        let (az, calls) = stub_az("replicas");
+        // Expired token is refreshed before the first call
+        let expired = AccessToken::new("mgmt-1", Some(UNIX_EPOCH));
+        let client = AzureClient::with_endpoint(
+            &format!("http://{}", addr),
+            &az.display().to_string(),
+            Some(expired),
+        );
+
+        let (tx, mut rx) = mpsc::channel(256);
+        let supervisor = tokio::spawn(async move {
+            run_azure_replicas(&client, &app_ref(), None, "azure/web", &tx, Duration::from_millis(50))
+                .await
+        });
+
+        let mut seen = Vec::new();
+        let mut attached = Vec::new();
+        collect_until(&mut rx, &mut seen, &mut attached, ("azure/web/web--r1-aaa", "hello from web--r1-aaa")).await;
+        collect_until(&mut rx, &mut seen, &mut attached, ("azure/web/web--r1-aaa", "no newline")).await;
+
+        replicas.lock().unwrap().push("web--r1-bbb".into());
+        collect_until(&mut rx, &mut seen, &mut attached, ("azure/web/web--r1-bbb", "hello from web--r1-bbb")).await;
+
+        // The newest active revision was picked, and each replica attached once
+        attached.dedup();
+        assert_eq!(attached, vec!["azure/web/web--r1-aaa", "azure/web/web--r1-bbb"]);
+        assert!(seen.contains(&("azure/web/web--r1-aaa".to_string(), "second line".to_string())));
+        // One refresh up front, then the token was reused
+        assert_eq!(call_count(&calls), 1);
+
+        supervisor.abort();
+        let _ = std::fs::remove_dir_all(az.parent().unwrap());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn streams_system_logs_from_event_stream() {
+        let (addr, _) = start_mock(vec![]).await;
+        let (az, _) = stub_az("system");
+        let client = AzureClient::with_endpoint(
+            &format!("http://{}", addr),
+            &az.display().to_string(),
+            Some(AccessToken::new("mgmt-2", None)),
+        );
+
+        let (tx, mut rx) = mpsc::channel(16);
+        run_azure_eventstream(&client, &app_ref(), "azure/web/system", &tx)
+            .await
+            .unwrap();
+        let mut lines = Vec::new();
+        while let Ok(ev) = rx.try_recv() {
+            if let SourceEvent::Log { source, line } = ev {
+                lines.push((source, line));
+            }
+        }
+        assert_eq!(
+            lines,
+            vec![("azure/web/system".to_string(), "Replica web--r1-aaa scaled up".to_string())]
+        );
+
+        let _ = std::fs::remove_dir_all(az.parent().unwrap());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn refreshes_rejected_token() {
+        let (addr, _) = start_mock(vec![]).await;
+        let (az, calls) = stub_az("revoked");
+        // Looks fresh, but the server rejects it
+        let revoked = AccessToken::new("revoked", None);
+        let client = AzureClient::with_endpoint(
+            &format!("http://{}", addr),
+            &az.display().to_string(),
+            Some(revoked),
+        );
+
+        let revisions = list_revisions(&client, &app_ref()).await.unwrap();
+        assert_eq!(revisions[0].name, "web--r1");
+        assert_eq!(call_count(&calls), 1);
+        assert!(client.get_json(&format!("{}/nope", APP)).await.is_err());
+        assert_eq!(call_count(&calls), 1);
+        // Absolute links are only followed on the management host
+        let next_link = format!("http://{}{}/revisions?api-version=2024-03-01", addr, APP);
+        assert!(client.get_json(&next_link).await.is_ok());
+        assert!(client
+            .get_json("https://evil.example/subscriptions")
+            .await
+            .is_err());
+        assert_eq!(call_count(&calls), 1);
+
+        let _ = std::fs::remove_dir_all(az.parent().unwrap());
+    }
+}
@@ -0,0 +1,531 @@
+use std::collections::HashSet;
+use std::convert::Infallible;
+use std::future::Future;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+
+use anyhow::Result;
+use http_body_util::{BodyExt, Full, Limited};
+use hyper::body::{Bytes, Incoming};
+use hyper::header::{HeaderMap, CONTENT_ENCODING, CONTENT_TYPE};
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper::{Method, StatusCode};
+use hyper_util::rt::TokioIo;
+use tokio::net::TcpListener;
+use tokio::sync::mpsc;
+use tokio::task::{JoinHandle, JoinSet};
+
+use super::{SourceEvent, SourceInfo, SourceStatus};
+use crate::parse::Level;
+
+/// Largest request body accepted by the built-in HTTP listeners.
+const MAX_BODY: usize = 8 * 1024 * 1024;
+
+/// Header naming the source a push belongs to (overridden by `/ingest/<name>`).
+pub const SOURCE_HEADER: &str = "x-logradar-source";
+
+/// A fully-read request handed to a listener's handler.
+pub(crate) struct HttpRequest {
+    pub method: Method,
+    pub path: String,
+    pub headers: HeaderMap,
+    pub peer: SocketAddr,
+    pub body: Bytes,
+}
+
+impl HttpRequest {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).and_then(|v| v.to_str().ok())
+    }
+
+    pub fn content_type(&self) -> Option<&str> {
+        self.header(CONTENT_TYPE.as_str())
+    }
+}
+
+pub(crate) struct HttpResponse {
+    pub status: StatusCode,
+    pub content_type: &'static str,
+    pub body: Vec<u8>,
+}
+
+impl HttpResponse {
+    pub fn empty(status: StatusCode) -> HttpResponse {
+        HttpResponse {
+            status,
+            content_type: "text/plain; charset=utf-8",
+            body: Vec::new(),
+        }
+    }
+
+    pub fn text(status: StatusCode, body: impl Into<String>) -> HttpResponse {
+        HttpResponse {
+            status,
+            content_type: "text/plain; charset=utf-8",
+            body: body.into().into_bytes(),
+        }
+    }
+
+    fn into_hyper(self) -> hyper::Response<Full<Bytes>> {
+        let mut resp = hyper::Response::new(Full::new(Bytes::from(self.body)));
+        *resp.status_mut() = self.status;
+        resp.headers_mut()
+            .insert(CONTENT_TYPE, hyper::header::HeaderValue::from_static(self.content_type));
+        resp
+    }
+}
+
+async fn read_request(
+    req: hyper::Request<Incoming>,
+    peer: SocketAddr,
+) -> Result<HttpRequest, HttpResponse> {
+    let (parts, body) = req.into_parts();
+    let body = match Limited::new(body, MAX_BODY).collect().await {
+        Ok(collected) => collected.to_bytes(),
+        Err(e) if e.is::<http_body_util::LengthLimitError>() => {
+            return Err(HttpResponse::text(
+                StatusCode::PAYLOAD_TOO_LARGE,
+                format!("body exceeds {} bytes", MAX_BODY),
+            ));
+        }
+        Err(e) => return Err(HttpResponse::text(StatusCode::BAD_REQUEST, e.to_string())),
+    };
+    let gzipped = parts
+        .headers
+        .get(CONTENT_ENCODING)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.eq_ignore_ascii_case("gzip"));
+    let body = if gzipped { gunzip(&body)? } else { body };
+    Ok(HttpRequest {
+        method: parts.method,
+        path: parts.uri.path().to_string(),
+        headers: parts.headers,
+        peer,
+        body,
+    })
+}
+
+/// Decompress a gzip body (OTLP exporters and log shippers compress by
+/// default), keeping the same size limit as uncompressed bodies.
+fn gunzip(body: &[u8]) -> Result<Bytes, HttpResponse> {
+    use std::io::Read;
+
+    let mut out = Vec::new();
+    flate2::read::GzDecoder::new(body)
+        .take(MAX_BODY as u64 + 1)
+        .read_to_end(&mut out)
+        .map_err(|e| HttpResponse::text(StatusCode::BAD_REQUEST, format!("gzip: {}", e)))?;
+    if out.len() > MAX_BODY {
+        return Err(HttpResponse::text(
+            StatusCode::PAYLOAD_TOO_LARGE,
+            format!("decompressed body exceeds {} bytes", MAX_BODY),
+        ));
+    }
+    Ok(Bytes::from(out))
+}
+
+/// Accept HTTP/1 connections on `listener` and answer each request with
+/// `handler`. Connection tasks are aborted when the returned future is dropped.
+pub(crate) async fn serve<H, Fut>(listener: TcpListener, handler: H) -> Result<()>
+where
+    H: Fn(HttpRequest) -> Fut + Clone + Send + Sync + 'static,
+    Fut: Future<Output = HttpResponse> + Send + 'static,
+{
+    let mut connections = JoinSet::new();
+    loop {
+        let (stream, peer) = listener.accept().await?;
+        while connections.try_join_next().is_some() {}
+        let handler = handler.clone();
+        connections.spawn(async move {
+            let service = service_fn(move |req: hyper::Request<Incoming>| {
+                let handler = handler.clone();
+                async move {
+                    let response = match read_request(req, peer).await {
+                        Ok(req) => handler(req).await,
+                        Err(resp) => resp,
+                    };
+                    Ok::<_, Infallible>(response.into_hyper())
+                }
+            });
+            let _ = http1::Builder::new()
+                .serve_connection(TokioIo::new(stream), service)
+                .await;
+        });
+    }
+}
+
+/// One pushed line, with its level when the sender supplied one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PushedLine {
+    pub line: String,
+    pub level: Option<Level>,
+}
+
+const MESSAGE_KEYS: [&str; 4] = ["message", "msg", "log", "line"];
+const LEVEL_KEYS: [&str; 4] = ["level", "severity", "lvl", "loglevel"];
+
+/// Reduce one JSON value to a line: the first message-like field of an
+/// object (or the whole object), or a bare string.
+fn json_line(value: &serde_json::Value) -> Option<PushedLine> {
+    match value {
+        serde_json::Value::String(s) => Some(PushedLine {
+            line: s.clone(),
+            level: None,
+        }),
+        serde_json::Value::Object(map) => {
+            let line = MESSAGE_KEYS
+                .iter()
+                .find_map(|k| map.get(*k).and_then(|v| v.as_str()))
+                .map(str::to_string)
+                .unwrap_or_else(|| value.to_string());
+            let level = LEVEL_KEYS
+                .iter()
+                .find_map(|k| map.get(*k).and_then(|v| v.as_str()))
+                .and_then(Level::from_name);
+            Some(PushedLine { line, level })
+        }
+        serde_json::Value::Null => None,
+        other => Some(PushedLine {
+            line: other.to_string(),
+            level: None,
+        }),
+    }
+}
+
+/// Parse an `/ingest` body. JSON content types accept a single value or an
+/// array; anything else is read line by line, with JSON-object lines
+/// (NDJSON) unpacked and other lines taken verbatim.
+pub fn parse_body(content_type: Option<&str>, body: &str) -> Result<Vec<PushedLine>> {
+    let ct = content_type.unwrap_or("").to_ascii_lowercase();
+    let is_ndjson = ct.contains("ndjson") || ct.contains("jsonl") || ct.contains("json-seq");
+    let trimmed = body.trim_start();
+    if (ct.contains("json") && !is_ndjson) || (ct.is_empty() && trimmed.starts_with('[')) {
+        let value: serde_json::Value = serde_json::from_str(body)
+            .map_err(|e| anyhow::anyhow!("invalid JSON body: {}", e))?;
+        return Ok(match value {
+            serde_json::Value::Array(items) => items.iter().filter_map(json_line).collect(),
+            other => json_line(&other).into_iter().collect(),
+        });
+    }
+    Ok(body
+        .lines()
+        .map(|l| l.trim_end_matches('\r'))
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| {
+            if l.trim_start().starts_with('{') {
+                if let Some(pushed) = serde_json::from_str(l).ok().as_ref().and_then(json_line) {
+                    return pushed;
+                }
+            }
+            PushedLine {
+                line: l.to_string(),
+                level: None,
+            }
+        })
+        .collect())
+}
+
+/// One Loki stream: its labels reduced to a source name plus its lines.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LokiStream {
+    pub name: Option<String>,
+    pub lines: Vec<PushedLine>,
+}
+
+const LOKI_NAME_LABELS: [&str; 5] = ["service_name", "app", "job", "container", "instance"];
+
+/// Parse a Loki push body (`{"streams":[{"stream":{..},"values":[[ts, line], ..]}]}`).
+pub fn parse_loki(body: &str) -> Result<Vec<LokiStream>> {
+    let json: serde_json::Value =
+        serde_json::from_str(body).map_err(|e| anyhow::anyhow!("invalid Loki push body: {}", e))?;
+    let streams = json
+        .get("streams")
+        .and_then(|s| s.as_array())
+        .ok_or_else(|| anyhow::anyhow!("Loki push body has no \"streams\" array"))?;
+
+    Ok(streams
+        .iter()
+        .map(|stream| {
+            let labels = stream.get("stream");
+            let label = |key: &str| labels.and_then(|l| l.get(key)).and_then(|v| v.as_str());
+            let name = LOKI_NAME_LABELS
+                .iter()
+                .find_map(|k| label(k))
+                .map(str::to_string);
+            let level = label("level")
+                .or_else(|| label("detected_level"))
+                .and_then(Level::from_name);
+            let lines = stream
+                .get("values")
+                .and_then(|v| v.as_array())
+                .map(|values| {
+                    values
+                        .iter()
+                        .filter_map(|v| v.get(1).and_then(|l| l.as_str()))
+                        .map(|line| PushedLine {
+                            line: line.to_string(),
+                            level,
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+            LokiStream { name, lines }
+        })
+        .collect())
+}
+
+/// Shared state of one ingest listener.
+#[derive(Clone)]
+struct Ingest {
+    tx: mpsc::Sender<SourceEvent>,
+    seen: Arc<Mutex<HashSet<String>>>,
+}
+
+impl Ingest {
+    async fn handle(&self, req: HttpRequest) -> HttpResponse {
+        if req.method != Method::POST {
+            return HttpResponse::text(StatusCode::METHOD_NOT_ALLOWED, "use POST");
+        }
+        let body = String::from_utf8_lossy(&req.body);
+        let header_name = req.header(SOURCE_HEADER).map(str::to_string);
+        let fallback = req.peer.ip().to_string();
+
+        let batches = if req.path == "/loki/api/v1/push" {
+            match parse_loki(&body) {
+                Ok(streams) => streams
+                    .into_iter()
+                    .map(|s| {
+                        let name = header_name.clone().or(s.name).unwrap_or(fallback.clone());
+                        (name, s.lines)
+                    })
+                    .collect(),
+                Err(e) => return HttpResponse::text(StatusCode::BAD_REQUEST, e.to_string()),
+            }
+        } else if req.path == "/ingest" || req.path.starts_with("/ingest/") {
+            let segment = req.path["/ingest".len()..].trim_matches('/');
+            let name = if segment.is_empty() {
+                header_name.unwrap_or(fallback)
+            } else {
+                segment.to_string()
+            };
+            match parse_body(req.content_type(), &body) {
+                Ok(lines) => vec![(name, lines)],
+                Err(e) => return HttpResponse::text(StatusCode::BAD_REQUEST, e.to_string()),
+            }
+        } else {
+            return HttpResponse::text(
+                StatusCode::NOT_FOUND,
+                "POST to /ingest, /ingest/<source> or /loki/api/v1/push",
+            );
+        };
+
+        for (name, lines) in batches {
+            if !self.deliver(&name, lines).await {
+                return HttpResponse::text(StatusCode::SERVICE_UNAVAILABLE, "shutting down");
+            }
+        }
+        HttpResponse::empty(StatusCode::NO_CONTENT)
+    }
+
+    /// Forward one batch; returns false once the receiver is gone.
+    async fn deliver(&self, name: &str, lines: Vec<PushedLine>) -> bool {
+        if lines.is_empty() {
+            return true;
+        }
+        let source = format!("http/{}", name);
+        let is_new = self.seen.lock().unwrap().insert(source.clone());
+        if is_new {
+            let _ = self
+                .tx
+                .send(SourceEvent::Attached {
+                    info: SourceInfo {
+                        id: source.clone(),
+                        kind: "http".into(),
+                        status: SourceStatus::Running,
+                    },
+                })
+                .await;
+        }
+        for pushed in lines {
+            let event = match pushed.level {
+                Some(level) => SourceEvent::Record {
+                    source: source.clone(),
+                    line: pushed.line,
+                    level: Some(level),
+                    fields: Vec::new(),
+                },
+                None => SourceEvent::Log {
+                    source: source.clone(),
+                    line: pushed.line,
+                },
+            };
+            if self.tx.send(event).await.is_err() {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+pub fn spawn_http(addr: SocketAddr, tx: mpsc::Sender<SourceEvent>) -> (SourceInfo, JoinHandle<()>) {
+    let id = format!("http/{}", addr);
+    let info = SourceInfo {
+        id: id.clone(),
+        kind: "http".into(),
+        status: SourceStatus::Starting,
+    };
+    let handle = tokio::spawn(async move {
+        if let Err(e) = run_http(addr, &id, tx.clone()).await {
+            let _ = tx
+                .send(SourceEvent::Status {
+                    source: id,
+                    status: SourceStatus::Error(format!("http: {}", e)),
+                })
+                .await;
+        }
+    });
+    (info, handle)
+}
+
+async fn run_http(addr: SocketAddr, source_id: &str, tx: mpsc::Sender<SourceEvent>) -> Result<()> {
+    let listener = TcpListener::bind(addr).await?;
+    let _ = tx
+        .send(SourceEvent::Status {
+            source: source_id.to_string(),
+            status: SourceStatus::Running,
+        })
+        .await;
+
+    let ingest = Ingest {
+        tx,
+        seen: Arc::new(Mutex::new(HashSet::new())),
+    };
+    serve(listener, move |req| {
+        let ingest = ingest.clone();
+        async move { ingest.handle(req).await }
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    #[test]
+    fn parse_plain_text_lines() {
+        let lines = parse_body(Some("text/plain"), "first line\r\n\nsecond line\n").unwrap();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].line, "first line");
+        assert_eq!(lines[1].level, None);
+    }
+
+    #[test]
+    fn parse_ndjson_lines() {
+        let body = "{\"msg\":\"db timeout\",\"level\":\"error\"}\n{\"ts\":1}\nnot json {\n";
+        let lines = parse_body(Some("application/x-ndjson"), body).unwrap();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0].line, "db timeout");
+        assert_eq!(lines[0].level, Some(Level::Error));
+        // Objects without a message field are kept whole
+        assert_eq!(lines[1].line, "{\"ts\":1}");
+        assert_eq!(lines[2].line, "not json {");
+    }
+
+    #[test]
+    fn parse_json_array() {
+        let body = r#"[{"message":"ok","severity":"warning"},"plain",null]"#;
+        let lines = parse_body(Some("application/json"), body).unwrap();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].level, Some(Level::Warn));
+        assert_eq!(lines[1].line, "plain");
+        // Arrays are detected without a content type too
+        assert_eq!(parse_body(None, body).unwrap().len(), 2);
+        assert!(parse_body(Some("application/json"), "[oops").is_err());
+    }
+
+    #[test]
+    fn parse_loki_push() {
+        let body = r#"{"streams":[
+            {"stream":{"job":"api","level":"error"},"values":[["1700000000000000000","boom"],["1700000000000000001","again"]]},
+            {"stream":{"env":"dev"},"values":[["1700000000000000002","hello",{"trace_id":"abc"}]]}
+        ]}"#;
+        let streams = parse_loki(body).unwrap();
+        assert_eq!(streams.len(), 2);
+        assert_eq!(streams[0].name.as_deref(), Some("api"));
+        assert_eq!(streams[0].lines.len(), 2);
+        assert_eq!(streams[0].lines[0].level, Some(Level::Error));
+        assert_eq!(streams[1].name, None);
+        assert_eq!(streams[1].lines[0].line, "hello");
+        assert!(parse_loki("{}").is_err());
+    }
+
+    async fn post(addr: SocketAddr, path: &str, headers: &str, body: &str) -> String {
+        let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+        let req = format!(
+            "POST {} HTTP/1.1\r\nHost: test\r\nConnection: close\r\n{}Content-Length: {}\r\n\r\n{}",
+            path,
+            headers,
+            body.len(),
+            body
+        );
+        stream.write_all(req.as_bytes()).await.unwrap();
+        let mut resp = String::new();
+        stream.read_to_string(&mut resp).await.unwrap();
+        resp
+    }
+
+    #[tokio::test]
+    async fn http_listener_feeds_sources() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (tx, mut rx) = mpsc::channel(64);
+        let ingest = Ingest {
+            tx,
+            seen: Arc::new(Mutex::new(HashSet::new())),
+        };
+        let server = tokio::spawn(serve(listener, move |req| {
+            let ingest = ingest.clone();
+            async move { ingest.handle(req).await }
+        }));
+
+        let resp = post(addr, "/ingest/billing", "", "charge ok\ncharge failed\n").await;
+        assert!(resp.starts_with("HTTP/1.1 204"), "{}", resp);
+        let resp = post(
+            addr,
+            "/ingest",
+            "X-Logradar-Source: worker\r\nContent-Type: application/json\r\n",
+            r#"[{"msg":"job done","level":"info"}]"#,
+        )
+        .await;
+        assert!(resp.starts_with("HTTP/1.1 204"), "{}", resp);
+        let resp = post(addr, "/nope", "", "x").await;
+        assert!(resp.starts_with("HTTP/1.1 404"), "{}", resp);
+
+        let mut attached = Vec::new();
+        let mut lines = Vec::new();
+        while let Ok(ev) = rx.try_recv() {
+            match ev {
+                SourceEvent::Attached { info } => attached.push(info.id),
+                SourceEvent::Log { source, line } => lines.push((source, line, None)),
+                SourceEvent::Record {
+                    source,
+                    line,
+                    level,
+                    ..
+                } => lines.push((source, line, level)),
+                _ => {}
+            }
+        }
+        assert_eq!(attached, vec!["http/billing", "http/worker"]);
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[1], ("http/billing".to_string(), "charge failed".to_string(), None));
+        assert_eq!(
+            lines[2],
+            ("http/worker".to_string(), "job done".to_string(), Some(Level::Info))
+        );
+
+        server.abort();
+    }
+}
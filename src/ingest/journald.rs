@@ -0,0 +1,348 @@
+use std::collections::HashSet;
+
+use anyhow::Result;
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::process::Command;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use super::{SourceEvent, SourceInfo, SourceStatus};
+use crate::parse::Level;
+
+/// Filters passed through to `journalctl`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct JournalFilter {
+    /// `--unit` values; empty follows the whole journal.
+    pub units: Vec<String>,
+    /// `--priority` (e.g. `warning` or `0..4`).
+    pub priority: Option<String>,
+    /// `--since` (e.g. `-1h` or `2026-01-01 00:00`).
+    pub since: Option<String>,
+}
+
+impl JournalFilter {
+    /// Label used in the supervisor's source id.
+    pub fn label(&self) -> String {
+        if self.units.is_empty() {
+            "*".to_string()
+        } else {
+            self.units.join(",")
+        }
+    }
+
+    /// `journald:<label>`. The `:` keeps it distinct from the `journald/<unit>`
+    /// rows it attaches, even when the filter names a single unit.
+    pub fn source_id(&self) -> String {
+        format!("journald:{}", self.label())
+    }
+
+    fn args(&self) -> Vec<String> {
+        self.resume_args(None)
+    }
+
+    /// `journalctl` arguments; with a cursor, resume right after that entry
+    /// instead of replaying the backlog (used when a remote follow reconnects).
+    pub(crate) fn resume_args(&self, cursor: Option<&str>) -> Vec<String> {
+        let mut args = vec!["-o".to_string(), "json".to_string(), "-f".to_string()];
+        for unit in &self.units {
+            args.push("--unit".into());
+            args.push(unit.clone());
+        }
+        if let Some(ref p) = self.priority {
+            args.push("--priority".into());
+            args.push(p.clone());
+        }
+        if let Some(cursor) = cursor {
+            args.push(format!("--after-cursor={}", cursor));
+            return args;
+        }
+        match self.since {
+            Some(ref since) => {
+                args.push("--since".into());
+                args.push(since.clone());
+            }
+            None => {
+                args.push("-n".into());
+                args.push("100".into());
+            }
+        }
+        args
+    }
+}
+
+/// One journal entry reduced to what the pattern store needs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JournalEntry {
+    pub unit: String,
+    pub level: Level,
+    pub message: String,
+    /// `__CURSOR`, for resuming a follow after a reconnect.
+    pub cursor: Option<String>,
+}
+
+/// The journalctl binary to invoke. `LOGRADAR_JOURNALCTL` overrides it so a
+/// stub can replay recorded journal JSON.
+pub fn journalctl_bin() -> String {
+    std::env::var("LOGRADAR_JOURNALCTL").unwrap_or_else(|_| "journalctl".to_string())
+}
+
+/// Journal fields are strings, but non-UTF-8 payloads are exported as byte arrays.
+fn field_str(entry: &serde_json::Value, key: &str) -> Option<String> {
+    match entry.get(key)? {
+        serde_json::Value::String(s) => Some(s.clone()),
+        serde_json::Value::Array(bytes) => {
+            let raw: Vec<u8> = bytes
+                .iter()
+                .filter_map(|b| b.as_u64().map(|b| b as u8))
+                .collect();
+            Some(String::from_utf8_lossy(&raw).into_owned())
+        }
+        _ => None,
+    }
+}
+
+/// Parse one line of `journalctl -o json` output.
+pub fn parse_entry(line: &str) -> Option<JournalEntry> {
+    let json: serde_json::Value = serde_json::from_str(line).ok()?;
+    let message = field_str(&json, "MESSAGE")?;
+    let level = field_str(&json, "PRIORITY")
+        .and_then(|p| p.trim().parse::<u8>().ok())
+        .map(Level::from_syslog_severity)
+        .unwrap_or(Level::Unknown);
+    let unit = field_str(&json, "_SYSTEMD_UNIT")
+        .or_else(|| field_str(&json, "SYSLOG_IDENTIFIER"))
+        .or_else(|| field_str(&json, "_COMM"))
+        .unwrap_or_else(|| "journal".to_string());
+    Some(JournalEntry {
+        unit,
+        level,
+        message,
+        cursor: field_str(&json, "__CURSOR"),
+    })
+}
+
+pub fn spawn_journald(
+    filter: JournalFilter,
+    tx: mpsc::Sender<SourceEvent>,
+) -> (SourceInfo, JoinHandle<()>) {
+    let id = filter.source_id();
+    let info = SourceInfo {
+        id: id.clone(),
+        kind: "journald".into(),
+        status: SourceStatus::Starting,
+    };
+    let handle = tokio::spawn(async move {
+        let _ = run_journald(&journalctl_bin(), &filter, &id, tx).await;
+    });
+    (info, handle)
+}
+
+/// Follow `journalctl -o json -f`, attaching one source per systemd unit as
+/// units first appear.
+async fn run_journald(
+    journalctl: &str,
+    filter: &JournalFilter,
+    source_id: &str,
+    tx: mpsc::Sender<SourceEvent>,
+) -> Result<()> {
+    let result = Command::new(journalctl)
+        .args(filter.args())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::null())
+        .kill_on_drop(true)
+        .spawn();
+
+    let mut child = match result {
+        Ok(child) => {
+            let _ = tx.send(SourceEvent::Status {
+                source: source_id.to_string(),
+                status: SourceStatus::Running,
+            }).await;
+            child
+        }
+        Err(e) => {
+            let _ = tx.send(SourceEvent::Status {
+                source: source_id.to_string(),
+                status: SourceStatus::Error(format!("journalctl: {}", e)),
+            }).await;
+            return Err(e.into());
+        }
+    };
+
+    let mut seen_units: HashSet<String> = HashSet::new();
+
+    if let Some(stdout) = child.stdout.take() {
+        let mut lines = BufReader::new(stdout).lines();
+        loop {
+            let line = match lines.next_line().await {
+                Ok(Some(line)) => line,
+                Ok(None) => break,
+                Err(e) => {
+                    let _ = tx.send(SourceEvent::Status {
+                        source: source_id.to_string(),
+                        status: SourceStatus::Error(format!("journalctl: {}", e)),
+                    }).await;
+                    return Err(e.into());
+                }
+            };
+            let Some(entry) = parse_entry(&line) else {
+                continue;
+            };
+            let unit_id = format!("journald/{}", entry.unit);
+            if seen_units.insert(entry.unit) {
+                let _ = tx
+                    .send(SourceEvent::Attached {
+                        info: SourceInfo {
+                            id: unit_id.clone(),
+                            kind: "journald".into(),
+                            status: SourceStatus::Running,
+                        },
+                    })
+                    .await;
+            }
+            if tx
+                .send(SourceEvent::Record {
+                    source: unit_id,
+                    line: entry.message,
+                    level: Some(entry.level),
+                    fields: Vec::new(),
+                })
+                .await
+                .is_err()
+            {
+                break;
+            }
+        }
+    }
+
+    let _ = tx.send(SourceEvent::Status {
+        source: source_id.to_string(),
+        status: SourceStatus::Stopped,
+    }).await;
+
+    let _ = child.wait().await;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Recorded from `journalctl -o json` (trimmed to the fields we read)
+    const RECORDED: &str = r#"{"__REALTIME_TIMESTAMP":"1760000000000000","PRIORITY":"6","_SYSTEMD_UNIT":"nginx.service","SYSLOG_IDENTIFIER":"nginx","MESSAGE":"started worker process 4242"}
+{"__REALTIME_TIMESTAMP":"1760000000100000","PRIORITY":"3","_SYSTEMD_UNIT":"postgresql.service","MESSAGE":"FATAL:  password authentication failed"}
+{"__REALTIME_TIMESTAMP":"1760000000200000","PRIORITY":"4","SYSLOG_IDENTIFIER":"kernel","MESSAGE":"TCP: request_sock_TCP: Possible SYN flooding"}
+{"__REALTIME_TIMESTAMP":"1760000000300000","PRIORITY":"7","_SYSTEMD_UNIT":"app.service","MESSAGE":[104,105,255]}
+not json at all"#;
+
+    #[test]
+    fn parse_recorded_journal() {
+        let entries: Vec<JournalEntry> = RECORDED.lines().filter_map(parse_entry).collect();
+        assert_eq!(entries.len(), 4);
+        assert_eq!(entries[0].unit, "nginx.service");
+        assert_eq!(entries[0].level, Level::Info);
+        assert_eq!(entries[1].level, Level::Error);
+        // Falls back to SYSLOG_IDENTIFIER when there is no unit
+        assert_eq!(entries[2].unit, "kernel");
+        assert_eq!(entries[2].level, Level::Warn);
+        // Byte-array MESSAGE is decoded lossily
+        assert_eq!(entries[3].message, "hi\u{fffd}");
+        assert_eq!(entries[3].level, Level::Debug);
+    }
+
+    #[test]
+    fn filter_args() {
+        let f = JournalFilter {
+            units: vec!["nginx.service".into(), "app.service".into()],
+            priority: Some("warning".into()),
+            since: Some("-1h".into()),
+        };
+        assert_eq!(
+            f.args(),
+            vec![
+                "-o", "json", "-f", "--unit", "nginx.service", "--unit", "app.service",
+                "--priority", "warning", "--since", "-1h",
+            ]
+        );
+        assert_eq!(f.label(), "nginx.service,app.service");
+        assert_eq!(JournalFilter::default().label(), "*");
+        let one = JournalFilter {
+            units: vec!["nginx.service".into()],
+            ..JournalFilter::default()
+        };
+        assert_eq!(one.source_id(), "journald:nginx.service");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn follows_recorded_journal_per_unit() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join(format!("logradar-journalctl-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let recorded = dir.join("journal.json");
+        std::fs::write(&recorded, RECORDED).unwrap();
+        let stub = dir.join("journalctl");
+        std::fs::write(&stub, format!("#!/bin/sh\ncat '{}'\n", recorded.display())).unwrap();
+        std::fs::set_permissions(&stub, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let (tx, mut rx) = mpsc::channel(64);
+        run_journald(
+            &stub.display().to_string(),
+            &JournalFilter::default(),
+            "journald:*",
+            tx,
+        )
+        .await
+        .unwrap();
+
+        let mut attached = Vec::new();
+        let mut records = Vec::new();
+        while let Ok(ev) = rx.try_recv() {
+            match ev {
+                SourceEvent::Attached { info } => attached.push(info.id),
+                SourceEvent::Record { source, level, .. } => records.push((source, level)),
+                _ => {}
+            }
+        }
+        assert_eq!(
+            attached,
+            vec![
+                "journald/nginx.service",
+                "journald/postgresql.service",
+                "journald/kernel",
+                "journald/app.service",
+            ]
+        );
+        assert_eq!(records.len(), 4);
+        assert_eq!(records[1], ("journald/postgresql.service".to_string(), Some(Level::Error)));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn read_error_reports_a_status() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join(format!("logradar-journalctl-bad-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let stub = dir.join("journalctl");
+        // Not UTF-8, so reading the line fails
+        std::fs::write(&stub, "#!/bin/sh\nprintf '\\377\\n'\n").unwrap();
+        std::fs::set_permissions(&stub, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let (tx, mut rx) = mpsc::channel(64);
+        let result = run_journald(&stub.display().to_string(), &JournalFilter::default(), "journald:*", tx).await;
+        let _ = std::fs::remove_dir_all(&dir);
+        assert!(result.is_err());
+
+        let mut last = None;
+        while let Ok(ev) = rx.try_recv() {
+            if let SourceEvent::Status { status, .. } = ev {
+                last = Some(status);
+            }
+        }
+        assert!(matches!(last, Some(SourceStatus::Error(_))), "{:?}", last);
+    }
+}
@@ -0,0 +1,534 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::Result;
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::process::Command;
+use tokio::sync::mpsc;
+use tokio::task::{AbortHandle, JoinHandle, JoinSet};
+
+use super::{SourceEvent, SourceInfo, SourceStatus};
+
+/// How often the supervisor re-lists pods to pick up churn.
+const POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Which pods a Kubernetes source follows.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KubeSelector {
+    pub namespace: String,
+    /// Label selector (`app=api,tier=web`). `None` with no `pod` = whole namespace.
+    pub labels: Option<String>,
+    /// Follow a single pod by name instead of a label selector.
+    pub pod: Option<String>,
+}
+
+impl KubeSelector {
+    /// Parse `namespace/selector` from the CLI. A selector containing `=` is a
+    /// label selector, anything else is a pod name. A bare selector uses the
+    /// `default` namespace; `namespace/` follows the whole namespace.
+    pub fn parse(spec: &str) -> KubeSelector {
+        let (namespace, rest) = match spec.split_once('/') {
+            Some((ns, rest)) => (ns.to_string(), rest),
+            None => ("default".to_string(), spec),
+        };
+        let rest = rest.trim();
+        if rest.is_empty() {
+            KubeSelector { namespace, labels: None, pod: None }
+        } else if rest.contains('=') {
+            KubeSelector { namespace, labels: Some(rest.to_string()), pod: None }
+        } else {
+            KubeSelector { namespace, labels: None, pod: Some(rest.to_string()) }
+        }
+    }
+
+    /// The `--k8s` spec this selector parses from.
+    pub fn spec(&self) -> String {
+        let selector = self.pod.as_ref().or(self.labels.as_ref()).map_or("", String::as_str);
+        format!("{}/{}", self.namespace, selector)
+    }
+
+    /// Human-readable label used in the supervisor's source id.
+    pub fn label(&self) -> String {
+        match (&self.pod, &self.labels) {
+            (Some(pod), _) => format!("{}/{}", self.namespace, pod),
+            (None, Some(labels)) => format!("{}/{}", self.namespace, labels),
+            (None, None) => format!("{}/*", self.namespace),
+        }
+    }
+
+    fn list_args(&self) -> Vec<String> {
+        let mut args = vec![
+            "get".to_string(),
+            "pods".to_string(),
+            "-n".to_string(),
+            self.namespace.clone(),
+            "-o".to_string(),
+            "json".to_string(),
+        ];
+        if let Some(ref pod) = self.pod {
+            args.push("--field-selector".into());
+            args.push(format!("metadata.name={}", pod));
+        } else if let Some(ref labels) = self.labels {
+            args.push("-l".into());
+            args.push(labels.clone());
+        }
+        args
+    }
+}
+
+/// A running container that can be followed with `kubectl logs -f`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PodContainer {
+    pub namespace: String,
+    pub pod: String,
+    pub container: String,
+}
+
+impl PodContainer {
+    pub fn source_id(&self) -> String {
+        format!("k8s/{}/{}/{}", self.namespace, self.pod, self.container)
+    }
+}
+
+/// The kubectl binary to invoke. `LOGRADAR_KUBECTL` overrides it so a stub
+/// can stand in for a real cluster.
+pub fn kubectl_bin() -> String {
+    std::env::var("LOGRADAR_KUBECTL").unwrap_or_else(|_| "kubectl".to_string())
+}
+
+/// Extract running pod/container pairs from `kubectl get pods -o json`.
+/// Pods without a `metadata.namespace` are taken to be in `namespace`.
+pub fn parse_running_containers(json: &serde_json::Value, namespace: &str) -> Vec<PodContainer> {
+    let mut out = Vec::new();
+    let items = json.get("items").and_then(|v| v.as_array());
+    for item in items.into_iter().flatten() {
+        let Some(pod) = item.pointer("/metadata/name").and_then(|v| v.as_str()) else {
+            continue;
+        };
+        let phase = item
+            .pointer("/status/phase")
+            .and_then(|v| v.as_str())
+            .unwrap_or("");
+        if phase != "Running" {
+            continue;
+        }
+        // Pods being deleted still report Running until they're gone
+        if item.pointer("/metadata/deletionTimestamp").is_some() {
+            continue;
+        }
+        let pod_namespace = item
+            .pointer("/metadata/namespace")
+            .and_then(|v| v.as_str())
+            .unwrap_or(namespace);
+        let containers = item.pointer("/spec/containers").and_then(|v| v.as_array());
+        for c in containers.into_iter().flatten() {
+            if let Some(name) = c.get("name").and_then(|v| v.as_str()) {
+                out.push(PodContainer {
+                    namespace: pod_namespace.to_string(),
+                    pod: pod.to_string(),
+                    container: name.to_string(),
+                });
+            }
+        }
+    }
+    out
+}
+
+pub fn spawn_kubernetes(
+    selector: KubeSelector,
+    tx: mpsc::Sender<SourceEvent>,
+) -> (SourceInfo, JoinHandle<()>) {
+    let id = format!("k8s/{}", selector.label());
+    let info = SourceInfo {
+        id: id.clone(),
+        kind: "kubernetes".into(),
+        status: SourceStatus::Starting,
+    };
+    let handle = tokio::spawn(async move {
+        let _ = run_kubernetes(&kubectl_bin(), &selector, &id, tx, POLL_INTERVAL).await;
+    });
+    (info, handle)
+}
+
+/// Supervisor: list pods on an interval, follow each running container as its
+/// own source, and stop followers whose pods have gone away. Followers are
+/// aborted (killing their kubectl children) when the supervisor is aborted.
+async fn run_kubernetes(
+    kubectl: &str,
+    selector: &KubeSelector,
+    source_id: &str,
+    tx: mpsc::Sender<SourceEvent>,
+    poll_interval: Duration,
+) -> Result<()> {
+    // Dropping the JoinSet aborts every follower (and its kubectl child)
+    // when the supervisor itself is aborted.
+    let mut tasks = JoinSet::new();
+    let mut followers: HashMap<PodContainer, AbortHandle> = HashMap::new();
+    let mut reported_running = false;
+
+    loop {
+        match list_pods(kubectl, selector).await {
+            Ok(running) => {
+                if !reported_running {
+                    let _ = tx
+                        .send(SourceEvent::Status {
+                            source: source_id.to_string(),
+                            status: SourceStatus::Running,
+                        })
+                        .await;
+                    reported_running = true;
+                }
+                reconcile(kubectl, &running, &mut followers, &mut tasks, &tx, poll_interval).await;
+            }
+            Err(e) => {
+                let _ = tx
+                    .send(SourceEvent::Status {
+                        source: source_id.to_string(),
+                        status: SourceStatus::Error(format!("kubectl: {}", e)),
+                    })
+                    .await;
+                reported_running = false;
+            }
+        }
+        while tasks.try_join_next().is_some() {}
+        if tx.is_closed() {
+            break;
+        }
+        tokio::time::sleep(poll_interval).await;
+    }
+    Ok(())
+}
+
+async fn reconcile(
+    kubectl: &str,
+    running: &[PodContainer],
+    followers: &mut HashMap<PodContainer, AbortHandle>,
+    tasks: &mut JoinSet<()>,
+    tx: &mpsc::Sender<SourceEvent>,
+    poll_interval: Duration,
+) {
+    let wanted: HashSet<&PodContainer> = running.iter().collect();
+
+    // Pods that disappeared
+    let gone: Vec<PodContainer> = followers
+        .keys()
+        .filter(|pc| !wanted.contains(pc))
+        .cloned()
+        .collect();
+    for pc in gone {
+        if let Some(handle) = followers.remove(&pc) {
+            handle.abort();
+        }
+        let _ = tx
+            .send(SourceEvent::Status {
+                source: pc.source_id(),
+                status: SourceStatus::Stopped,
+            })
+            .await;
+    }
+
+    for pc in running {
+        let since = match followers.get(pc) {
+            None => None,
+            // Stream ended while the pod is still up (container restart):
+            // resume from roughly when it could have ended.
+            Some(handle) if handle.is_finished() => Some(poll_interval.as_secs() + 1),
+            Some(_) => continue,
+        };
+        if since.is_none() {
+            let _ = tx
+                .send(SourceEvent::Attached {
+                    info: SourceInfo {
+                        id: pc.source_id(),
+                        kind: "kubernetes".into(),
+                        status: SourceStatus::Starting,
+                    },
+                })
+                .await;
+        }
+        let handle = tasks.spawn(follow_container(
+            kubectl.to_string(),
+            pc.clone(),
+            since,
+            tx.clone(),
+        ));
+        followers.insert(pc.clone(), handle);
+    }
+}
+
+async fn list_pods(kubectl: &str, selector: &KubeSelector) -> Result<Vec<PodContainer>> {
+    let output = Command::new(kubectl)
+        .args(selector.list_args())
+        .output()
+        .await?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow::anyhow!("get pods failed: {}", stderr.trim()));
+    }
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    Ok(parse_running_containers(&json, &selector.namespace))
+}
+
+async fn follow_container(
+    kubectl: String,
+    pc: PodContainer,
+    since_secs: Option<u64>,
+    tx: mpsc::Sender<SourceEvent>,
+) {
+    let source_id = pc.source_id();
+    let mut args = vec![
+        "logs".to_string(),
+        "-f".to_string(),
+        "-n".to_string(),
+        pc.namespace.clone(),
+        pc.pod.clone(),
+        "-c".to_string(),
+        pc.container.clone(),
+    ];
+    match since_secs {
+        Some(s) => args.push(format!("--since={}s", s)),
+        None => args.push("--tail=100".to_string()),
+    }
+
+    let result = Command::new(&kubectl)
+        .args(&args)
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .kill_on_drop(true)
+        .spawn();
+
+    let mut child = match result {
+        Ok(child) => {
+            let _ = tx
+                .send(SourceEvent::Status {
+                    source: source_id.clone(),
+                    status: SourceStatus::Running,
+                })
+                .await;
+            child
+        }
+        Err(e) => {
+            let _ = tx
+                .send(SourceEvent::Status {
+                    source: source_id,
+                    status: SourceStatus::Error(format!("kubectl: {}", e)),
+                })
+                .await;
+            return;
+        }
+    };
+
+    // Keep the last stderr line: kubectl explains RBAC or readiness failures there
+    let last_err = Arc::new(Mutex::new(None::<String>));
+    let stderr_task = child.stderr.take().map(|err| {
+        let last_err = last_err.clone();
+        tokio::spawn(async move {
+            let mut lines = BufReader::new(err).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                if !line.trim().is_empty() {
+                    *last_err.lock().unwrap() = Some(line.trim().to_string());
+                }
+            }
+        })
+    });
+
+    if let Some(stdout) = child.stdout.take() {
+        let mut lines = BufReader::new(stdout).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            if tx
+                .send(SourceEvent::Log {
+                    source: source_id.clone(),
+                    line,
+                })
+                .await
+                .is_err()
+            {
+                return;
+            }
+        }
+    }
+
+    let exit = child.wait().await;
+    if let Some(task) = stderr_task {
+        let _ = task.await;
+    }
+    let reason = last_err.lock().unwrap().take();
+    let status = match exit {
+        Ok(s) if s.success() => SourceStatus::Stopped,
+        Ok(s) => SourceStatus::Error(reason.unwrap_or_else(|| match s.code() {
+            Some(code) => format!("kubectl logs: exited with status {}", code),
+            None => "kubectl logs: terminated".to_string(),
+        })),
+        Err(e) => SourceStatus::Error(format!("kubectl: {}", e)),
+    };
+    let _ = tx
+        .send(SourceEvent::Status {
+            source: source_id,
+            status,
+        })
+        .await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    fn pods_json() -> serde_json::Value {
+        serde_json::json!({
+            "items": [
+                {
+                    "metadata": {"name": "api-1"},
+                    "spec": {"containers": [{"name": "web"}, {"name": "sidecar"}]},
+                    "status": {"phase": "Running"}
+                },
+                {
+                    "metadata": {"name": "api-2"},
+                    "spec": {"containers": [{"name": "web"}]},
+                    "status": {"phase": "Pending"}
+                },
+                {
+                    "metadata": {"name": "api-3", "deletionTimestamp": "2026-01-01T00:00:00Z"},
+                    "spec": {"containers": [{"name": "web"}]},
+                    "status": {"phase": "Running"}
+                }
+            ]
+        })
+    }
+
+    #[test]
+    fn parse_selector_labels() {
+        let s = KubeSelector::parse("prod/app=api");
+        assert_eq!(s.namespace, "prod");
+        assert_eq!(s.labels.as_deref(), Some("app=api"));
+        assert!(s.pod.is_none());
+        assert_eq!(s.label(), "prod/app=api");
+    }
+
+    #[test]
+    fn parse_selector_pod_and_default_namespace() {
+        let s = KubeSelector::parse("api-7f9c");
+        assert_eq!(s.namespace, "default");
+        assert_eq!(s.pod.as_deref(), Some("api-7f9c"));
+    }
+
+    #[test]
+    fn parse_selector_whole_namespace() {
+        let s = KubeSelector::parse("kube-system/");
+        assert_eq!(s.namespace, "kube-system");
+        assert!(s.labels.is_none() && s.pod.is_none());
+        assert_eq!(s.label(), "kube-system/*");
+    }
+
+    #[test]
+    fn running_containers_skip_pending_and_terminating() {
+        let pcs = parse_running_containers(&pods_json(), "prod");
+        assert_eq!(pcs.len(), 2);
+        assert_eq!(pcs[0].source_id(), "k8s/prod/api-1/web");
+        assert_eq!(pcs[1].source_id(), "k8s/prod/api-1/sidecar");
+
+        // Same pod name in another namespace gets its own id
+        let staging = parse_running_containers(&pods_json(), "staging");
+        assert_eq!(staging[0].source_id(), "k8s/staging/api-1/web");
+    }
+
+    /// Write an executable kubectl stub that serves the pod list from
+    /// `pods.json` next to it and echoes a line per `logs` call.
+    #[cfg(unix)]
+    fn write_stub(dir: &std::path::Path) -> String {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::create_dir_all(dir).unwrap();
+        let script = dir.join("kubectl");
+        let body = format!(
+            "#!/bin/sh\nif [ \"$1\" = get ]; then cat '{}'; exit 0; fi\n\
+             if [ \"$1\" = logs ]; then echo \"hello from $5/$7\"; sleep 30; fi\n",
+            dir.join("pods.json").display()
+        );
+        std::fs::write(&script, body).unwrap();
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+        script.display().to_string()
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn supervisor_follows_pods_and_handles_churn() {
+        let dir = std::env::temp_dir().join(format!("logradar-kubectl-{}", std::process::id()));
+        let kubectl = write_stub(&dir);
+        std::fs::write(dir.join("pods.json"), pods_json().to_string()).unwrap();
+
+        let (tx, mut rx) = mpsc::channel(64);
+        let selector = KubeSelector::parse("prod/app=api");
+        let task = tokio::spawn(async move {
+            let _ = run_kubernetes(&kubectl, &selector, "k8s/prod/app=api", tx, Duration::from_millis(200)).await;
+        });
+
+        let mut attached = HashSet::new();
+        let mut logs = HashSet::new();
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while (attached.len() < 2 || logs.len() < 2) && Instant::now() < deadline {
+            match tokio::time::timeout(Duration::from_secs(1), rx.recv()).await {
+                Ok(Some(SourceEvent::Attached { info })) => {
+                    attached.insert(info.id);
+                }
+                Ok(Some(SourceEvent::Log { source, line })) => {
+                    assert!(line.starts_with("hello from api-1/"));
+                    logs.insert(source);
+                }
+                _ => {}
+            }
+        }
+        assert!(attached.contains("k8s/prod/api-1/web"));
+        assert!(attached.contains("k8s/prod/api-1/sidecar"));
+        assert_eq!(logs, attached);
+
+        // Pod goes away: both followers should be reported stopped
+        std::fs::write(dir.join("pods.json"), r#"{"items": []}"#).unwrap();
+        let mut stopped = HashSet::new();
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while stopped.len() < 2 && Instant::now() < deadline {
+            if let Ok(Some(SourceEvent::Status { source, status: SourceStatus::Stopped })) =
+                tokio::time::timeout(Duration::from_secs(1), rx.recv()).await
+            {
+                stopped.insert(source);
+            }
+        }
+        assert_eq!(stopped, attached);
+
+        task.abort();
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn failed_logs_call_reports_kubectls_error() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = std::env::temp_dir().join(format!("logradar-kubectl-forbidden-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let kubectl = dir.join("kubectl");
+        std::fs::write(
+            &kubectl,
+            "#!/bin/sh\necho 'Error from server (Forbidden): pods \"api-1\" is forbidden' >&2\nexit 1\n",
+        )
+        .unwrap();
+        std::fs::set_permissions(&kubectl, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let (tx, mut rx) = mpsc::channel(8);
+        let pc = PodContainer {
+            namespace: "prod".into(),
+            pod: "api-1".into(),
+            container: "web".into(),
+        };
+        follow_container(kubectl.display().to_string(), pc, None, tx).await;
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let mut last = None;
+        while let Ok(SourceEvent::Status { status, .. }) = rx.try_recv() {
+            last = Some(status);
+        }
+        match last {
+            Some(SourceStatus::Error(reason)) => assert!(reason.contains("Forbidden"), "{}", reason),
+            other => panic!("expected an error status, got {:?}", other),
+        }
+    }
+}
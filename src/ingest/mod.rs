@@ -1,9 +1,25 @@
-use anyhow::Result;
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
 use tokio::io::{AsyncBufReadExt, BufReader};
 use tokio::process::Command;
 use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+use crate::parse::{Fields, Level};
+use crate::session::SessionAction;
+
+pub mod azure;
+pub mod http;
+pub mod journald;
+pub mod kubernetes;
+pub mod otlp;
+pub mod ssh;
+pub mod syslog;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum SourceStatus {
     Starting,
     Running,
@@ -12,25 +28,140 @@ pub enum SourceStatus {
 }
 
 impl SourceStatus {
-    #[allow(dead_code)]
     pub fn is_active(&self) -> bool {
         matches!(self, SourceStatus::Starting | SourceStatus::Running)
     }
 }
 
+/// Everything a source reports. Live variants are what `--record` writes
+/// to a session file; the `Replay*` variants only exist while replaying one.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
 pub enum SourceEvent {
     Log { source: String, line: String },
+    /// A line from a structured source that already knows its severity
+    /// (None when the record leaves it unspecified), with any attributes
+    /// it carries (e.g. OTLP).
+    Record {
+        source: String,
+        line: String,
+        level: Option<Level>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        fields: Fields,
+    },
     Status { source: String, status: SourceStatus },
+    /// A source discovered at runtime by a supervising task (e.g. a new pod).
+    Attached { info: SourceInfo },
+    /// A recorded event, stamped with its offset into the recording.
+    #[serde(skip)]
+    Replayed { at: Duration, event: Box<SourceEvent> },
+    /// A recorded pattern-store tick.
+    #[serde(skip)]
+    ReplayTick { at: Duration },
+    /// A recorded reset or counter clear.
+    #[serde(skip)]
+    ReplayAction { at: Duration, action: SessionAction },
+    /// The replay jumped backwards: clear state before it re-plays from the start.
+    #[serde(skip)]
+    ReplayReset,
 }
 
-#[allow(dead_code)]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SourceInfo {
     pub id: String,
     pub kind: String,
     pub status: SourceStatus,
 }
 
+/// A source to start, independent of the TUI. [`Source::spawn`] runs it as a
+/// tokio task that reports lines and status changes on `tx`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    /// `docker logs -f` for a container.
+    Docker(String),
+    /// A shell command's stdout (`sh -c`).
+    Command(String),
+    /// `tail -f` a local file.
+    File(String),
+    /// A remote file or journal over ssh.
+    Ssh(ssh::SshTarget),
+    /// Pods matching a selector, via kubectl.
+    Kubernetes(kubernetes::KubeSelector),
+    /// The local systemd journal.
+    Journald(journald::JournalFilter),
+    /// A syslog receiver.
+    Syslog(syslog::SyslogListen),
+    /// An HTTP push endpoint.
+    Http(SocketAddr),
+    /// An OTLP/HTTP logs receiver.
+    Otlp(SocketAddr),
+}
+
+/// Source kinds accepted by [`Source::parse`], named after the CLI flags.
+pub const SOURCE_KINDS: &[&str] = &["docker", "cmd", "file", "ssh", "k8s", "journald", "syslog", "http", "otlp"];
+
+impl Source {
+    /// A source from a kind and a spec as the CLI flag takes it, e.g.
+    /// `("file", "/var/log/app.log")`. For `journald` the spec is an
+    /// optional list of units.
+    pub fn parse(kind: &str, spec: &str) -> Result<Source> {
+        let spec = spec.trim();
+        if spec.is_empty() && kind != "journald" {
+            bail!("{} source needs a target", kind);
+        }
+        let listen = |spec: &str| -> Result<SocketAddr> {
+            spec.parse().with_context(|| format!("invalid listen address '{}'", spec))
+        };
+        Ok(match kind {
+            "docker" => Source::Docker(spec.to_string()),
+            "cmd" => Source::Command(spec.to_string()),
+            "file" => Source::File(spec.to_string()),
+            "ssh" => Source::ssh(spec)?,
+            "k8s" => Source::kubernetes(spec),
+            "journald" => Source::Journald(journald::JournalFilter {
+                units: spec.split_whitespace().map(String::from).collect(),
+                ..Default::default()
+            }),
+            "syslog" => Source::syslog(spec)?,
+            "http" => Source::Http(listen(spec)?),
+            "otlp" => Source::Otlp(listen(spec)?),
+            _ => bail!("unknown source kind '{}' (available: {})", kind, SOURCE_KINDS.join(", ")),
+        })
+    }
+
+    /// Parse an `--ssh` style spec (`[user@]host:/path` or `host:journald[:units]`).
+    pub fn ssh(spec: &str) -> Result<Source> {
+        Ok(Source::Ssh(ssh::SshTarget::parse(spec)?))
+    }
+
+    /// Parse a `--syslog` style address (`udp://0.0.0.0:5514`).
+    pub fn syslog(spec: &str) -> Result<Source> {
+        Ok(Source::Syslog(syslog::SyslogListen::parse(spec)?))
+    }
+
+    /// Parse a `--k8s` selector (`namespace/label=value`, `namespace/pod`).
+    pub fn kubernetes(spec: &str) -> Source {
+        Source::Kubernetes(kubernetes::KubeSelector::parse(spec))
+    }
+
+    pub fn spawn(self, tx: mpsc::Sender<SourceEvent>) -> (SourceInfo, JoinHandle<()>) {
+        match self {
+            Source::Docker(container) => spawn_docker(container, tx),
+            Source::Command(cmd) => {
+                let name = cmd.split_whitespace().next().unwrap_or("cmd").to_string();
+                spawn_command(name, cmd, tx)
+            }
+            Source::File(path) => spawn_file(path, tx),
+            Source::Ssh(target) => ssh::spawn_ssh(target, tx),
+            Source::Kubernetes(selector) => kubernetes::spawn_kubernetes(selector, tx),
+            Source::Journald(filter) => journald::spawn_journald(filter, tx),
+            Source::Syslog(listen) => syslog::spawn_syslog(listen, tx),
+            Source::Http(addr) => http::spawn_http(addr, tx),
+            Source::Otlp(addr) => otlp::spawn_otlp(addr, tx),
+        }
+    }
+}
+
 // --- Docker source ---
 
 pub fn spawn_docker(
@@ -181,272 +312,6 @@ async fn run_command(cmd: &str, source_id: &str, tx: mpsc::Sender<SourceEvent>)
     Ok(())
 }
 
-// --- Azure Container App source ---
-
-pub fn spawn_azure_containerapp(
-    app_name: String,
-    resource_group: String,
-    subscription_id: String,
-    token: Option<String>,
-    tx: mpsc::Sender<SourceEvent>,
-) -> (SourceInfo, tokio::task::JoinHandle<()>) {
-    let id = format!("azure/{}", app_name);
-    let info = SourceInfo {
-        id: id.clone(),
-        kind: "azure".into(),
-        status: SourceStatus::Starting,
-    };
-    let handle = tokio::spawn(async move {
-        let _ = run_azure_containerapp(&app_name, &resource_group, &subscription_id, token.as_deref(), &id, tx).await;
-    });
-    (info, handle)
-}
-
-async fn run_azure_containerapp(
-    app_name: &str,
-    resource_group: &str,
-    subscription_id: &str,
-    token: Option<&str>,
-    source_id: &str,
-    tx: mpsc::Sender<SourceEvent>,
-) -> Result<()> {
-    // Try the fast curl-based approach if we have a pre-fetched token
-    if let Some(token) = token {
-        if !subscription_id.is_empty() {
-            match run_azure_fast(app_name, resource_group, subscription_id, token, source_id, &tx).await {
-                Ok(()) => return Ok(()),
-                Err(_) => {
-                    // Fast path failed — fall through to az CLI
-                }
-            }
-        }
-    }
-
-    // Fallback: use az CLI (slower but more reliable)
-    run_azure_cli(app_name, resource_group, source_id, tx).await
-}
-
-/// Fast path: use curl + pre-fetched token to call Azure REST API directly.
-/// Avoids the ~3-5s Python startup of the az CLI.
-async fn run_azure_fast(
-    app_name: &str,
-    resource_group: &str,
-    subscription_id: &str,
-    token: &str,
-    source_id: &str,
-    tx: &mpsc::Sender<SourceEvent>,
-) -> Result<()> {
-    let base = format!(
-        "https://management.azure.com/subscriptions/{}/resourceGroups/{}/providers/Microsoft.App/containerApps/{}",
-        subscription_id, resource_group, app_name
-    );
-    let api = "api-version=2024-03-01";
-    let auth_header = format!("Authorization: Bearer {}", token);
-
-    // Step 1: Get app details (need managedEnvironmentId for the log stream domain)
-    let app_json = curl_get_json(&format!("{}?{}", base, api), &auth_header).await?;
-    let env_id = app_json
-        .pointer("/properties/managedEnvironmentId")
-        .and_then(|v| v.as_str())
-        .ok_or_else(|| anyhow::anyhow!("no managedEnvironmentId"))?;
-
-    // Step 2: Get environment domain
-    let env_json = curl_get_json(
-        &format!("https://management.azure.com{}?{}", env_id, api),
-        &auth_header,
-    )
-    .await?;
-    let env_domain = env_json
-        .pointer("/properties/defaultDomain")
-        .and_then(|v| v.as_str())
-        .ok_or_else(|| anyhow::anyhow!("no defaultDomain"))?
-        .to_string();
-
-    // Step 3: Get latest active revision
-    let revisions_json = curl_get_json(
-        &format!("{}/revisions?{}", base, api),
-        &auth_header,
-    )
-    .await?;
-    let latest_rev = revisions_json
-        .pointer("/value/0/name")
-        .and_then(|v| v.as_str())
-        .ok_or_else(|| anyhow::anyhow!("no revisions"))?
-        .to_string();
-
-    // Step 4: Get first replica
-    let replicas_json = curl_get_json(
-        &format!("{}/revisions/{}/replicas?{}", base, latest_rev, api),
-        &auth_header,
-    )
-    .await?;
-    let replica = replicas_json
-        .pointer("/value/0/name")
-        .and_then(|v| v.as_str())
-        .ok_or_else(|| anyhow::anyhow!("no replicas"))?
-        .to_string();
-
-    // Step 5: Get log stream auth token
-    let auth_json = curl_post_json(
-        &format!("{}/getAuthToken?{}", base, api),
-        &auth_header,
-    )
-    .await?;
-    let log_token = auth_json
-        .pointer("/properties/token")
-        .and_then(|v| v.as_str())
-        .ok_or_else(|| anyhow::anyhow!("no log stream token"))?;
-
-    // Step 6: Stream logs from the log stream endpoint
-    let log_url = format!(
-        "https://{}/subscriptions/{}/resourceGroups/{}/containerApps/{}/revisions/{}/replicas/{}/logstream?follow=true&tailLines=100&output=text",
-        env_domain, subscription_id, resource_group, app_name, latest_rev, replica
-    );
-
-    let mut child = Command::new("curl")
-        .args([
-            "-N", "-s", "-f",
-            "-H", &format!("Authorization: Bearer {}", log_token),
-            &log_url,
-        ])
-        .stdout(std::process::Stdio::piped())
-        .stderr(std::process::Stdio::null())
-        .kill_on_drop(true)
-        .spawn()?;
-
-    if let Some(stdout) = child.stdout.take() {
-        let reader = BufReader::new(stdout);
-        let mut lines = reader.lines();
-        let mut first = true;
-        while let Some(line) = lines.next_line().await? {
-            if first {
-                let _ = tx.send(SourceEvent::Status {
-                    source: source_id.to_string(),
-                    status: SourceStatus::Running,
-                }).await;
-                first = false;
-            }
-            if tx
-                .send(SourceEvent::Log {
-                    source: source_id.to_string(),
-                    line,
-                })
-                .await
-                .is_err()
-            {
-                break;
-            }
-        }
-    }
-
-    let _ = tx.send(SourceEvent::Status {
-        source: source_id.to_string(),
-        status: SourceStatus::Stopped,
-    }).await;
-
-    let _ = child.wait().await;
-    Ok(())
-}
-
-/// Fallback: use az CLI for log streaming (slower due to Python startup).
-async fn run_azure_cli(
-    app_name: &str,
-    resource_group: &str,
-    source_id: &str,
-    tx: mpsc::Sender<SourceEvent>,
-) -> Result<()> {
-    let result = Command::new("az")
-        .args([
-            "containerapp",
-            "logs",
-            "show",
-            "-n",
-            app_name,
-            "-g",
-            resource_group,
-            "--type",
-            "console",
-            "--follow",
-        ])
-        .stdout(std::process::Stdio::piped())
-        .stderr(std::process::Stdio::null())
-        .kill_on_drop(true)
-        .spawn();
-
-    let mut child = match result {
-        Ok(child) => child,
-        Err(e) => {
-            let _ = tx.send(SourceEvent::Status {
-                source: source_id.to_string(),
-                status: SourceStatus::Error(format!("az: {}", e)),
-            }).await;
-            return Err(e.into());
-        }
-    };
-
-    if let Some(stdout) = child.stdout.take() {
-        let reader = BufReader::new(stdout);
-        let mut lines = reader.lines();
-        let mut first = true;
-        while let Some(line) = lines.next_line().await? {
-            if first {
-                let _ = tx.send(SourceEvent::Status {
-                    source: source_id.to_string(),
-                    status: SourceStatus::Running,
-                }).await;
-                first = false;
-            }
-            if tx
-                .send(SourceEvent::Log {
-                    source: source_id.to_string(),
-                    line,
-                })
-                .await
-                .is_err()
-            {
-                break;
-            }
-        }
-    }
-
-    let _ = tx.send(SourceEvent::Status {
-        source: source_id.to_string(),
-        status: SourceStatus::Stopped,
-    }).await;
-
-    let _ = child.wait().await;
-    Ok(())
-}
-
-/// Helper: GET a URL with auth header, parse response as JSON.
-async fn curl_get_json(url: &str, auth_header: &str) -> Result<serde_json::Value> {
-    let output = Command::new("curl")
-        .args(["-sf", "-H", auth_header, url])
-        .output()
-        .await?;
-    if !output.status.success() {
-        return Err(anyhow::anyhow!("curl GET failed for {}", url));
-    }
-    Ok(serde_json::from_slice(&output.stdout)?)
-}
-
-/// Helper: POST to a URL with auth header, parse response as JSON.
-async fn curl_post_json(url: &str, auth_header: &str) -> Result<serde_json::Value> {
-    let output = Command::new("curl")
-        .args([
-            "-sf", "-X", "POST",
-            "-H", auth_header,
-            "-H", "Content-Type: application/json",
-            url,
-        ])
-        .output()
-        .await?;
-    if !output.status.success() {
-        return Err(anyhow::anyhow!("curl POST failed for {}", url));
-    }
-    Ok(serde_json::from_slice(&output.stdout)?)
-}
-
 // --- File tail source ---
 
 pub fn spawn_file(
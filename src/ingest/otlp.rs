@@ -0,0 +1,563 @@
+use std::collections::HashSet;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+
+use anyhow::Result;
+use hyper::{Method, StatusCode};
+use prost::Message;
+use serde_json::Value;
+use tokio::net::TcpListener;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use super::http::{serve, HttpRequest, HttpResponse};
+use super::{SourceEvent, SourceInfo, SourceStatus};
+use crate::parse::{Fields, Level};
+
+/// The subset of `opentelemetry/proto/collector/logs/v1` we read. Field tags
+/// match the upstream .proto files; unknown fields are skipped by prost.
+pub mod proto {
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct ExportLogsServiceRequest {
+        #[prost(message, repeated, tag = "1")]
+        pub resource_logs: Vec<ResourceLogs>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct ResourceLogs {
+        #[prost(message, optional, tag = "1")]
+        pub resource: Option<Resource>,
+        #[prost(message, repeated, tag = "2")]
+        pub scope_logs: Vec<ScopeLogs>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Resource {
+        #[prost(message, repeated, tag = "1")]
+        pub attributes: Vec<KeyValue>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct ScopeLogs {
+        #[prost(message, repeated, tag = "2")]
+        pub log_records: Vec<LogRecord>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct LogRecord {
+        #[prost(fixed64, tag = "1")]
+        pub time_unix_nano: u64,
+        #[prost(int32, tag = "2")]
+        pub severity_number: i32,
+        #[prost(string, tag = "3")]
+        pub severity_text: String,
+        #[prost(message, optional, tag = "5")]
+        pub body: Option<AnyValue>,
+        #[prost(message, repeated, tag = "6")]
+        pub attributes: Vec<KeyValue>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct KeyValue {
+        #[prost(string, tag = "1")]
+        pub key: String,
+        #[prost(message, optional, tag = "2")]
+        pub value: Option<AnyValue>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct AnyValue {
+        #[prost(oneof = "any_value::Value", tags = "1, 2, 3, 4, 5, 6, 7")]
+        pub value: Option<any_value::Value>,
+    }
+
+    pub mod any_value {
+        // Variant names mirror the oneof fields in common.proto
+        #[allow(clippy::enum_variant_names)]
+        #[derive(Clone, PartialEq, prost::Oneof)]
+        pub enum Value {
+            #[prost(string, tag = "1")]
+            StringValue(String),
+            #[prost(bool, tag = "2")]
+            BoolValue(bool),
+            #[prost(int64, tag = "3")]
+            IntValue(i64),
+            #[prost(double, tag = "4")]
+            DoubleValue(f64),
+            #[prost(message, tag = "5")]
+            ArrayValue(super::ArrayValue),
+            #[prost(message, tag = "6")]
+            KvlistValue(super::KeyValueList),
+            #[prost(bytes, tag = "7")]
+            BytesValue(Vec<u8>),
+        }
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct ArrayValue {
+        #[prost(message, repeated, tag = "1")]
+        pub values: Vec<AnyValue>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct KeyValueList {
+        #[prost(message, repeated, tag = "1")]
+        pub values: Vec<KeyValue>,
+    }
+}
+
+use proto::any_value::Value as AnyKind;
+
+/// Service name the OpenTelemetry SDKs report when none is configured.
+const UNKNOWN_SERVICE: &str = "unknown_service";
+
+/// One log record flattened for the pattern store.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OtlpRecord {
+    pub service: String,
+    pub level: Option<Level>,
+    pub message: String,
+    pub fields: Fields,
+}
+
+/// Render an attribute or body value as text.
+fn render(value: &proto::AnyValue) -> String {
+    match value.value {
+        Some(AnyKind::StringValue(ref s)) => s.clone(),
+        Some(AnyKind::BoolValue(b)) => b.to_string(),
+        Some(AnyKind::IntValue(i)) => i.to_string(),
+        Some(AnyKind::DoubleValue(d)) => d.to_string(),
+        Some(AnyKind::ArrayValue(ref arr)) => format!(
+            "[{}]",
+            arr.values.iter().map(render).collect::<Vec<_>>().join(", ")
+        ),
+        Some(AnyKind::KvlistValue(ref kvs)) => format!(
+            "{{{}}}",
+            kvs.values
+                .iter()
+                .map(|kv| format!("{}={}", kv.key, kv.value.as_ref().map(render).unwrap_or_default()))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        Some(AnyKind::BytesValue(ref bytes)) => bytes.iter().map(|b| format!("{:02x}", b)).collect(),
+        None => String::new(),
+    }
+}
+
+/// Flatten an export request into one record per log record.
+pub fn flatten(request: &proto::ExportLogsServiceRequest) -> Vec<OtlpRecord> {
+    let mut out = Vec::new();
+    for rl in &request.resource_logs {
+        let service = rl
+            .resource
+            .as_ref()
+            .and_then(|r| r.attributes.iter().find(|kv| kv.key == "service.name"))
+            .and_then(|kv| kv.value.as_ref())
+            .map(render)
+            .filter(|s| !s.is_empty())
+            .unwrap_or_else(|| UNKNOWN_SERVICE.to_string());
+        for record in rl.scope_logs.iter().flat_map(|sl| &sl.log_records) {
+            let level = Level::from_otel_severity(record.severity_number)
+                .or_else(|| Level::from_name(&record.severity_text));
+            let fields = record
+                .attributes
+                .iter()
+                .map(|kv| (kv.key.clone(), kv.value.as_ref().map(render).unwrap_or_default()))
+                .collect();
+            out.push(OtlpRecord {
+                service: service.clone(),
+                level,
+                message: record.body.as_ref().map(render).unwrap_or_default(),
+                fields,
+            });
+        }
+    }
+    out
+}
+
+/// OTLP/JSON uses lowerCamelCase, but receivers also accept the proto names.
+fn get<'a>(v: &'a Value, camel: &str, snake: &str) -> Option<&'a Value> {
+    v.get(camel).or_else(|| v.get(snake))
+}
+
+fn json_list<'a>(v: &'a Value, camel: &str, snake: &str) -> &'a [Value] {
+    get(v, camel, snake)
+        .and_then(|l| l.as_array())
+        .map(Vec::as_slice)
+        .unwrap_or(&[])
+}
+
+fn json_any(v: &Value) -> proto::AnyValue {
+    let kind = if let Some(s) = get(v, "stringValue", "string_value").and_then(|s| s.as_str()) {
+        Some(AnyKind::StringValue(s.to_string()))
+    } else if let Some(b) = get(v, "boolValue", "bool_value").and_then(|b| b.as_bool()) {
+        Some(AnyKind::BoolValue(b))
+    } else if let Some(i) = get(v, "intValue", "int_value") {
+        // 64-bit integers are JSON strings per the protobuf JSON mapping
+        i.as_i64()
+            .or_else(|| i.as_str().and_then(|s| s.parse().ok()))
+            .map(AnyKind::IntValue)
+    } else if let Some(d) = get(v, "doubleValue", "double_value").and_then(|d| d.as_f64()) {
+        Some(AnyKind::DoubleValue(d))
+    } else if let Some(arr) = get(v, "arrayValue", "array_value") {
+        Some(AnyKind::ArrayValue(proto::ArrayValue {
+            values: json_list(arr, "values", "values").iter().map(json_any).collect(),
+        }))
+    } else if let Some(kvs) = get(v, "kvlistValue", "kvlist_value") {
+        Some(AnyKind::KvlistValue(proto::KeyValueList {
+            values: json_list(kvs, "values", "values").iter().map(json_kv).collect(),
+        }))
+    } else {
+        // bytesValue is base64 in JSON; keep the encoded text
+        get(v, "bytesValue", "bytes_value")
+            .and_then(|b| b.as_str())
+            .map(|s| AnyKind::StringValue(s.to_string()))
+    };
+    proto::AnyValue { value: kind }
+}
+
+fn json_kv(v: &Value) -> proto::KeyValue {
+    proto::KeyValue {
+        key: v.get("key").and_then(|k| k.as_str()).unwrap_or_default().to_string(),
+        value: v.get("value").map(json_any),
+    }
+}
+
+fn json_attributes(v: &Value) -> Vec<proto::KeyValue> {
+    json_list(v, "attributes", "attributes").iter().map(json_kv).collect()
+}
+
+/// Decode an OTLP/JSON body into the protobuf message types.
+pub fn decode_json(body: &[u8]) -> Result<proto::ExportLogsServiceRequest> {
+    let json: Value = serde_json::from_slice(body)
+        .map_err(|e| anyhow::anyhow!("invalid OTLP/JSON body: {}", e))?;
+    let resource_logs = json_list(&json, "resourceLogs", "resource_logs")
+        .iter()
+        .map(|rl| proto::ResourceLogs {
+            resource: rl.get("resource").map(|r| proto::Resource {
+                attributes: json_attributes(r),
+            }),
+            scope_logs: json_list(rl, "scopeLogs", "scope_logs")
+                .iter()
+                .map(|sl| proto::ScopeLogs {
+                    log_records: json_list(sl, "logRecords", "log_records")
+                        .iter()
+                        .map(|lr| proto::LogRecord {
+                            time_unix_nano: get(lr, "timeUnixNano", "time_unix_nano")
+                                .and_then(|t| t.as_u64().or_else(|| t.as_str()?.parse().ok()))
+                                .unwrap_or(0),
+                            severity_number: get(lr, "severityNumber", "severity_number")
+                                .and_then(|n| n.as_i64())
+                                .unwrap_or(0) as i32,
+                            severity_text: get(lr, "severityText", "severity_text")
+                                .and_then(|t| t.as_str())
+                                .unwrap_or_default()
+                                .to_string(),
+                            body: lr.get("body").map(json_any),
+                            attributes: json_attributes(lr),
+                        })
+                        .collect(),
+                })
+                .collect(),
+        })
+        .collect();
+    Ok(proto::ExportLogsServiceRequest { resource_logs })
+}
+
+/// Shared state of one OTLP listener.
+#[derive(Clone)]
+struct Receiver {
+    tx: mpsc::Sender<SourceEvent>,
+    seen: Arc<Mutex<HashSet<String>>>,
+}
+
+impl Receiver {
+    async fn handle(&self, req: HttpRequest) -> HttpResponse {
+        if req.path != "/v1/logs" {
+            return HttpResponse::text(StatusCode::NOT_FOUND, "OTLP logs are accepted at /v1/logs");
+        }
+        if req.method != Method::POST {
+            return HttpResponse::text(StatusCode::METHOD_NOT_ALLOWED, "use POST");
+        }
+        let content_type = req.content_type().unwrap_or("").to_ascii_lowercase();
+        let is_json = content_type.starts_with("application/json");
+        let decoded = if is_json {
+            decode_json(&req.body)
+        } else if content_type.starts_with("application/x-protobuf") {
+            proto::ExportLogsServiceRequest::decode(req.body.as_ref())
+                .map_err(|e| anyhow::anyhow!("invalid OTLP protobuf body: {}", e))
+        } else {
+            return HttpResponse::text(
+                StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                "use application/x-protobuf or application/json",
+            );
+        };
+        let request = match decoded {
+            Ok(request) => request,
+            Err(e) => return HttpResponse::text(StatusCode::BAD_REQUEST, e.to_string()),
+        };
+
+        for record in flatten(&request) {
+            if !self.deliver(record).await {
+                return HttpResponse::text(StatusCode::SERVICE_UNAVAILABLE, "shutting down");
+            }
+        }
+
+        // An empty ExportLogsServiceResponse: `{}` in JSON, zero bytes in protobuf
+        if is_json {
+            HttpResponse {
+                status: StatusCode::OK,
+                content_type: "application/json",
+                body: b"{}".to_vec(),
+            }
+        } else {
+            HttpResponse {
+                status: StatusCode::OK,
+                content_type: "application/x-protobuf",
+                body: Vec::new(),
+            }
+        }
+    }
+
+    /// Forward one record; returns false once the receiver is gone.
+    async fn deliver(&self, record: OtlpRecord) -> bool {
+        if record.message.is_empty() {
+            return true;
+        }
+        let source = format!("otlp/{}", record.service);
+        let is_new = self.seen.lock().unwrap().insert(source.clone());
+        if is_new {
+            let _ = self
+                .tx
+                .send(SourceEvent::Attached {
+                    info: SourceInfo {
+                        id: source.clone(),
+                        kind: "otlp".into(),
+                        status: SourceStatus::Running,
+                    },
+                })
+                .await;
+        }
+        self.tx
+            .send(SourceEvent::Record {
+                source,
+                line: record.message,
+                level: record.level,
+                fields: record.fields,
+            })
+            .await
+            .is_ok()
+    }
+}
+
+pub fn spawn_otlp(addr: SocketAddr, tx: mpsc::Sender<SourceEvent>) -> (SourceInfo, JoinHandle<()>) {
+    let id = format!("otlp/{}", addr);
+    let info = SourceInfo {
+        id: id.clone(),
+        kind: "otlp".into(),
+        status: SourceStatus::Starting,
+    };
+    let handle = tokio::spawn(async move {
+        if let Err(e) = run_otlp(addr, &id, tx.clone()).await {
+            let _ = tx
+                .send(SourceEvent::Status {
+                    source: id,
+                    status: SourceStatus::Error(format!("otlp: {}", e)),
+                })
+                .await;
+        }
+    });
+    (info, handle)
+}
+
+async fn run_otlp(addr: SocketAddr, source_id: &str, tx: mpsc::Sender<SourceEvent>) -> Result<()> {
+    let listener = TcpListener::bind(addr).await?;
+    let _ = tx
+        .send(SourceEvent::Status {
+            source: source_id.to_string(),
+            status: SourceStatus::Running,
+        })
+        .await;
+
+    let receiver = Receiver {
+        tx,
+        seen: Arc::new(Mutex::new(HashSet::new())),
+    };
+    serve(listener, move |req| {
+        let receiver = receiver.clone();
+        async move { receiver.handle(req).await }
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    // Adapted from the OTLP/JSON example in opentelemetry-proto
+    const JSON_EXPORT: &str = r#"{
+      "resourceLogs": [{
+        "resource": {"attributes": [{"key": "service.name", "value": {"stringValue": "checkout"}}]},
+        "scopeLogs": [{
+          "scope": {"name": "my.library", "version": "1.0.0"},
+          "logRecords": [{
+            "timeUnixNano": "1544712660300000000",
+            "severityNumber": 17,
+            "severityText": "Error",
+            "traceId": "5B8EFFF798038103D269B633813FC60C",
+            "body": {"stringValue": "payment declined for order 4411"},
+            "attributes": [
+              {"key": "http.status_code", "value": {"intValue": "402"}},
+              {"key": "retry", "value": {"boolValue": false}},
+              {"key": "tags", "value": {"arrayValue": {"values": [{"stringValue": "a"}, {"stringValue": "b"}]}}}
+            ]
+          }, {
+            "severityText": "warning",
+            "body": {"kvlistValue": {"values": [{"key": "event", "value": {"stringValue": "slow"}}]}}
+          }]
+        }]
+      }, {
+        "scopeLogs": [{"logRecords": [{"body": {"stringValue": "no resource"}}]}]
+      }]
+    }"#;
+
+    #[test]
+    fn decode_otlp_json() {
+        let records = flatten(&decode_json(JSON_EXPORT.as_bytes()).unwrap());
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[0].service, "checkout");
+        assert_eq!(records[0].level, Some(Level::Error));
+        assert_eq!(records[0].message, "payment declined for order 4411");
+        assert_eq!(
+            records[0].fields,
+            vec![
+                ("http.status_code".to_string(), "402".to_string()),
+                ("retry".to_string(), "false".to_string()),
+                ("tags".to_string(), "[a, b]".to_string()),
+            ]
+        );
+        // severityText is the fallback when severityNumber is unspecified
+        assert_eq!(records[1].level, Some(Level::Warn));
+        assert_eq!(records[1].message, "{event=slow}");
+        assert_eq!(records[2].service, UNKNOWN_SERVICE);
+        assert_eq!(records[2].level, None);
+        assert!(decode_json(b"not json").is_err());
+    }
+
+    fn sample_request() -> proto::ExportLogsServiceRequest {
+        let string = |s: &str| proto::AnyValue {
+            value: Some(AnyKind::StringValue(s.to_string())),
+        };
+        proto::ExportLogsServiceRequest {
+            resource_logs: vec![proto::ResourceLogs {
+                resource: Some(proto::Resource {
+                    attributes: vec![proto::KeyValue {
+                        key: "service.name".into(),
+                        value: Some(string("inventory")),
+                    }],
+                }),
+                scope_logs: vec![proto::ScopeLogs {
+                    log_records: vec![proto::LogRecord {
+                        time_unix_nano: 1,
+                        severity_number: 13,
+                        severity_text: String::new(),
+                        body: Some(string("stock low for sku 991")),
+                        attributes: vec![proto::KeyValue {
+                            key: "sku".into(),
+                            value: Some(proto::AnyValue {
+                                value: Some(AnyKind::IntValue(991)),
+                            }),
+                        }],
+                    }],
+                }],
+            }],
+        }
+    }
+
+    #[test]
+    fn decode_otlp_protobuf() {
+        let bytes = sample_request().encode_to_vec();
+        let decoded = proto::ExportLogsServiceRequest::decode(bytes.as_slice()).unwrap();
+        let records = flatten(&decoded);
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].service, "inventory");
+        assert_eq!(records[0].level, Some(Level::Warn));
+        assert_eq!(records[0].fields, vec![("sku".to_string(), "991".to_string())]);
+    }
+
+    async fn post(addr: SocketAddr, headers: &str, body: &[u8]) -> String {
+        let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+        let head = format!(
+            "POST /v1/logs HTTP/1.1\r\nHost: test\r\nConnection: close\r\n{}Content-Length: {}\r\n\r\n",
+            headers,
+            body.len()
+        );
+        stream.write_all(head.as_bytes()).await.unwrap();
+        stream.write_all(body).await.unwrap();
+        let mut resp = String::new();
+        stream.read_to_string(&mut resp).await.unwrap();
+        resp
+    }
+
+    #[tokio::test]
+    async fn otlp_receiver_accepts_protobuf_and_gzip_json() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (tx, mut rx) = mpsc::channel(64);
+        let receiver = Receiver {
+            tx,
+            seen: Arc::new(Mutex::new(HashSet::new())),
+        };
+        let server = tokio::spawn(serve(listener, move |req| {
+            let receiver = receiver.clone();
+            async move { receiver.handle(req).await }
+        }));
+
+        let resp = post(
+            addr,
+            "Content-Type: application/x-protobuf\r\n",
+            &sample_request().encode_to_vec(),
+        )
+        .await;
+        assert!(resp.starts_with("HTTP/1.1 200"), "{}", resp);
+
+        let mut gz = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        gz.write_all(JSON_EXPORT.as_bytes()).unwrap();
+        let resp = post(
+            addr,
+            "Content-Type: application/json\r\nContent-Encoding: gzip\r\n",
+            &gz.finish().unwrap(),
+        )
+        .await;
+        assert!(resp.starts_with("HTTP/1.1 200"), "{}", resp);
+        assert!(resp.ends_with("{}"), "{}", resp);
+
+        let resp = post(addr, "Content-Type: text/plain\r\n", b"hi").await;
+        assert!(resp.starts_with("HTTP/1.1 415"), "{}", resp);
+
+        let mut attached = Vec::new();
+        let mut records = Vec::new();
+        while let Ok(ev) = rx.try_recv() {
+            match ev {
+                SourceEvent::Attached { info } => attached.push(info.id),
+                SourceEvent::Record {
+                    source,
+                    level,
+                    fields,
+                    ..
+                } => records.push((source, level, fields.len())),
+                _ => {}
+            }
+        }
+        assert_eq!(
+            attached,
+            vec!["otlp/inventory", "otlp/checkout", "otlp/unknown_service"]
+        );
+        assert_eq!(records.len(), 4);
+        assert_eq!(records[1], ("otlp/checkout".to_string(), Some(Level::Error), 3));
+
+        server.abort();
+    }
+}
@@ -0,0 +1,507 @@
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::{bail, Result};
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::process::Command;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use super::journald::{self, JournalFilter};
+use super::{SourceEvent, SourceInfo, SourceStatus};
+
+/// Printed by the remote shell before exec'ing the follower, so the source
+/// only turns Running once the connection and login actually succeeded.
+const READY_MARKER: &str = "__logradar_ready__";
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// What to follow on the remote host.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SshRemote {
+    /// `tail -F` a file.
+    File(String),
+    /// `journalctl -o json -f`, optionally limited to units.
+    Journald(JournalFilter),
+}
+
+/// A parsed `--ssh` spec: `[user@]host:/path` or `[user@]host:journald[:unit,...]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SshTarget {
+    /// Passed to `ssh` as-is, so `~/.ssh/config` aliases and users apply.
+    pub destination: String,
+    /// Host without the user, used to group sources.
+    pub host: String,
+    pub remote: SshRemote,
+}
+
+impl SshTarget {
+    pub fn parse(spec: &str) -> Result<SshTarget> {
+        let (user, rest) = match spec.split_once('@') {
+            Some((user, rest)) => (Some(user), rest),
+            None => (None, spec),
+        };
+        // Bracketed IPv6 literals contain colons of their own
+        let (host, remote) = if let Some(inner) = rest.strip_prefix('[') {
+            match inner.split_once("]:") {
+                Some((host, remote)) => (host, remote),
+                None => bail!("invalid ssh target '{}': expected [host]:path", spec),
+            }
+        } else {
+            match rest.split_once(':') {
+                Some((host, remote)) => (host, remote),
+                None => bail!("invalid ssh target '{}': expected host:/path or host:journald", spec),
+            }
+        };
+        // ssh would read a leading '-' in the destination as an option
+        let bad_user = user.is_some_and(|u| u.is_empty() || u.starts_with('-'));
+        if host.is_empty() || host.starts_with('-') || bad_user {
+            bail!("invalid ssh host in '{}'", spec);
+        }
+        let remote = if remote == "journald" {
+            SshRemote::Journald(JournalFilter::default())
+        } else if let Some(units) = remote.strip_prefix("journald:") {
+            SshRemote::Journald(JournalFilter {
+                units: units
+                    .split(',')
+                    .filter(|u| !u.is_empty())
+                    .map(String::from)
+                    .collect(),
+                ..JournalFilter::default()
+            })
+        } else if remote.is_empty() {
+            bail!("invalid ssh target '{}': missing remote path", spec);
+        } else {
+            SshRemote::File(remote.to_string())
+        };
+        let destination = match user {
+            Some(user) => format!("{}@{}", user, host),
+            None => host.to_string(),
+        };
+        Ok(SshTarget {
+            destination,
+            host: host.to_string(),
+            remote,
+        })
+    }
+
+    /// Sources are grouped per host in the Sources pane.
+    pub fn kind(&self) -> String {
+        format!("ssh:{}", self.host)
+    }
+
+    /// The `--ssh` spec this target parses from.
+    pub fn spec(&self) -> String {
+        let user = self.destination.strip_suffix(self.host.as_str()).unwrap_or("");
+        let host = if self.host.contains(':') {
+            format!("[{}]", self.host)
+        } else {
+            self.host.clone()
+        };
+        let remote = match self.remote {
+            SshRemote::File(ref path) => path.clone(),
+            SshRemote::Journald(ref filter) if filter.units.is_empty() => "journald".to_string(),
+            SshRemote::Journald(ref filter) => format!("journald:{}", filter.units.join(",")),
+        };
+        format!("{}{}:{}", user, host, remote)
+    }
+
+    /// `ssh/<host>:<path>` or `ssh/<host>:journald/<units>`.
+    pub fn source_id(&self) -> String {
+        match self.remote {
+            SshRemote::File(ref path) => format!("ssh/{}:{}", self.host, path),
+            SshRemote::Journald(ref filter) => {
+                format!("ssh/{}:journald/{}", self.host, filter.label())
+            }
+        }
+    }
+
+    /// Remote shell command. `resumed` skips the backlog after a reconnect;
+    /// for journald it carries the last cursor seen.
+    fn remote_command(&self, resumed: bool, cursor: Option<&str>) -> String {
+        let follower = match self.remote {
+            SshRemote::File(ref path) => {
+                let lines = if resumed { "0" } else { "100" };
+                format!("tail -F -n {} -- {}", lines, shell_quote(path))
+            }
+            SshRemote::Journald(ref filter) => {
+                let args: Vec<String> = filter
+                    .resume_args(cursor)
+                    .iter()
+                    .map(|a| shell_quote(a))
+                    .collect();
+                format!("journalctl {}", args.join(" "))
+            }
+        };
+        format!("echo {}; exec {}", READY_MARKER, follower)
+    }
+}
+
+/// Quote for a POSIX shell on the remote end.
+fn shell_quote(s: &str) -> String {
+    if !s.is_empty()
+        && s
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "/._-+=,:@".contains(c))
+    {
+        return s.to_string();
+    }
+    format!("'{}'", s.replace('\'', r"'\''"))
+}
+
+/// The ssh binary to invoke. `LOGRADAR_SSH` overrides it (e.g. a wrapper
+/// that adds a jump host, or a test stub).
+pub fn ssh_bin() -> String {
+    std::env::var("LOGRADAR_SSH").unwrap_or_else(|_| "ssh".to_string())
+}
+
+pub fn spawn_ssh(target: SshTarget, tx: mpsc::Sender<SourceEvent>) -> (SourceInfo, JoinHandle<()>) {
+    let id = target.source_id();
+    let info = SourceInfo {
+        id: id.clone(),
+        kind: target.kind(),
+        status: SourceStatus::Starting,
+    };
+    let handle = tokio::spawn(async move {
+        run_ssh(&ssh_bin(), &target, &id, tx, INITIAL_BACKOFF).await;
+    });
+    (info, handle)
+}
+
+/// How one ssh session ended.
+enum Session {
+    /// The remote command exited cleanly; nothing more to follow.
+    Finished,
+    /// Connection failed or dropped; `ready` tells whether it ever came up.
+    Failed { ready: bool, reason: String },
+    /// The event channel closed (app shutting down).
+    Closed,
+}
+
+/// Follow the target over ssh, reconnecting with exponential backoff.
+/// Failures are reported as `SourceStatus::Error` until the next attempt.
+async fn run_ssh(
+    ssh: &str,
+    target: &SshTarget,
+    source_id: &str,
+    tx: mpsc::Sender<SourceEvent>,
+    initial_backoff: Duration,
+) {
+    let mut backoff = initial_backoff;
+    let mut resumed = false;
+    let mut cursor: Option<String> = None;
+    let mut seen_units: HashSet<String> = HashSet::new();
+
+    loop {
+        match run_session(ssh, target, source_id, &tx, resumed, &mut cursor, &mut seen_units).await {
+            Session::Finished => {
+                let _ = tx.send(SourceEvent::Status {
+                    source: source_id.to_string(),
+                    status: SourceStatus::Stopped,
+                }).await;
+                return;
+            }
+            Session::Closed => return,
+            Session::Failed { ready, reason } => {
+                if ready {
+                    // It was up: retry promptly and don't replay the backlog
+                    backoff = initial_backoff;
+                    resumed = true;
+                }
+                if tx
+                    .send(SourceEvent::Status {
+                        source: source_id.to_string(),
+                        status: SourceStatus::Error(reason),
+                    })
+                    .await
+                    .is_err()
+                {
+                    return;
+                }
+            }
+        }
+        tokio::time::sleep(backoff).await;
+        backoff = (backoff * 2).min(MAX_BACKOFF);
+        let _ = tx.send(SourceEvent::Status {
+            source: source_id.to_string(),
+            status: SourceStatus::Starting,
+        }).await;
+    }
+}
+
+async fn run_session(
+    ssh: &str,
+    target: &SshTarget,
+    source_id: &str,
+    tx: &mpsc::Sender<SourceEvent>,
+    resumed: bool,
+    cursor: &mut Option<String>,
+    seen_units: &mut HashSet<String>,
+) -> Session {
+    let result = Command::new(ssh)
+        .args([
+            "-T",
+            "-o",
+            "BatchMode=yes",
+            "-o",
+            "ConnectTimeout=10",
+            "-o",
+            "ServerAliveInterval=15",
+            "-o",
+            "ServerAliveCountMax=3",
+            "--",
+            &target.destination,
+            &target.remote_command(resumed, cursor.as_deref()),
+        ])
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .kill_on_drop(true)
+        .spawn();
+
+    let mut child = match result {
+        Ok(child) => child,
+        Err(e) => {
+            return Session::Failed {
+                ready: false,
+                reason: format!("ssh: {}", e),
+            }
+        }
+    };
+
+    // Keep the last stderr line: ssh reports why the connection failed there
+    let last_err = Arc::new(Mutex::new(None::<String>));
+    let stderr_task = child.stderr.take().map(|err| {
+        let last_err = last_err.clone();
+        tokio::spawn(async move {
+            let mut lines = BufReader::new(err).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                if !line.trim().is_empty() {
+                    *last_err.lock().unwrap() = Some(line.trim().to_string());
+                }
+            }
+        })
+    });
+
+    let mut ready = false;
+    if let Some(stdout) = child.stdout.take() {
+        let mut lines = BufReader::new(stdout).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            if !ready {
+                if line == READY_MARKER {
+                    ready = true;
+                    if tx
+                        .send(SourceEvent::Status {
+                            source: source_id.to_string(),
+                            status: SourceStatus::Running,
+                        })
+                        .await
+                        .is_err()
+                    {
+                        return Session::Closed;
+                    }
+                }
+                // Anything before the marker is login noise (motd etc.)
+                continue;
+            }
+            let event = match target.remote {
+                SshRemote::File(_) => SourceEvent::Log {
+                    source: source_id.to_string(),
+                    line,
+                },
+                SshRemote::Journald(_) => {
+                    let Some(entry) = journald::parse_entry(&line) else {
+                        continue;
+                    };
+                    if entry.cursor.is_some() {
+                        *cursor = entry.cursor;
+                    }
+                    let unit_id = format!("ssh/{}:{}", target.host, entry.unit);
+                    if seen_units.insert(entry.unit) {
+                        let _ = tx
+                            .send(SourceEvent::Attached {
+                                info: SourceInfo {
+                                    id: unit_id.clone(),
+                                    kind: target.kind(),
+                                    status: SourceStatus::Running,
+                                },
+                            })
+                            .await;
+                    }
+                    SourceEvent::Record {
+                        source: unit_id,
+                        line: entry.message,
+                        level: Some(entry.level),
+                        fields: Vec::new(),
+                    }
+                }
+            };
+            if tx.send(event).await.is_err() {
+                return Session::Closed;
+            }
+        }
+    }
+
+    let status = child.wait().await;
+    if let Some(task) = stderr_task {
+        let _ = task.await;
+    }
+    let reason = last_err.lock().unwrap().take();
+    match status {
+        Ok(s) if s.success() => Session::Finished,
+        Ok(s) => Session::Failed {
+            ready,
+            reason: reason.unwrap_or_else(|| match s.code() {
+                Some(255) => "ssh: connection failed".to_string(),
+                Some(code) => format!("ssh: exited with status {}", code),
+                None => "ssh: terminated".to_string(),
+            }),
+        },
+        Err(e) => Session::Failed {
+            ready,
+            reason: format!("ssh: {}", e),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_specs() {
+        let t = SshTarget::parse("deploy@web1:/var/log/app.log").unwrap();
+        assert_eq!(t.destination, "deploy@web1");
+        assert_eq!(t.host, "web1");
+        assert_eq!(t.remote, SshRemote::File("/var/log/app.log".into()));
+        assert_eq!(t.source_id(), "ssh/web1:/var/log/app.log");
+        assert_eq!(t.kind(), "ssh:web1");
+
+        let t = SshTarget::parse("db2:journald:postgresql.service,pgbouncer.service").unwrap();
+        assert_eq!(t.destination, "db2");
+        assert_eq!(
+            t.remote,
+            SshRemote::Journald(JournalFilter {
+                units: vec!["postgresql.service".into(), "pgbouncer.service".into()],
+                ..JournalFilter::default()
+            })
+        );
+        assert_eq!(t.source_id(), "ssh/db2:journald/postgresql.service,pgbouncer.service");
+
+        let t = SshTarget::parse("root@[fe80::1]:journald").unwrap();
+        assert_eq!(t.destination, "root@fe80::1");
+        assert_eq!(t.remote, SshRemote::Journald(JournalFilter::default()));
+
+        assert!(SshTarget::parse("web1").is_err());
+        assert!(SshTarget::parse("web1:").is_err());
+        assert!(SshTarget::parse("-oProxyCommand=x:/etc/passwd").is_err());
+        assert!(SshTarget::parse("-oProxyCommand=x@web1:/var/log/x").is_err());
+    }
+
+    #[test]
+    fn remote_commands() {
+        let t = SshTarget::parse("web1:/var/log/my app's.log").unwrap();
+        assert_eq!(
+            t.remote_command(false, None),
+            r"echo __logradar_ready__; exec tail -F -n 100 -- '/var/log/my app'\''s.log'"
+        );
+        assert!(t.remote_command(true, None).contains("tail -F -n 0 --"));
+
+        let t = SshTarget::parse("web1:journald:nginx.service").unwrap();
+        assert_eq!(
+            t.remote_command(false, None),
+            "echo __logradar_ready__; exec journalctl -o json -f --unit nginx.service -n 100"
+        );
+        assert_eq!(
+            t.remote_command(true, Some("s=abc;i=1")),
+            "echo __logradar_ready__; exec journalctl -o json -f --unit nginx.service '--after-cursor=s=abc;i=1'"
+        );
+    }
+
+    #[cfg(unix)]
+    fn stub_ssh(name: &str, script: &str) -> std::path::PathBuf {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join(format!("logradar-ssh-{}-{}", name, std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let stub = dir.join("ssh");
+        std::fs::write(&stub, format!("#!/bin/sh\n{}", script)).unwrap();
+        std::fs::set_permissions(&stub, std::fs::Permissions::from_mode(0o755)).unwrap();
+        stub
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn follows_remote_file() {
+        // Login banner before the marker must not show up as log lines
+        let stub = stub_ssh(
+            "file",
+            "echo 'Welcome to web1'\necho __logradar_ready__\necho 'GET /health 200'\necho 'GET /api 500'\n",
+        );
+        let target = SshTarget::parse("web1:/var/log/app.log").unwrap();
+        let (tx, mut rx) = mpsc::channel(64);
+        run_ssh(
+            &stub.display().to_string(),
+            &target,
+            &target.source_id(),
+            tx,
+            Duration::from_millis(10),
+        )
+        .await;
+
+        let mut statuses = Vec::new();
+        let mut lines = Vec::new();
+        while let Ok(ev) = rx.try_recv() {
+            match ev {
+                SourceEvent::Status { status, .. } => statuses.push(status),
+                SourceEvent::Log { source, line } => {
+                    assert_eq!(source, "ssh/web1:/var/log/app.log");
+                    lines.push(line);
+                }
+                _ => {}
+            }
+        }
+        assert_eq!(statuses, vec![SourceStatus::Running, SourceStatus::Stopped]);
+        assert_eq!(lines, vec!["GET /health 200", "GET /api 500"]);
+
+        let _ = std::fs::remove_dir_all(stub.parent().unwrap());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn reports_connection_failure_and_retries() {
+        let stub = stub_ssh(
+            "fail",
+            "echo 'ssh: connect to host web9 port 22: Connection refused' >&2\nexit 255\n",
+        );
+        let target = SshTarget::parse("web9:journald").unwrap();
+        let (tx, mut rx) = mpsc::channel(64);
+        let ssh = stub.display().to_string();
+        let id = target.source_id();
+        let task = tokio::spawn(async move {
+            run_ssh(&ssh, &target, &id, tx, Duration::from_millis(10)).await;
+        });
+
+        let mut statuses = Vec::new();
+        while statuses.len() < 3 {
+            match tokio::time::timeout(Duration::from_secs(5), rx.recv()).await {
+                Ok(Some(SourceEvent::Status { status, .. })) => statuses.push(status),
+                Ok(Some(_)) => {}
+                _ => panic!("no status from ssh source"),
+            }
+        }
+        task.abort();
+
+        assert_eq!(
+            statuses,
+            vec![
+                SourceStatus::Error("ssh: connect to host web9 port 22: Connection refused".into()),
+                SourceStatus::Starting,
+                SourceStatus::Error("ssh: connect to host web9 port 22: Connection refused".into()),
+            ]
+        );
+
+        let _ = std::fs::remove_dir_all(stub.parent().unwrap());
+    }
+}
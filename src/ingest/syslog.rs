@@ -0,0 +1,509 @@
+use std::collections::HashSet;
+use std::net::SocketAddr;
+
+use anyhow::Result;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, BufReader};
+use tokio::net::{TcpListener, UdpSocket};
+use tokio::sync::mpsc;
+use tokio::task::{JoinHandle, JoinSet};
+
+use super::{SourceEvent, SourceInfo, SourceStatus};
+use crate::parse::Level;
+
+/// Largest frame accepted on TCP (octet-counted or newline-delimited).
+const MAX_FRAME: usize = 64 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyslogProto {
+    Udp,
+    Tcp,
+}
+
+/// Where to listen, parsed from `udp://0.0.0.0:5514` or `tcp://[::]:6514`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyslogListen {
+    pub proto: SyslogProto,
+    pub addr: SocketAddr,
+}
+
+impl SyslogListen {
+    pub fn parse(spec: &str) -> Result<SyslogListen> {
+        let (proto, rest) = if let Some(rest) = spec.strip_prefix("udp://") {
+            (SyslogProto::Udp, rest)
+        } else if let Some(rest) = spec.strip_prefix("tcp://") {
+            (SyslogProto::Tcp, rest)
+        } else {
+            // Bare host:port defaults to UDP, the common case for devices
+            (SyslogProto::Udp, spec)
+        };
+        let addr = rest
+            .parse()
+            .map_err(|e| anyhow::anyhow!("invalid syslog address '{}': {}", rest, e))?;
+        Ok(SyslogListen { proto, addr })
+    }
+
+    pub fn label(&self) -> String {
+        let scheme = match self.proto {
+            SyslogProto::Udp => "udp",
+            SyslogProto::Tcp => "tcp",
+        };
+        format!("{}://{}", scheme, self.addr)
+    }
+}
+
+/// A parsed syslog message (RFC 5424 or RFC 3164).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyslogMessage {
+    pub severity: u8,
+    pub hostname: Option<String>,
+    pub app_name: Option<String>,
+    pub message: String,
+}
+
+impl SyslogMessage {
+    pub fn level(&self) -> Level {
+        Level::from_syslog_severity(self.severity)
+    }
+
+    /// `syslog/<host>/<app>`, falling back to the peer address for the host.
+    pub fn source_id(&self, peer: &SocketAddr) -> String {
+        let host = self
+            .hostname
+            .clone()
+            .unwrap_or_else(|| peer.ip().to_string());
+        match self.app_name {
+            Some(ref app) => format!("syslog/{}/{}", host, app),
+            None => format!("syslog/{}", host),
+        }
+    }
+}
+
+fn nil(field: &str) -> Option<String> {
+    if field.is_empty() || field == "-" {
+        None
+    } else {
+        Some(field.to_string())
+    }
+}
+
+/// Split off the next space-delimited token.
+fn next_token(s: &str) -> (&str, &str) {
+    match s.split_once(' ') {
+        Some((tok, rest)) => (tok, rest),
+        None => (s, ""),
+    }
+}
+
+/// Parse a syslog message. Messages without a valid `<PRI>` are kept whole
+/// with severity "notice" so nothing a device sends is lost.
+pub fn parse_message(raw: &str) -> SyslogMessage {
+    let raw = raw.trim_end_matches(['\r', '\n', '\0']);
+    let Some((pri, rest)) = parse_pri(raw) else {
+        return SyslogMessage {
+            severity: 5,
+            hostname: None,
+            app_name: None,
+            message: raw.to_string(),
+        };
+    };
+    let severity = pri % 8;
+
+    if let Some(rest) = rest.strip_prefix("1 ") {
+        parse_5424(severity, rest)
+    } else {
+        parse_3164(severity, rest)
+    }
+}
+
+fn parse_pri(raw: &str) -> Option<(u8, &str)> {
+    let rest = raw.strip_prefix('<')?;
+    let end = rest.find('>')?;
+    if end == 0 || end > 3 {
+        return None;
+    }
+    let pri: u8 = rest[..end].parse().ok()?;
+    if pri > 191 {
+        return None;
+    }
+    Some((pri, &rest[end + 1..]))
+}
+
+/// `TIMESTAMP HOSTNAME APP-NAME PROCID MSGID STRUCTURED-DATA [MSG]`
+fn parse_5424(severity: u8, rest: &str) -> SyslogMessage {
+    let (_timestamp, rest) = next_token(rest);
+    let (hostname, rest) = next_token(rest);
+    let (app_name, rest) = next_token(rest);
+    let (_procid, rest) = next_token(rest);
+    let (_msgid, rest) = next_token(rest);
+    let message = skip_structured_data(rest);
+    SyslogMessage {
+        severity,
+        hostname: nil(hostname),
+        app_name: nil(app_name),
+        message: message.trim_start_matches('\u{feff}').to_string(),
+    }
+}
+
+/// Skip `-` or one or more `[id k="v" ...]` elements (which may contain
+/// escaped `\]` inside values).
+fn skip_structured_data(s: &str) -> &str {
+    if let Some(rest) = s.strip_prefix('-') {
+        return rest.strip_prefix(' ').unwrap_or(rest);
+    }
+    let bytes = s.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() && bytes[i] == b'[' {
+        let mut in_quotes = false;
+        i += 1;
+        while i < bytes.len() {
+            match bytes[i] {
+                b'\\' => i += 1,
+                b'"' => in_quotes = !in_quotes,
+                b']' if !in_quotes => break,
+                _ => {}
+            }
+            i += 1;
+        }
+        i += 1;
+    }
+    let rest = &s[i.min(s.len())..];
+    rest.strip_prefix(' ').unwrap_or(rest)
+}
+
+/// `Mmm dd hh:mm:ss HOSTNAME TAG[pid]: MSG`. Some devices omit the hostname,
+/// in which case the token after the timestamp is already the tag.
+fn parse_3164(severity: u8, rest: &str) -> SyslogMessage {
+    let mut rest = rest;
+    if looks_like_3164_timestamp(rest) {
+        rest = rest[15..].trim_start();
+    }
+
+    let (first, after_first) = next_token(rest);
+    let (hostname, tagged) = if first.ends_with(':') || first.contains('[') {
+        (None, rest)
+    } else {
+        (nil(first), after_first)
+    };
+
+    let (app_name, message) = match tagged.find(": ") {
+        Some(pos) if !tagged[..pos].contains(' ') => {
+            let tag = &tagged[..pos];
+            let app = tag.split('[').next().unwrap_or(tag);
+            (nil(app), &tagged[pos + 2..])
+        }
+        _ => (None, tagged),
+    };
+
+    SyslogMessage {
+        severity,
+        hostname,
+        app_name,
+        message: message.to_string(),
+    }
+}
+
+fn looks_like_3164_timestamp(s: &str) -> bool {
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+    let b = s.as_bytes();
+    // ASCII so that cutting at byte 15 stays on a char boundary
+    b.len() >= 15
+        && b[..15].is_ascii()
+        && MONTHS.iter().any(|m| s.starts_with(m))
+        && b[3] == b' '
+        && b[6] == b' '
+        && b[9] == b':'
+        && b[12] == b':'
+}
+
+pub fn spawn_syslog(
+    listen: SyslogListen,
+    tx: mpsc::Sender<SourceEvent>,
+) -> (SourceInfo, JoinHandle<()>) {
+    let id = format!("syslog/{}", listen.label());
+    let info = SourceInfo {
+        id: id.clone(),
+        kind: "syslog".into(),
+        status: SourceStatus::Starting,
+    };
+    let handle = tokio::spawn(async move {
+        let result = match listen.proto {
+            SyslogProto::Udp => run_udp(listen.addr, &id, tx.clone()).await,
+            SyslogProto::Tcp => run_tcp(listen.addr, &id, tx.clone()).await,
+        };
+        if let Err(e) = result {
+            let _ = tx
+                .send(SourceEvent::Status {
+                    source: id,
+                    status: SourceStatus::Error(format!("syslog: {}", e)),
+                })
+                .await;
+        }
+    });
+    (info, handle)
+}
+
+/// Tracks which per-device sources have been attached so far.
+struct Devices {
+    seen: HashSet<String>,
+    tx: mpsc::Sender<SourceEvent>,
+}
+
+impl Devices {
+    /// Forward one message; returns false once the receiver is gone.
+    async fn deliver(&mut self, raw: &str, peer: &SocketAddr) -> bool {
+        let msg = parse_message(raw);
+        if msg.message.is_empty() {
+            return true;
+        }
+        let source = msg.source_id(peer);
+        if self.seen.insert(source.clone()) {
+            let _ = self
+                .tx
+                .send(SourceEvent::Attached {
+                    info: SourceInfo {
+                        id: source.clone(),
+                        kind: "syslog".into(),
+                        status: SourceStatus::Running,
+                    },
+                })
+                .await;
+        }
+        self.tx
+            .send(SourceEvent::Record {
+                source,
+                level: Some(msg.level()),
+                line: msg.message,
+                fields: Vec::new(),
+            })
+            .await
+            .is_ok()
+    }
+}
+
+async fn run_udp(addr: SocketAddr, source_id: &str, tx: mpsc::Sender<SourceEvent>) -> Result<()> {
+    let socket = UdpSocket::bind(addr).await?;
+    let _ = tx
+        .send(SourceEvent::Status {
+            source: source_id.to_string(),
+            status: SourceStatus::Running,
+        })
+        .await;
+
+    let mut devices = Devices {
+        seen: HashSet::new(),
+        tx,
+    };
+    let mut buf = vec![0u8; MAX_FRAME];
+    loop {
+        let (n, peer) = socket.recv_from(&mut buf).await?;
+        let raw = String::from_utf8_lossy(&buf[..n]);
+        if !devices.deliver(&raw, &peer).await {
+            return Ok(());
+        }
+    }
+}
+
+async fn run_tcp(addr: SocketAddr, source_id: &str, tx: mpsc::Sender<SourceEvent>) -> Result<()> {
+    let listener = TcpListener::bind(addr).await?;
+    let _ = tx
+        .send(SourceEvent::Status {
+            source: source_id.to_string(),
+            status: SourceStatus::Running,
+        })
+        .await;
+
+    // Connection tasks are aborted with the listener when the source stops
+    let mut connections = JoinSet::new();
+    loop {
+        let (stream, peer) = listener.accept().await?;
+        while connections.try_join_next().is_some() {}
+        let tx = tx.clone();
+        connections.spawn(async move {
+            let mut devices = Devices {
+                seen: HashSet::new(),
+                tx,
+            };
+            let mut reader = BufReader::new(stream);
+            while let Ok(Some(frame)) = read_frame(&mut reader).await {
+                if !devices.deliver(&frame, &peer).await {
+                    break;
+                }
+            }
+        });
+    }
+}
+
+/// Read one TCP frame: RFC 6587 octet counting (`LEN SP MSG`) when the frame
+/// starts with a digit, otherwise newline-delimited.
+async fn read_frame<R>(reader: &mut BufReader<R>) -> Result<Option<String>>
+where
+    R: tokio::io::AsyncRead + Unpin,
+{
+    let first = match reader.fill_buf().await? {
+        [] => return Ok(None),
+        buf => buf[0],
+    };
+
+    if first.is_ascii_digit() {
+        let mut len_buf = Vec::new();
+        // Enough digits for any count up to MAX_FRAME, plus the space
+        reader.take(8).read_until(b' ', &mut len_buf).await?;
+        let len: usize = std::str::from_utf8(&len_buf)?
+            .trim_end()
+            .parse()
+            .map_err(|_| anyhow::anyhow!("bad octet count"))?;
+        if len > MAX_FRAME {
+            return Err(anyhow::anyhow!("frame too large: {}", len));
+        }
+        let mut msg = vec![0u8; len];
+        reader.read_exact(&mut msg).await?;
+        Ok(Some(String::from_utf8_lossy(&msg).into_owned()))
+    } else {
+        let mut line = Vec::new();
+        // Bounded, so a client that never sends a newline can't grow the buffer
+        reader.take(MAX_FRAME as u64 + 1).read_until(b'\n', &mut line).await?;
+        if line.len() > MAX_FRAME {
+            return Err(anyhow::anyhow!("frame too large: over {} bytes", MAX_FRAME));
+        }
+        Ok(Some(String::from_utf8_lossy(&line).into_owned()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn peer() -> SocketAddr {
+        "10.0.0.9:40000".parse().unwrap()
+    }
+
+    #[test]
+    fn parse_listen_spec() {
+        let l = SyslogListen::parse("udp://0.0.0.0:5514").unwrap();
+        assert_eq!(l.proto, SyslogProto::Udp);
+        assert_eq!(l.addr.port(), 5514);
+        let l = SyslogListen::parse("tcp://127.0.0.1:6514").unwrap();
+        assert_eq!(l.proto, SyslogProto::Tcp);
+        assert_eq!(l.label(), "tcp://127.0.0.1:6514");
+        assert!(SyslogListen::parse("udp://nope").is_err());
+    }
+
+    #[test]
+    fn parse_rfc5424() {
+        let m = parse_message(
+            r#"<165>1 2003-10-11T22:14:15.003Z mymachine.example.com evntslog - ID47 [exampleSDID@32473 iut="3" eventSource="Application" eventID="1011"] An application event log entry"#,
+        );
+        assert_eq!(m.severity, 5);
+        assert_eq!(m.level(), Level::Info);
+        assert_eq!(m.hostname.as_deref(), Some("mymachine.example.com"));
+        assert_eq!(m.app_name.as_deref(), Some("evntslog"));
+        assert_eq!(m.message, "An application event log entry");
+        assert_eq!(m.source_id(&peer()), "syslog/mymachine.example.com/evntslog");
+    }
+
+    #[test]
+    fn parse_rfc5424_nil_fields_and_escaped_sd() {
+        let m = parse_message(r#"<11>1 - - - - - [a@1 x="q\]uote"][b@1] disk failure"#);
+        assert_eq!(m.level(), Level::Error);
+        assert!(m.hostname.is_none());
+        assert!(m.app_name.is_none());
+        assert_eq!(m.message, "disk failure");
+        assert_eq!(m.source_id(&peer()), "syslog/10.0.0.9");
+    }
+
+    #[test]
+    fn parse_rfc3164() {
+        let m = parse_message("<34>Oct 11 22:14:15 mymachine su[230]: 'su root' failed for lonvick on /dev/pts/8");
+        assert_eq!(m.severity, 2);
+        assert_eq!(m.level(), Level::Error);
+        assert_eq!(m.hostname.as_deref(), Some("mymachine"));
+        assert_eq!(m.app_name.as_deref(), Some("su"));
+        assert_eq!(m.message, "'su root' failed for lonvick on /dev/pts/8");
+    }
+
+    #[test]
+    fn parse_rfc3164_without_hostname() {
+        let m = parse_message("<188>Feb  3 09:01:02 %LINK-3-UPDOWN: Interface Gi0/1, changed state to down");
+        assert_eq!(m.level(), Level::Warn);
+        assert!(m.hostname.is_none());
+        assert_eq!(m.app_name.as_deref(), Some("%LINK-3-UPDOWN"));
+        assert_eq!(m.source_id(&peer()), "syslog/10.0.0.9/%LINK-3-UPDOWN");
+    }
+
+    #[test]
+    fn parse_rfc3164_with_multibyte_in_timestamp() {
+        // Not a timestamp: parsed as a line without one instead of panicking
+        let m = parse_message("<14>Jan 01 00:00:0é host app: x");
+        assert_eq!(m.severity, 6);
+        assert!(m.message.ends_with("00:00:0é host app: x"));
+    }
+
+    #[test]
+    fn parse_without_pri_keeps_line() {
+        let m = parse_message("plain text from a weird device\n");
+        assert_eq!(m.level(), Level::Info);
+        assert_eq!(m.message, "plain text from a weird device");
+    }
+
+    #[tokio::test]
+    async fn tcp_frames_octet_counted_and_newline() {
+        let data: &[u8] = b"20 <14>1 - h a - - - hi<13>Oct 11 22:14:15 sw1 app: second\n";
+        let mut reader = BufReader::new(data);
+        let first = read_frame(&mut reader).await.unwrap().unwrap();
+        assert_eq!(first, "<14>1 - h a - - - hi");
+        assert_eq!(parse_message(&first).message, "hi");
+        let second = read_frame(&mut reader).await.unwrap().unwrap();
+        assert_eq!(parse_message(&second).message, "second");
+        assert!(read_frame(&mut reader).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn tcp_frame_without_newline_is_capped() {
+        let data = vec![b'x'; MAX_FRAME * 4];
+        let mut reader = BufReader::new(&data[..]);
+        assert!(read_frame(&mut reader).await.is_err());
+        // Reading stopped one byte past the limit
+        let consumed = data.len() - reader.get_ref().len() - reader.buffer().len();
+        assert_eq!(consumed, MAX_FRAME + 1);
+    }
+
+    #[tokio::test]
+    async fn udp_listener_attaches_devices() {
+        let (tx, mut rx) = mpsc::channel(16);
+        // Bind on an ephemeral port, then hand the address to the listener
+        let probe = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let addr = probe.local_addr().unwrap();
+        drop(probe);
+        let task = tokio::spawn(async move {
+            let _ = run_udp(addr, "syslog/udp", tx).await;
+        });
+
+        // Wait for the listener to report Running before sending
+        loop {
+            if let Some(SourceEvent::Status { status: SourceStatus::Running, .. }) = rx.recv().await {
+                break;
+            }
+        }
+        let client = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        client
+            .send_to(b"<12>1 2026-01-01T00:00:00Z fw01 kernel - - - link down", addr)
+            .await
+            .unwrap();
+
+        match rx.recv().await {
+            Some(SourceEvent::Attached { info }) => assert_eq!(info.id, "syslog/fw01/kernel"),
+            _ => panic!("expected Attached"),
+        }
+        match rx.recv().await {
+            Some(SourceEvent::Record { source, line, level, .. }) => {
+                assert_eq!(source, "syslog/fw01/kernel");
+                assert_eq!(line, "link down");
+                assert_eq!(level, Some(Level::Warn));
+            }
+            _ => panic!("expected Record"),
+        }
+        task.abort();
+    }
+}
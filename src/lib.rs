@@ -0,0 +1,63 @@
+//! Log pattern clustering, ingestion and alerting behind the `logradar` TUI.
+//!
+//! The pieces most useful on their own:
+//!
+//! - [`Pipeline`] takes raw lines (or [`ingest::SourceEvent`]s), clusters them
+//!   into patterns and hands back serializable [`PatternSnapshot`]s.
+//! - [`parse`] has level detection and [`parse::normalize`], which turns a
+//!   line into the signature patterns are grouped by.
+//! - [`ingest::Source`] starts any of the built-in sources (Docker, files,
+//!   commands, journald, Kubernetes, SSH, syslog, HTTP, OTLP), each sending
+//!   events over a tokio channel.
+//!
+//! ```
+//! use logradar::{Level, Pipeline};
+//!
+//! let mut pipeline = Pipeline::new().min_level(Level::Info);
+//! pipeline.push("app", "GET /api/users/17 200 in 12ms");
+//! pipeline.push("app", "GET /api/users/42 200 in 9ms");
+//! pipeline.push("app", "DEBUG cache warm");
+//!
+//! let patterns = pipeline.snapshot();
+//! assert_eq!(patterns.len(), 1);
+//! assert_eq!(patterns[0].count, 2);
+//! ```
+//!
+//! The remaining modules (`app`, `tui`, `discovery`, `search`, `watch`) make
+//! up the binary and are not meant to be depended on.
+
+pub mod alerts;
+pub mod color;
+pub mod config;
+pub mod export;
+pub mod ingest;
+pub mod metrics;
+pub mod parse;
+pub mod pattern;
+pub mod pipeline;
+pub mod profile;
+pub mod redact;
+pub mod session;
+pub mod theme;
+
+#[doc(hidden)]
+pub mod app;
+#[doc(hidden)]
+pub mod discovery;
+#[doc(hidden)]
+pub mod reload;
+#[doc(hidden)]
+pub mod search;
+#[doc(hidden)]
+pub mod tui;
+#[doc(hidden)]
+pub mod watch;
+#[doc(hidden)]
+pub mod workspace;
+
+mod util;
+
+pub use ingest::{Source, SourceEvent, SourceInfo, SourceStatus};
+pub use parse::{Level, LogEvent};
+pub use pattern::{Pattern, PatternSnapshot, PatternStore, Trend};
+pub use pipeline::Pipeline;
@@ -1,26 +1,12 @@
-mod app;
-mod config;
-mod discovery;
-mod ingest;
-mod parse;
-mod pattern;
-mod profile;
-mod search;
-mod theme;
-mod tui;
-mod util;
-
-use anyhow::Result;
-use clap::{Parser, Subcommand};
-use crossterm::{
-    event::{self, Event, KeyCode},
-    terminal::{self, EnterAlternateScreen, LeaveAlternateScreen},
-};
-use ratatui::backend::CrosstermBackend;
+
+use anyhow::{Context, Result};
+use clap::{Args, Parser, Subcommand};
+use std::net::SocketAddr;
 use std::time::Duration;
 
-use app::{AppMode, Pane};
-use tui::source_menu::SourceMenuScreen;
+use logradar::{
+    alerts, app, color, config, discovery, ingest, metrics, redact, reload, session, theme, tui, watch, workspace, Source,
+};
 
 #[derive(Parser)]
 #[command(name = "logradar", version, about = "Modern log analysis TUI")]
@@ -29,6 +15,105 @@ struct Cli {
     command: Commands,
 }
 
+/// Sources to start, shared by `tui` and `watch`.
+#[derive(Args)]
+struct SourceArgs {
+    /// Docker container to tail
+    #[arg(long)]
+    docker: Vec<String>,
+
+    /// Shell command to stream
+    #[arg(long)]
+    cmd: Vec<String>,
+
+    /// File path to tail
+    #[arg(long)]
+    file: Vec<String>,
+
+    /// Follow a remote file or journal over ssh: user@host:/var/log/app.log or host:journald[:unit,...]
+    #[arg(long)]
+    ssh: Vec<String>,
+
+    /// Kubernetes pods to follow: namespace/label=selector, namespace/pod or namespace/
+    #[arg(long)]
+    k8s: Vec<String>,
+
+    /// Listen for syslog (RFC 3164/5424): udp://0.0.0.0:5514 or tcp://0.0.0.0:6514
+    #[arg(long)]
+    syslog: Vec<String>,
+
+    /// Accept pushed logs over HTTP (text, JSON, NDJSON, Loki push), e.g. 127.0.0.1:8088
+    #[arg(long)]
+    http_listen: Vec<SocketAddr>,
+
+    /// Receive OpenTelemetry logs over OTLP/HTTP (protobuf or JSON), e.g. 0.0.0.0:4318
+    #[arg(long)]
+    otlp_listen: Vec<SocketAddr>,
+
+    /// Follow the systemd journal (journalctl -o json -f)
+    #[arg(long)]
+    journald: bool,
+
+    /// journald: only follow this unit (repeatable, implies --journald)
+    #[arg(long)]
+    unit: Vec<String>,
+
+    /// journald: minimum priority, e.g. "warning" or "0..4" (implies --journald)
+    #[arg(long)]
+    priority: Option<String>,
+
+    /// journald: start from this time, e.g. "-1h" (implies --journald)
+    #[arg(long)]
+    since: Option<String>,
+
+    /// Start the sources of a [workspaces.NAME] config table, with its profile and theme
+    #[arg(long, value_name = "NAME")]
+    workspace: Option<String>,
+}
+
+impl SourceArgs {
+    fn is_empty(&self) -> bool {
+        self.docker.is_empty()
+            && self.cmd.is_empty()
+            && self.file.is_empty()
+            && self.ssh.is_empty()
+            && self.k8s.is_empty()
+            && self.syslog.is_empty()
+            && self.http_listen.is_empty()
+            && self.otlp_listen.is_empty()
+            && self.journal_filter().is_none()
+            && self.workspace.is_none()
+    }
+
+    fn into_sources(self) -> Result<Vec<Source>> {
+        let journal = self.journal_filter();
+        let mut sources = Vec::new();
+        sources.extend(self.docker.into_iter().map(Source::Docker));
+        sources.extend(self.cmd.into_iter().map(Source::Command));
+        sources.extend(self.file.into_iter().map(Source::File));
+        for spec in &self.ssh {
+            sources.push(Source::ssh(spec)?);
+        }
+        sources.extend(self.k8s.iter().map(|spec| Source::kubernetes(spec)));
+        sources.extend(journal.map(Source::Journald));
+        for spec in &self.syslog {
+            sources.push(Source::syslog(spec)?);
+        }
+        sources.extend(self.http_listen.into_iter().map(Source::Http));
+        sources.extend(self.otlp_listen.into_iter().map(Source::Otlp));
+        Ok(sources)
+    }
+
+    fn journal_filter(&self) -> Option<ingest::journald::JournalFilter> {
+        (self.journald || !self.unit.is_empty() || self.priority.is_some() || self.since.is_some())
+            .then(|| ingest::journald::JournalFilter {
+                units: self.unit.clone(),
+                priority: self.priority.clone(),
+                since: self.since.clone(),
+            })
+    }
+}
+
 #[derive(Subcommand)]
 enum Commands {
     /// Launch the TUI dashboard
@@ -37,29 +122,102 @@ enum Commands {
         #[arg(long)]
         profile: Option<String>,
 
-        /// Docker container to tail
+        #[command(flatten)]
+        sources: SourceArgs,
+
+        /// Serve Prometheus metrics (pattern, source and drop counters) on /metrics, e.g. 127.0.0.1:9464
+        #[arg(long, value_name = "ADDR")]
+        metrics_listen: Option<SocketAddr>,
+
+        /// Config file merged on top of the system, user and project (./logradar.toml) files; LOGRADAR_* variables still win. See `logradar config show`
+        #[arg(long)]
+        config: Option<String>,
+
+        /// Theme name (matrix, nebula, frostbyte, ember, deepwave, signal, obsidian, mono, or a [themes] entry)
+        #[arg(long)]
+        theme: Option<String>,
+
+        /// Disable ASCII banner header
         #[arg(long)]
-        docker: Vec<String>,
+        no_banner: bool,
 
-        /// Shell command to stream
+        /// Leave the mouse to the terminal (native text selection)
         #[arg(long)]
-        cmd: Vec<String>,
+        no_mouse: bool,
 
-        /// File path to tail
+        /// Colors to draw with: auto (NO_COLOR, COLORTERM, TERM), always (truecolor), never, 256 or 16
+        #[arg(long, value_enum, default_value_t = color::ColorChoice::Auto)]
+        color: color::ColorChoice,
+
+        /// Record every source event to a session file for later --replay
+        #[arg(long, value_name = "FILE", conflicts_with = "replay")]
+        record: Option<String>,
+
+        /// Play back a session recorded with --record
+        #[arg(long, value_name = "FILE")]
+        replay: Option<String>,
+
+        /// Replay speed: 1x, 10x, 0.5x or max
+        #[arg(long, default_value = "1x", requires = "replay")]
+        replay_speed: String,
+    },
+    /// Run sources, clustering and alert rules without a terminal UI
+    Watch {
+        /// Profile name (default, ops, network, or custom)
         #[arg(long)]
-        file: Vec<String>,
+        profile: Option<String>,
 
-        /// Path to config file (default: ./logradar.toml or ~/.config/logradar/config.toml)
+        #[command(flatten)]
+        sources: SourceArgs,
+
+        /// Serve Prometheus metrics (pattern, source and drop counters) on /metrics, e.g. 127.0.0.1:9464
+        #[arg(long, value_name = "ADDR")]
+        metrics_listen: Option<SocketAddr>,
+
+        /// Config file merged on top of the system, user and project (./logradar.toml) files; LOGRADAR_* variables still win. See `logradar config show`
         #[arg(long)]
         config: Option<String>,
 
-        /// Theme name (matrix, nebula, frostbyte, ember, deepwave, signal, obsidian, mono)
+        /// Seconds between summaries
+        #[arg(long, value_name = "SECS", default_value_t = 60)]
+        interval: u64,
+
+        /// Summary and alert output format
+        #[arg(long, value_enum, default_value_t = watch::WatchFormat::Text)]
+        format: watch::WatchFormat,
+
+        /// Append output to this file instead of stdout
+        #[arg(long, value_name = "FILE")]
+        output: Option<String>,
+
+        /// Stop after this many seconds
+        #[arg(long, value_name = "SECS")]
+        duration: Option<u64>,
+
+        /// Exit as soon as an alert fires instead of at the end
         #[arg(long)]
-        theme: Option<String>,
+        fail_fast: bool,
+    },
+    /// Inspect the config merged from system, user, project and --config files and LOGRADAR_* variables
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
+}
 
-        /// Disable ASCII banner header
+#[derive(Subcommand)]
+enum ConfigCommand {
+    /// Print the merged config, each value commented with the layer it comes from
+    Show {
+        /// Extra config file layered on top (as with tui --config)
         #[arg(long)]
-        no_banner: bool,
+        config: Option<String>,
+    },
+    /// Check every config file, then the merged config
+    Validate {
+        /// Extra config file layered on top (as with tui --config)
+        #[arg(long)]
+        config: Option<String>,
     },
 }
 
@@ -70,69 +228,270 @@ async fn main() -> Result<()> {
     match cli.command {
         Commands::Tui {
             profile,
-            docker,
-            cmd,
-            file,
+            sources,
+            metrics_listen,
             config: config_path,
             theme: theme_name,
             no_banner,
+            no_mouse,
+            color,
+            record,
+            replay,
+            replay_speed,
+        } => {
+            run_tui(
+                profile,
+                sources,
+                metrics_listen,
+                config_path,
+                theme_name,
+                no_banner,
+                no_mouse,
+                color,
+                record,
+                replay,
+                replay_speed,
+            )
+            .await?;
+        }
+        Commands::Watch {
+            profile,
+            sources,
+            metrics_listen,
+            config: config_path,
+            interval,
+            format,
+            output,
+            duration,
+            fail_fast,
         } => {
-            run_tui(profile, docker, cmd, file, config_path, theme_name, no_banner).await?;
+            let options = watch::WatchOptions {
+                interval: Duration::from_secs(interval.max(1)),
+                format,
+                output,
+                duration: duration.map(Duration::from_secs),
+                fail_fast,
+            };
+            let alerted = run_watch(profile, sources, metrics_listen, config_path, options).await?;
+            if alerted {
+                std::process::exit(watch::ALERT_EXIT_CODE);
+            }
         }
+        Commands::Config { command } => match command {
+            ConfigCommand::Show { config: config_path } => show_config(config_path.as_deref())?,
+            ConfigCommand::Validate { config: config_path } => validate_config(config_path.as_deref())?,
+        },
     }
 
     Ok(())
 }
 
+/// Build the app from the merged config: profiles and compiled alert rules.
+/// A `--profile` beats the workspace's, which beats `default_profile`.
+fn load_app(config_path: Option<&str>, profile: Option<String>, workspace: Option<&str>) -> Result<app::App> {
+    let mut cfg = config::Config::load(config_path)?;
+    let workspaces = std::mem::take(&mut cfg.workspaces);
+    let workspace_profile = match workspace {
+        Some(name) => {
+            let ws = workspaces.get(name).with_context(|| {
+                let names: Vec<&str> = workspaces.keys().map(String::as_str).collect();
+                format!("unknown workspace '{}' (available: {})", name, names.join(", "))
+            })?;
+            ws.profile.clone()
+        }
+        None => None,
+    };
+    let default_profile = cfg.default_profile.clone();
+    let alerts = alerts::AlertEngine::from_config(&cfg.alerts)?;
+    let redactor = redact::Redactor::from_config(&cfg.redaction)?;
+    let layout = tui::layout::LayoutState::from_config(&cfg.layout);
+    let keymap = tui::keymap::Keymap::from_config(&cfg.keys)?;
+    let themes = cfg.custom_themes()?;
+    let profiles = cfg.into_profiles(&themes);
+    workspace::check(&workspaces, &profiles, &themes)?;
+    let profile_name = profile.or(workspace_profile).or(default_profile);
+    let mut app = app::App::with_profiles(profiles, profile_name.as_deref());
+    app.workspaces = workspaces;
+    app.custom_themes = themes;
+    app.alerts = alerts;
+    app.redactor = redactor;
+    app.layout_state = layout;
+    app.keymap = keymap;
+    app.config_path = config::Config::locate(config_path).or_else(config::Config::global_path);
+    app.config_watcher = Some(reload::ConfigWatcher::new(config::Config::layer_files(config_path)));
+    Ok(app)
+}
+
+/// `config show`: the layers, then the merged config with origins.
+fn show_config(config_path: Option<&str>) -> Result<()> {
+    let files = config::Config::layer_files(config_path);
+    let layered = config::LayeredConfig::load(&files, std::env::vars())?;
+    println!("# Layers, lowest precedence first:");
+    for file in &files {
+        let missing = if layered.files.contains(file) { "" } else { " (not found)" };
+        println!("#   {:<9} {}{}", file.layer.name(), file.path.display(), missing);
+    }
+    println!("#   {:<9} LOGRADAR_* variables", config::ConfigLayer::Env.name());
+    println!("# Anything not set below has its built-in default.");
+    println!();
+    print!("{}", layered.show());
+    Ok(())
+}
+
+/// `config validate`: parse each file on its own, then build the app from
+/// the merged config so every setting gets checked.
+fn validate_config(config_path: Option<&str>) -> Result<()> {
+    let mut failed = false;
+    for file in config::Config::layer_files(config_path) {
+        let status = match file.read() {
+            Ok(Some(_)) => "ok".to_string(),
+            Ok(None) => "not found".to_string(),
+            Err(e) => {
+                failed = true;
+                format!("error: {:#}", e)
+            }
+        };
+        println!("{:<9} {}: {}", file.layer.name(), file.path.display(), status);
+    }
+    if failed {
+        std::process::exit(1);
+    }
+    load_app(config_path, None, None).context("merged config")?;
+    println!("merged config: ok");
+    Ok(())
+}
+
+/// Start everything given on the command line, then the `--workspace`
+/// sources. Bad specs print and exit.
+fn spawn_sources(app: &mut app::App, sources: SourceArgs) {
+    let workspace = sources.workspace.clone();
+    match sources.into_sources() {
+        Ok(sources) => {
+            for source in sources {
+                app.add_source(source);
+            }
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    }
+    if let Some(name) = workspace {
+        let started = app.workspace(&name).and_then(|ws| app.start_workspace_sources(&ws));
+        if let Err(e) = started {
+            eprintln!("[workspaces.{}]: {:#}", name, e);
+            std::process::exit(1);
+        }
+    }
+}
+
+async fn start_metrics(
+    app: &mut app::App,
+    metrics_listen: Option<SocketAddr>,
+) -> Result<Option<tokio::task::JoinHandle<()>>> {
+    match metrics_listen {
+        Some(addr) => {
+            let shared = metrics::SharedMetrics::default();
+            app.metrics = Some(shared.clone());
+            Ok(Some(metrics::spawn_metrics(addr, shared).await?))
+        }
+        None => Ok(None),
+    }
+}
+
+async fn run_watch(
+    profile: Option<String>,
+    sources: SourceArgs,
+    metrics_listen: Option<SocketAddr>,
+    config_path: Option<String>,
+    options: watch::WatchOptions,
+) -> Result<bool> {
+    if sources.is_empty() {
+        anyhow::bail!("watch needs at least one source (--docker, --cmd, --file, --journald, --workspace, ...)");
+    }
+    let mut app = load_app(config_path.as_deref(), profile, sources.workspace.as_deref())?;
+    if app.alerts.is_empty() {
+        eprintln!("logradar: no [[alerts]] rules configured; only summaries will be written");
+    }
+    let (tx, rx) = tokio::sync::mpsc::channel(1024);
+    app.set_tx(tx);
+    let _metrics_server = start_metrics(&mut app, metrics_listen).await?;
+    spawn_sources(&mut app, sources);
+    watch::run(app, rx, options).await
+}
+
+#[allow(clippy::too_many_arguments)]
 async fn run_tui(
     profile: Option<String>,
-    dockers: Vec<String>,
-    cmds: Vec<String>,
-    files: Vec<String>,
+    sources: SourceArgs,
+    metrics_listen: Option<SocketAddr>,
     config_path: Option<String>,
     theme_name: Option<String>,
     no_banner: bool,
+    no_mouse: bool,
+    color: color::ColorChoice,
+    record: Option<String>,
+    replay: Option<String>,
+    replay_speed: String,
 ) -> Result<()> {
-    let cfg = config::Config::load(config_path.as_deref())?;
-    let default_profile = cfg.default_profile.clone();
-    let profiles = cfg.into_profiles();
-
-    let profile_name = profile.or(default_profile);
-    let mut app = app::App::with_profiles(profiles, profile_name.as_deref());
+    // Load the recording up front so a bad file fails before the TUI starts
+    let replay = match replay {
+        Some(path) => {
+            let recording = session::Recording::load(&path)?;
+            let speed = session::ReplaySpeed::parse(&replay_speed)?;
+            Some((path, recording, speed))
+        }
+        None => None,
+    };
+
+    // A replay uses the profile it was recorded with unless one is given
+    let recorded_profile = replay.as_ref().and_then(|(_, r, _)| r.profile.clone());
+    let mut app = load_app(
+        config_path.as_deref(),
+        profile.or(recorded_profile),
+        sources.workspace.as_deref(),
+    )?;
     app.show_banner = !no_banner;
-
-    // Apply --theme override
-    if let Some(ref name) = theme_name {
-        if let Some(t) = theme::Theme::by_name(name) {
+    app.mouse = !no_mouse;
+    app.color_depth = color::ColorDepth::resolve(color);
+
+    // Apply --theme override, else the workspace's theme
+    let workspace_theme = sources
+        .workspace
+        .as_deref()
+        .and_then(|name| app.workspaces.get(name))
+        .and_then(|ws| ws.theme.clone());
+    if let Some(ref name) = theme_name.or(workspace_theme) {
+        if let Some(t) = theme::Theme::by_name(name, &app.custom_themes) {
             app.theme_override = Some(t);
         } else {
             eprintln!(
                 "Unknown theme '{}'. Available: {}",
                 name,
-                theme::Theme::all_names().join(", ")
+                theme::Theme::all_names(&app.custom_themes).join(", ")
             );
             std::process::exit(1);
         }
     }
-    let (tx, mut rx) = tokio::sync::mpsc::channel(1024);
+    let (tx, rx) = tokio::sync::mpsc::channel(1024);
     app.set_tx(tx.clone());
 
+    let _metrics_server = start_metrics(&mut app, metrics_listen).await?;
+
+    if let Some(ref path) = record {
+        app.recorder = Some(session::Recorder::create(path, &app.profile().name)?);
+    }
+
     // Discovery channel
-    let (discovery_tx, mut discovery_rx) =
+    let (discovery_tx, discovery_rx) =
         tokio::sync::mpsc::channel::<discovery::DiscoveryResult>(16);
     app.discovery_tx = Some(discovery_tx);
 
     // Spawn ingest sources from CLI
-    let has_cli_sources =
-        !dockers.is_empty() || !cmds.is_empty() || !files.is_empty();
-    for container in dockers {
-        app.add_docker_source(container);
-    }
-    for cmd_str in cmds {
-        app.add_command_source(cmd_str);
-    }
-    for path in files {
-        app.add_file_source(path);
+    let has_cli_sources = !sources.is_empty() || replay.is_some();
+    spawn_sources(&mut app, sources);
+    if let Some((path, recording, speed)) = replay {
+        app.add_replay_source(&path, recording, speed);
     }
 
     // Keep tx alive for dynamic source additions (drop our local clone)
@@ -143,338 +502,5 @@ async fn run_tui(
         app.open_source_menu();
     }
 
-    // Setup terminal
-    terminal::enable_raw_mode()?;
-    let mut stdout = std::io::stdout();
-    crossterm::execute!(stdout, EnterAlternateScreen)?;
-    let backend = CrosstermBackend::new(stdout);
-    let mut terminal = ratatui::Terminal::new(backend)?;
-
-    let tick_rate = Duration::from_millis(50);
-
-    loop {
-        if app.needs_clear {
-            terminal.clear()?;
-            app.needs_clear = false;
-        }
-        app.update_filtered_view();
-        terminal.draw(|f| tui::ui::render(f, &mut app))?;
-
-        if event::poll(tick_rate)? {
-            if let Event::Key(key) = event::read()? {
-                handle_key_event(&mut app, key);
-            }
-        }
-
-        // Drain log events (non-blocking)
-        loop {
-            match rx.try_recv() {
-                Ok(ev) => app.process_event(ev),
-                Err(_) => break,
-            }
-        }
-
-        // Drain discovery results
-        loop {
-            match discovery_rx.try_recv() {
-                Ok(result) => app.handle_discovery_result(result),
-                Err(_) => break,
-            }
-        }
-
-        app.store.tick();
-        app.tick_source_rates();
-        app.tick_count += 1;
-
-        if app.should_quit {
-            break;
-        }
-    }
-
-    // Restore terminal
-    terminal::disable_raw_mode()?;
-    crossterm::execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
-    terminal.show_cursor()?;
-
-    Ok(())
-}
-
-fn handle_key_event(app: &mut app::App, key: event::KeyEvent) {
-    // Search mode: capture all input
-    if app.mode == AppMode::Search {
-        match key.code {
-            KeyCode::Esc => app.exit_search(false),
-            KeyCode::Enter => app.exit_search(true),
-            KeyCode::Backspace => {
-                app.search_query.pop();
-            }
-            KeyCode::Char(c) => app.search_query.push(c),
-            _ => {}
-        }
-        return;
-    }
-
-    // Source menu
-    if app.mode == AppMode::SourceMenu {
-        handle_source_menu_key(app, key);
-        return;
-    }
-
-    // Profile picker
-    if app.mode == AppMode::ProfilePicker {
-        match key.code {
-            KeyCode::Esc => app.mode = AppMode::Normal,
-            KeyCode::Up | KeyCode::Char('k') => app.prev_profile(),
-            KeyCode::Down | KeyCode::Char('j') => app.next_profile(),
-            KeyCode::Enter => app.mode = AppMode::Normal,
-            _ => {}
-        }
-        return;
-    }
-
-    // Help overlay
-    if app.mode == AppMode::Help {
-        match key.code {
-            KeyCode::Esc | KeyCode::Char('?') | KeyCode::Char('q') => {
-                app.mode = AppMode::Normal;
-                app.needs_clear = true;
-            }
-            _ => {}
-        }
-        return;
-    }
-
-    // Drilldown
-    if app.mode == AppMode::Drilldown {
-        match key.code {
-            KeyCode::Esc | KeyCode::Char('b') => {
-                app.mode = AppMode::Normal;
-                app.detail_scroll = 0;
-                app.needs_clear = true;
-            }
-            KeyCode::Up | KeyCode::Char('k') => {
-                if app.detail_scroll > 0 {
-                    app.detail_scroll -= 1;
-                }
-            }
-            KeyCode::Down | KeyCode::Char('j') => {
-                if let Some(p) = app.selected_pattern_data() {
-                    if app.detail_scroll + 1 < p.samples.len() {
-                        app.detail_scroll += 1;
-                    }
-                }
-            }
-            KeyCode::Char('n') => app.show_normalized = !app.show_normalized,
-            KeyCode::Char('q') => app.should_quit = true,
-            _ => {}
-        }
-        return;
-    }
-
-    // Normal mode
-    match key.code {
-        KeyCode::Esc => {
-            // Clear active search filter or source filter
-            if !app.search_query.is_empty() {
-                app.search_query.clear();
-                app.selected_pattern = 0;
-                app.needs_clear = true;
-            } else if app.active_source_filter.is_some() {
-                app.active_source_filter = None;
-                app.selected_pattern = 0;
-                app.needs_clear = true;
-            }
-        }
-        KeyCode::Char('q') => app.should_quit = true,
-        KeyCode::Char('?') => app.mode = AppMode::Help,
-        KeyCode::Char('/') => app.enter_search(),
-        KeyCode::Char('a') => app.open_source_menu(),
-        KeyCode::Char('p') => app.paused = !app.paused,
-        KeyCode::Char('P') => app.mode = AppMode::ProfilePicker,
-        KeyCode::Char('r') => {
-            app.store.reset();
-            app.needs_clear = true;
-        }
-        KeyCode::Char('c') => {
-            app.store.clear_counters();
-            app.needs_clear = true;
-        }
-        KeyCode::Char('n') => app.show_normalized = !app.show_normalized,
-        KeyCode::Char('t') => app.toggle_theme(),
-        KeyCode::Tab => app.next_pane(),
-        KeyCode::BackTab => app.prev_pane(),
-        KeyCode::Up | KeyCode::Char('k') => app.move_up(),
-        KeyCode::Down | KeyCode::Char('j') => app.move_down(),
-        KeyCode::Enter => {
-            if app.active_pane == Pane::Patterns && app.selected_pattern_data().is_some() {
-                app.mode = AppMode::Drilldown;
-                app.detail_scroll = 0;
-                app.needs_clear = true;
-            } else if app.active_pane == Pane::Sources {
-                app.activate_selected_source();
-            }
-        }
-        _ => {}
-    }
-}
-
-fn handle_source_menu_key(app: &mut app::App, key: event::KeyEvent) {
-    let screen = app.source_menu.screen;
-
-    match screen {
-        SourceMenuScreen::MainMenu => match key.code {
-            KeyCode::Esc => {
-                app.mode = AppMode::Normal;
-                app.needs_clear = true;
-            }
-            KeyCode::Up | KeyCode::Char('k') => {
-                if app.source_menu.main_cursor > 0 {
-                    app.source_menu.main_cursor -= 1;
-                }
-            }
-            KeyCode::Down | KeyCode::Char('j') => {
-                if app.source_menu.main_cursor < tui::source_menu::MAIN_MENU_ITEMS.len() - 1 {
-                    app.source_menu.main_cursor += 1;
-                }
-            }
-            KeyCode::Enter => {
-                match app.source_menu.main_cursor {
-                    0 => {
-                        // Docker Container — results already pre-fetched
-                        app.source_menu.screen = SourceMenuScreen::DockerDiscovery;
-                        app.source_menu.discovery_cursor = 0;
-                        app.source_menu.selected.clear();
-                    }
-                    1 => {
-                        // File (tail)
-                        app.source_menu.screen = SourceMenuScreen::FileInput;
-                        app.source_menu.text_input.clear();
-                    }
-                    2 => {
-                        // Azure Container App — results already pre-fetched
-                        app.source_menu.screen = SourceMenuScreen::AzureDiscovery;
-                        app.source_menu.discovery_cursor = 0;
-                        app.source_menu.selected.clear();
-                    }
-                    3 => {
-                        // Custom Command
-                        app.source_menu.screen = SourceMenuScreen::CommandInput;
-                        app.source_menu.text_input.clear();
-                    }
-                    _ => {}
-                }
-            }
-            _ => {}
-        },
-        SourceMenuScreen::DockerDiscovery => match key.code {
-            KeyCode::Esc => {
-                app.source_menu.screen = SourceMenuScreen::MainMenu;
-            }
-            KeyCode::Up | KeyCode::Char('k') => {
-                if app.source_menu.discovery_cursor > 0 {
-                    app.source_menu.discovery_cursor -= 1;
-                }
-            }
-            KeyCode::Down | KeyCode::Char('j') => {
-                let count = app.source_menu.docker_containers.len();
-                if count > 0 && app.source_menu.discovery_cursor < count - 1 {
-                    app.source_menu.discovery_cursor += 1;
-                }
-            }
-            KeyCode::Char(' ') => {
-                app.source_menu.toggle_selection();
-            }
-            KeyCode::Char('r') => {
-                app.source_menu.docker_loading = true;
-                app.source_menu.docker_error = None;
-                app.source_menu.docker_containers.clear();
-                app.source_menu.selected.clear();
-                app.source_menu.discovery_cursor = 0;
-                if let Some(dtx) = app.discovery_tx.clone() {
-                    discovery::discover_docker(dtx);
-                }
-            }
-            KeyCode::Enter => {
-                app.spawn_selected_docker_sources();
-                app.mode = AppMode::Normal;
-                app.needs_clear = true;
-            }
-            _ => {}
-        },
-        SourceMenuScreen::AzureDiscovery => match key.code {
-            KeyCode::Esc => {
-                app.source_menu.screen = SourceMenuScreen::MainMenu;
-            }
-            KeyCode::Up | KeyCode::Char('k') => {
-                if app.source_menu.discovery_cursor > 0 {
-                    app.source_menu.discovery_cursor -= 1;
-                }
-            }
-            KeyCode::Down | KeyCode::Char('j') => {
-                let count = app.source_menu.azure_apps.len();
-                if count > 0 && app.source_menu.discovery_cursor < count - 1 {
-                    app.source_menu.discovery_cursor += 1;
-                }
-            }
-            KeyCode::Char(' ') => {
-                app.source_menu.toggle_selection();
-            }
-            KeyCode::Char('r') => {
-                app.source_menu.azure_loading = true;
-                app.source_menu.azure_error = None;
-                app.source_menu.azure_apps.clear();
-                app.source_menu.selected.clear();
-                app.source_menu.discovery_cursor = 0;
-                if let Some(dtx) = app.discovery_tx.clone() {
-                    discovery::discover_azure(dtx);
-                }
-            }
-            KeyCode::Enter => {
-                app.spawn_selected_azure_sources();
-                app.mode = AppMode::Normal;
-                app.needs_clear = true;
-            }
-            _ => {}
-        },
-        SourceMenuScreen::FileInput => match key.code {
-            KeyCode::Esc => {
-                app.source_menu.screen = SourceMenuScreen::MainMenu;
-            }
-            KeyCode::Backspace => {
-                app.source_menu.text_input.pop();
-            }
-            KeyCode::Enter => {
-                let path = app.source_menu.text_input.trim().to_string();
-                if !path.is_empty() {
-                    app.add_file_source(path);
-                }
-                app.mode = AppMode::Normal;
-                app.needs_clear = true;
-            }
-            KeyCode::Char(c) => {
-                app.source_menu.text_input.push(c);
-            }
-            _ => {}
-        },
-        SourceMenuScreen::CommandInput => match key.code {
-            KeyCode::Esc => {
-                app.source_menu.screen = SourceMenuScreen::MainMenu;
-            }
-            KeyCode::Backspace => {
-                app.source_menu.text_input.pop();
-            }
-            KeyCode::Enter => {
-                let cmd = app.source_menu.text_input.trim().to_string();
-                if !cmd.is_empty() {
-                    app.add_command_source(cmd);
-                }
-                app.mode = AppMode::Normal;
-                app.needs_clear = true;
-            }
-            KeyCode::Char(c) => {
-                app.source_menu.text_input.push(c);
-            }
-            _ => {}
-        },
-    }
+    tui::run(app, rx, discovery_rx)
 }
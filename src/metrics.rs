@@ -0,0 +1,236 @@
+//! Prometheus metrics endpoint (`--metrics-listen`).
+//!
+//! The app publishes a snapshot of its counters about once a second; the
+//! listener renders the latest snapshot in the Prometheus text format on
+//! `GET /metrics`.
+
+use std::fmt::Write as _;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+
+use anyhow::Result;
+use hyper::{Method, StatusCode};
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+
+use crate::ingest::http::{serve, HttpRequest, HttpResponse};
+
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PatternMetric {
+    pub id: String,
+    pub level: &'static str,
+    pub canonical: String,
+    pub count: u64,
+    pub rate_1m: f64,
+    pub spike: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourceMetric {
+    pub id: String,
+    pub kind: String,
+    pub lines: u64,
+    pub rate_1m: f64,
+    pub up: bool,
+}
+
+/// Everything exported, as of the last publish.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MetricsSnapshot {
+    pub patterns: Vec<PatternMetric>,
+    pub sources: Vec<SourceMetric>,
+    pub lines: u64,
+    pub dropped_paused: u64,
+    pub dropped_below_level: u64,
+}
+
+pub type SharedMetrics = Arc<Mutex<MetricsSnapshot>>;
+
+/// Escape a label value (backslash, double quote, newline).
+fn label(value: &str) -> String {
+    value
+        .replace('\\', r"\\")
+        .replace('"', "\\\"")
+        .replace('\n', r"\n")
+}
+
+fn header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+}
+
+/// Render a snapshot in the Prometheus text exposition format.
+pub fn render(snapshot: &MetricsSnapshot) -> String {
+    let mut out = String::new();
+
+    header(&mut out, "logradar_lines_total", "counter", "Lines ingested into the pattern store.");
+    let _ = writeln!(out, "logradar_lines_total {}", snapshot.lines);
+
+    header(&mut out, "logradar_dropped_lines_total", "counter", "Lines received but not ingested, by reason.");
+    let _ = writeln!(out, "logradar_dropped_lines_total{{reason=\"paused\"}} {}", snapshot.dropped_paused);
+    let _ = writeln!(out, "logradar_dropped_lines_total{{reason=\"below_level\"}} {}", snapshot.dropped_below_level);
+
+    header(&mut out, "logradar_patterns", "gauge", "Patterns in the store.");
+    let _ = writeln!(out, "logradar_patterns {}", snapshot.patterns.len());
+
+    header(&mut out, "logradar_pattern_info", "gauge", "Canonical text of each pattern id.");
+    for p in &snapshot.patterns {
+        let _ = writeln!(
+            out,
+            "logradar_pattern_info{{id=\"{}\",level=\"{}\",pattern=\"{}\"}} 1",
+            p.id,
+            p.level,
+            label(&p.canonical)
+        );
+    }
+    header(&mut out, "logradar_pattern_lines_total", "counter", "Lines matched per pattern.");
+    for p in &snapshot.patterns {
+        let _ = writeln!(out, "logradar_pattern_lines_total{{id=\"{}\",level=\"{}\"}} {}", p.id, p.level, p.count);
+    }
+    header(&mut out, "logradar_pattern_rate_1m", "gauge", "Lines per pattern in the last minute.");
+    for p in &snapshot.patterns {
+        let _ = writeln!(out, "logradar_pattern_rate_1m{{id=\"{}\",level=\"{}\"}} {}", p.id, p.level, p.rate_1m);
+    }
+    header(&mut out, "logradar_pattern_spike", "gauge", "1 while a pattern is flagged as spiking.");
+    for p in &snapshot.patterns {
+        let _ = writeln!(out, "logradar_pattern_spike{{id=\"{}\",level=\"{}\"}} {}", p.id, p.level, p.spike as u8);
+    }
+
+    header(&mut out, "logradar_source_lines_total", "counter", "Lines received per source.");
+    for s in &snapshot.sources {
+        let _ = writeln!(
+            out,
+            "logradar_source_lines_total{{source=\"{}\",kind=\"{}\"}} {}",
+            label(&s.id),
+            label(&s.kind),
+            s.lines
+        );
+    }
+    header(&mut out, "logradar_source_rate_1m", "gauge", "Lines per source in the last minute.");
+    for s in &snapshot.sources {
+        let _ = writeln!(
+            out,
+            "logradar_source_rate_1m{{source=\"{}\",kind=\"{}\"}} {}",
+            label(&s.id),
+            label(&s.kind),
+            s.rate_1m
+        );
+    }
+    header(&mut out, "logradar_source_up", "gauge", "1 while a source is starting or running.");
+    for s in &snapshot.sources {
+        let _ = writeln!(
+            out,
+            "logradar_source_up{{source=\"{}\",kind=\"{}\"}} {}",
+            label(&s.id),
+            label(&s.kind),
+            s.up as u8
+        );
+    }
+    out
+}
+
+fn handle(req: &HttpRequest, shared: &SharedMetrics) -> HttpResponse {
+    match (req.path.as_str(), &req.method) {
+        ("/metrics", &Method::GET) => {
+            let body = render(&shared.lock().unwrap());
+            HttpResponse {
+                status: StatusCode::OK,
+                content_type: CONTENT_TYPE,
+                body: body.into_bytes(),
+            }
+        }
+        ("/metrics", _) => HttpResponse::empty(StatusCode::METHOD_NOT_ALLOWED),
+        _ => HttpResponse::text(StatusCode::NOT_FOUND, "try /metrics"),
+    }
+}
+
+/// Bind the listener now (so a busy port fails at startup) and serve in the background.
+pub async fn spawn_metrics(addr: SocketAddr, shared: SharedMetrics) -> Result<JoinHandle<()>> {
+    let listener = TcpListener::bind(addr)
+        .await
+        .map_err(|e| anyhow::anyhow!("metrics listener {}: {}", addr, e))?;
+    Ok(tokio::spawn(async move {
+        let _ = serve(listener, move |req: HttpRequest| {
+            let shared = shared.clone();
+            async move { handle(&req, &shared) }
+        })
+        .await;
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot() -> MetricsSnapshot {
+        MetricsSnapshot {
+            patterns: vec![PatternMetric {
+                id: "00000000deadbeef".into(),
+                level: "ERROR",
+                canonical: "query \"<STR>\" failed\\retry".into(),
+                count: 42,
+                rate_1m: 7.0,
+                spike: true,
+            }],
+            sources: vec![SourceMetric {
+                id: "docker/api".into(),
+                kind: "docker".into(),
+                lines: 1000,
+                rate_1m: 12.0,
+                up: true,
+            }],
+            lines: 900,
+            dropped_paused: 3,
+            dropped_below_level: 97,
+        }
+    }
+
+    #[test]
+    fn renders_text_format() {
+        let text = render(&snapshot());
+        assert!(text.contains("# TYPE logradar_pattern_lines_total counter\n"));
+        assert!(text.contains("logradar_pattern_lines_total{id=\"00000000deadbeef\",level=\"ERROR\"} 42\n"));
+        assert!(text.contains(r#"pattern="query \"<STR>\" failed\\retry"} 1"#));
+        assert!(text.contains("logradar_pattern_spike{id=\"00000000deadbeef\",level=\"ERROR\"} 1\n"));
+        assert!(text.contains("logradar_source_lines_total{source=\"docker/api\",kind=\"docker\"} 1000\n"));
+        assert!(text.contains("logradar_source_rate_1m{source=\"docker/api\",kind=\"docker\"} 12\n"));
+        assert!(text.contains("logradar_dropped_lines_total{reason=\"below_level\"} 97\n"));
+        assert!(text.contains("logradar_patterns 1\n"));
+        assert!(text.contains("logradar_lines_total 900\n"));
+        // Every sample line belongs to a declared metric family
+        for line in text.lines().filter(|l| !l.starts_with('#')) {
+            let name = line.split(['{', ' ']).next().unwrap();
+            assert!(text.contains(&format!("# TYPE {} ", name)), "{}", name);
+        }
+    }
+
+    #[tokio::test]
+    async fn serves_latest_snapshot() {
+        let shared: SharedMetrics = Arc::default();
+        let probe = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = probe.local_addr().unwrap();
+        drop(probe);
+        let handle = spawn_metrics(addr, shared.clone()).await.unwrap();
+
+        *shared.lock().unwrap() = snapshot();
+        let client = reqwest::Client::new();
+        let resp = client.get(format!("http://{}/metrics", addr)).send().await.unwrap();
+        assert_eq!(resp.status(), 200);
+        assert!(resp
+            .headers()
+            .get("content-type")
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .starts_with("text/plain; version=0.0.4"));
+        assert!(resp.text().await.unwrap().contains("logradar_lines_total 900"));
+
+        let resp = client.get(format!("http://{}/", addr)).send().await.unwrap();
+        assert_eq!(resp.status(), 404);
+        let resp = client.post(format!("http://{}/metrics", addr)).send().await.unwrap();
+        assert_eq!(resp.status(), 405);
+        handle.abort();
+    }
+}
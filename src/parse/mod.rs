@@ -1,7 +1,9 @@
 use once_cell::sync::Lazy;
 use regex::Regex;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
 pub enum Level {
     Trace,
     Debug,
@@ -34,6 +36,44 @@ impl Level {
         }
     }
 
+    /// Parse a level name as written by structured loggers (`warn`, `WARNING`,
+    /// `err`, `fatal`, ...). Returns None for anything unrecognised.
+    pub fn from_name(name: &str) -> Option<Level> {
+        match name.trim().to_ascii_uppercase().as_str() {
+            "TRACE" => Some(Level::Trace),
+            "DEBUG" | "DBG" => Some(Level::Debug),
+            "INFO" | "INF" | "NOTICE" | "INFORMATION" => Some(Level::Info),
+            "WARN" | "WARNING" | "WRN" => Some(Level::Warn),
+            "ERROR" | "ERR" | "FATAL" | "CRITICAL" | "CRIT" | "PANIC" | "ALERT" | "EMERG" => {
+                Some(Level::Error)
+            }
+            _ => None,
+        }
+    }
+
+    /// Map a syslog severity / journald priority (0 = emerg … 7 = debug).
+    pub fn from_syslog_severity(severity: u8) -> Level {
+        match severity {
+            0..=3 => Level::Error,
+            4 => Level::Warn,
+            5 | 6 => Level::Info,
+            7 => Level::Debug,
+            _ => Level::Unknown,
+        }
+    }
+
+    /// Map an OpenTelemetry `SeverityNumber` (1–24); 0 means unspecified.
+    pub fn from_otel_severity(number: i32) -> Option<Level> {
+        match number {
+            1..=4 => Some(Level::Trace),
+            5..=8 => Some(Level::Debug),
+            9..=12 => Some(Level::Info),
+            13..=16 => Some(Level::Warn),
+            17..=24 => Some(Level::Error),
+            _ => None,
+        }
+    }
+
     pub fn short(self) -> &'static str {
         match self {
             Level::Trace => "TRC",
@@ -52,12 +92,15 @@ impl std::fmt::Display for Level {
     }
 }
 
-#[allow(dead_code)]
+/// Key/value attributes carried alongside a structured record.
+pub type Fields = Vec<(String, String)>;
+
 pub struct LogEvent {
     pub level: Level,
     pub source: String,
     pub raw: String,
     pub normalized: String,
+    pub fields: Fields,
 }
 
 static ISO_TS: Lazy<Regex> = Lazy::new(|| {
@@ -176,15 +219,23 @@ pub fn normalize(line: &str) -> String {
     g.into_owned()
 }
 
+/// Parse one line, detecting its level from keywords.
 pub fn parse_line(source: &str, line: &str) -> LogEvent {
+    parse_record(source, line, None)
+}
+
+/// Like `parse_line`, but a source that knows the record's severity (journald
+/// priority, syslog severity, ...) can supply it instead of keyword guessing.
+pub fn parse_record(source: &str, line: &str, level: Option<Level>) -> LogEvent {
     let clean = crate::util::strip_ansi(line);
-    let level = detect_level(&clean);
+    let level = level.unwrap_or_else(|| detect_level(&clean));
     let normalized = normalize(&clean);
     LogEvent {
         level,
         source: source.to_string(),
         raw: clean,
         normalized,
+        fields: Fields::new(),
     }
 }
 
@@ -417,6 +468,14 @@ mod tests {
         assert!(ev.normalized.contains("<IP>"));
     }
 
+    #[test]
+    fn parse_record_prefers_supplied_level() {
+        let ev = parse_record("journald/x", "all good, no errors", Some(Level::Warn));
+        assert_eq!(ev.level, Level::Warn);
+        let ev = parse_record("journald/x", "all good, no errors", None);
+        assert_eq!(ev.level, Level::Error);
+    }
+
     #[test]
     fn normalize_syslog_timestamp() {
         let out = normalize("Feb 20 15:03:24 myhost sshd[12345]: Accepted");
@@ -455,6 +514,36 @@ mod tests {
         assert!(!out.contains("2026-02-20"));
     }
 
+    #[test]
+    fn level_from_name() {
+        assert_eq!(Level::from_name("warning"), Some(Level::Warn));
+        assert_eq!(Level::from_name(" Fatal "), Some(Level::Error));
+        assert_eq!(Level::from_name("dbg"), Some(Level::Debug));
+        assert_eq!(Level::from_name("verbose"), None);
+    }
+
+    #[test]
+    fn level_from_otel_severity() {
+        assert_eq!(Level::from_otel_severity(0), None);
+        assert_eq!(Level::from_otel_severity(1), Some(Level::Trace));
+        assert_eq!(Level::from_otel_severity(9), Some(Level::Info));
+        assert_eq!(Level::from_otel_severity(13), Some(Level::Warn));
+        // FATAL collapses into ERROR
+        assert_eq!(Level::from_otel_severity(21), Some(Level::Error));
+        assert_eq!(Level::from_otel_severity(25), None);
+    }
+
+    #[test]
+    fn level_from_syslog_severity() {
+        assert_eq!(Level::from_syslog_severity(0), Level::Error);
+        assert_eq!(Level::from_syslog_severity(3), Level::Error);
+        assert_eq!(Level::from_syslog_severity(4), Level::Warn);
+        assert_eq!(Level::from_syslog_severity(5), Level::Info);
+        assert_eq!(Level::from_syslog_severity(6), Level::Info);
+        assert_eq!(Level::from_syslog_severity(7), Level::Debug);
+        assert_eq!(Level::from_syslog_severity(9), Level::Unknown);
+    }
+
     #[test]
     fn level_severity_ordering() {
         assert!(Level::Error.severity() > Level::Warn.severity());
@@ -3,9 +3,12 @@ use std::collections::{HashMap, HashSet, VecDeque};
 use std::hash::{Hash, Hasher};
 use std::time::{Duration, Instant};
 
-use crate::parse::{Level, LogEvent};
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+use crate::parse::{Fields, Level, LogEvent};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Trend {
     Up,
     Down,
@@ -22,7 +25,6 @@ impl Trend {
     }
 }
 
-#[allow(dead_code)]
 #[derive(Debug, Clone)]
 pub struct Pattern {
     pub canonical: String,
@@ -34,6 +36,8 @@ pub struct Pattern {
     pub trend: Trend,
     pub spike: bool,
     pub sources: HashSet<String>,
+    /// Attributes of the most recent structured record (empty for plain lines).
+    pub fields: Fields,
     /// Completed sparkline buckets (each = events in one SPARKLINE_BUCKET_SECS window).
     pub sparkline_buckets: VecDeque<u16>,
     /// In-progress bucket count (not yet committed to sparkline_buckets).
@@ -50,7 +54,13 @@ const SPARKLINE_BUCKET_SECS: u64 = 5;
 const SPARKLINE_BUCKET_COUNT: usize = 24;
 
 impl Pattern {
-    fn new(canonical: String, level: Level, raw: String, source: String, now: Instant) -> Self {
+    fn new(event: &LogEvent, now: Instant) -> Self {
+        let (canonical, level, raw, source) = (
+            event.normalized.clone(),
+            event.level,
+            event.raw.clone(),
+            event.source.clone(),
+        );
         let mut samples = VecDeque::with_capacity(MAX_SAMPLES);
         samples.push_back(raw);
         let mut ts1 = VecDeque::new();
@@ -70,6 +80,7 @@ impl Pattern {
             trend: Trend::Stable,
             spike: false,
             sources,
+            fields: event.fields.clone(),
             sparkline_buckets,
             current_bucket_count: 1,
             sparkline_last_advance: now,
@@ -78,8 +89,12 @@ impl Pattern {
         }
     }
 
-    fn record(&mut self, raw: String, level: Level, source: &str, now: Instant) {
+    fn record(&mut self, event: &LogEvent, now: Instant) {
+        let (raw, level, source) = (event.raw.clone(), event.level, &event.source);
         self.sources.insert(source.to_string());
+        if !event.fields.is_empty() {
+            self.fields = event.fields.clone();
+        }
         self.count_total += 1;
         self.last_seen = now;
         if level.severity() > self.level.severity() {
@@ -94,10 +109,41 @@ impl Pattern {
         self.current_bucket_count = self.current_bucket_count.saturating_add(1);
     }
 
+    /// Stable identifier for this pattern: FNV-1a of the canonical form, so
+    /// the same pattern gets the same id across runs and machines.
+    pub fn id(&self) -> String {
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+        for byte in self.canonical.bytes() {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+        format!("{:016x}", hash)
+    }
+
     pub fn rate_1m(&self) -> f64 {
         self.timestamps_1m.len() as f64
     }
 
+    /// A serializable copy of the pattern's current state.
+    pub fn snapshot(&self) -> PatternSnapshot {
+        let mut sources: Vec<String> = self.sources.iter().cloned().collect();
+        sources.sort_unstable();
+        PatternSnapshot {
+            id: self.id(),
+            pattern: self.canonical.clone(),
+            level: self.level,
+            count: self.count_total,
+            rate_1m: self.rate_1m(),
+            rate_5m: self.rate_5m(),
+            trend: self.trend,
+            spike: self.spike,
+            sources,
+            fields: self.fields.clone(),
+            samples: self.samples.iter().cloned().collect(),
+            sparkline: self.sparkline_buckets.iter().copied().collect(),
+        }
+    }
+
     pub fn rate_5m(&self) -> f64 {
         self.timestamps_5m.len() as f64 / 5.0
     }
@@ -153,6 +199,35 @@ impl Pattern {
     }
 }
 
+/// A point-in-time copy of a [`Pattern`]. The live pattern holds `Instant`s
+/// for its rate windows, so this is what gets serialized.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PatternSnapshot {
+    /// Stable id, see [`Pattern::id`].
+    pub id: String,
+    /// Normalized text shared by every line in the pattern.
+    pub pattern: String,
+    /// Highest level seen.
+    pub level: Level,
+    pub count: u64,
+    pub rate_1m: f64,
+    pub rate_5m: f64,
+    pub trend: Trend,
+    pub spike: bool,
+    pub sources: Vec<String>,
+    pub fields: Fields,
+    /// Most recent raw lines, oldest first.
+    pub samples: Vec<String>,
+    /// Lines per 5-second bucket over the last two minutes, oldest first.
+    pub sparkline: Vec<u16>,
+}
+
+impl Serialize for Pattern {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.snapshot().serialize(serializer)
+    }
+}
+
 fn hash_str(s: &str) -> u64 {
     let mut hasher = DefaultHasher::new();
     s.hash(&mut hasher);
@@ -164,6 +239,12 @@ pub struct PatternStore {
     index: HashMap<u64, usize>,
 }
 
+impl Default for PatternStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl PatternStore {
     pub fn new() -> Self {
         PatternStore {
@@ -173,25 +254,26 @@ impl PatternStore {
     }
 
     pub fn ingest(&mut self, event: &LogEvent) {
-        let now = Instant::now();
+        self.ingest_at(event, Instant::now());
+    }
+
+    /// Ingest as of `now`; replay passes the recorded time instead of the wall clock.
+    pub fn ingest_at(&mut self, event: &LogEvent, now: Instant) {
         let hash = hash_str(&event.normalized);
         if let Some(&idx) = self.index.get(&hash) {
-            self.patterns[idx].record(event.raw.clone(), event.level, &event.source, now);
+            self.patterns[idx].record(event, now);
         } else {
             let idx = self.patterns.len();
-            self.patterns.push(Pattern::new(
-                event.normalized.clone(),
-                event.level,
-                event.raw.clone(),
-                event.source.clone(),
-                now,
-            ));
+            self.patterns.push(Pattern::new(event, now));
             self.index.insert(hash, idx);
         }
     }
 
     pub fn tick(&mut self) {
-        let now = Instant::now();
+        self.tick_at(Instant::now());
+    }
+
+    pub fn tick_at(&mut self, now: Instant) {
         for p in &mut self.patterns {
             p.prune_windows(now);
             p.update_trend();
@@ -216,7 +298,10 @@ impl PatternStore {
     }
 
     pub fn clear_counters(&mut self) {
-        let now = std::time::Instant::now();
+        self.clear_counters_at(Instant::now());
+    }
+
+    pub fn clear_counters_at(&mut self, now: Instant) {
         for p in &mut self.patterns {
             p.count_total = 0;
             p.timestamps_1m.clear();
@@ -237,6 +322,10 @@ impl PatternStore {
     pub fn len(&self) -> usize {
         self.patterns.len()
     }
+
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
 }
 
 #[cfg(test)]
@@ -250,6 +339,7 @@ mod tests {
             source: "test".into(),
             raw: raw.into(),
             normalized: normalized.into(),
+            fields: Vec::new(),
         }
     }
 
@@ -365,6 +455,31 @@ mod tests {
         assert_eq!(p.sparkline_buckets.len(), 0);
     }
 
+    #[test]
+    fn pattern_id_is_stable() {
+        let mut store = PatternStore::new();
+        store.ingest(&make_event("GET /api/<NUM>", "GET /api/1", Level::Info));
+        store.ingest(&make_event("", "", Level::Info));
+        assert_eq!(store.patterns()[0].id(), store.patterns()[0].id());
+        assert_eq!(store.patterns()[0].id().len(), 16);
+        // FNV-1a offset basis for the empty string
+        assert_eq!(store.patterns()[1].id(), "cbf29ce484222325");
+    }
+
+    #[test]
+    fn snapshot_roundtrips_through_json() {
+        let mut store = PatternStore::new();
+        store.ingest(&make_event("GET /api/<NUM>", "GET /api/1", Level::Info));
+        store.ingest(&make_event("GET /api/<NUM>", "GET /api/2", Level::Warn));
+        let p = &store.patterns()[0];
+        let json = serde_json::to_value(p).unwrap();
+        assert_eq!(json["level"], "WARN");
+        assert_eq!(json["trend"], "stable");
+        assert_eq!(json["samples"], serde_json::json!(["GET /api/1", "GET /api/2"]));
+        let back: PatternSnapshot = serde_json::from_value(json).unwrap();
+        assert_eq!(back, p.snapshot());
+    }
+
     #[test]
     fn integration_with_parse() {
         let mut store = PatternStore::new();
@@ -0,0 +1,160 @@
+//! The parse → cluster pipeline without the TUI around it.
+
+use std::time::Instant;
+
+use crate::ingest::SourceEvent;
+use crate::parse::{self, Fields, Level};
+use crate::pattern::{PatternSnapshot, PatternStore};
+use crate::redact::Redactor;
+
+/// Feeds lines through level detection and normalization into a
+/// [`PatternStore`], and hands back snapshots of the resulting patterns.
+///
+/// Rate windows, trends and spikes advance on [`Pipeline::tick`]; call it
+/// periodically (the TUI does so every 50ms) when ingesting a live stream.
+pub struct Pipeline {
+    store: PatternStore,
+    min_level: Level,
+    redactor: Option<Redactor>,
+    lines: u64,
+    dropped: u64,
+}
+
+impl Default for Pipeline {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Pipeline {
+    /// A pipeline that keeps every line.
+    pub fn new() -> Self {
+        Pipeline {
+            store: PatternStore::new(),
+            min_level: Level::Trace,
+            redactor: None,
+            lines: 0,
+            dropped: 0,
+        }
+    }
+
+    /// Drop lines below `level` (unknown levels count as INFO).
+    pub fn min_level(mut self, level: Level) -> Self {
+        self.min_level = level;
+        self
+    }
+
+    /// Mask secrets in every line before it is clustered, e.g.
+    /// `Redactor::builtin()`.
+    pub fn redactor(mut self, redactor: Redactor) -> Self {
+        self.redactor = Some(redactor);
+        self
+    }
+
+    /// Ingest one raw line; returns false if it was below the minimum level.
+    pub fn push(&mut self, source: &str, line: &str) -> bool {
+        self.push_record(source, line, None, Fields::new())
+    }
+
+    /// Ingest a line whose level (and attributes) are already known, as
+    /// structured sources report them. `None` falls back to detection.
+    pub fn push_record(&mut self, source: &str, line: &str, level: Option<Level>, fields: Fields) -> bool {
+        let (line, fields) = match self.redactor {
+            Some(ref redactor) => (redactor.redact_line(line), redactor.redact_fields(fields)),
+            None => (line.to_string(), fields),
+        };
+        let mut event = parse::parse_record(source, &line, level);
+        event.fields = fields;
+        if event.level.severity() < self.min_level.severity() {
+            self.dropped += 1;
+            return false;
+        }
+        self.store.ingest_at(&event, Instant::now());
+        self.lines += 1;
+        true
+    }
+
+    /// Ingest a source event. Status and attach events carry no lines and
+    /// are ignored; returns whether a line was kept.
+    pub fn push_event(&mut self, event: SourceEvent) -> bool {
+        match event {
+            SourceEvent::Log { source, line } => self.push(&source, &line),
+            SourceEvent::Record {
+                source,
+                line,
+                level,
+                fields,
+            } => self.push_record(&source, &line, level, fields),
+            SourceEvent::Replayed { event, .. } => self.push_event(*event),
+            _ => false,
+        }
+    }
+
+    /// Advance rate windows, trends, spikes and sparklines to now.
+    pub fn tick(&mut self) {
+        self.store.tick_at(Instant::now());
+    }
+
+    /// Every pattern, busiest (1-minute rate) first.
+    pub fn snapshot(&self) -> Vec<PatternSnapshot> {
+        self.store
+            .sorted_indices()
+            .into_iter()
+            .map(|i| self.store.patterns()[i].snapshot())
+            .collect()
+    }
+
+    pub fn store(&self) -> &PatternStore {
+        &self.store
+    }
+
+    /// Lines kept so far.
+    pub fn lines(&self) -> u64 {
+        self.lines
+    }
+
+    /// Lines dropped for being below the minimum level.
+    pub fn dropped(&self) -> u64 {
+        self.dropped
+    }
+
+    /// Forget every pattern and counter.
+    pub fn reset(&mut self) {
+        self.store.reset();
+        self.lines = 0;
+        self.dropped = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clusters_and_filters() {
+        let mut pipeline = Pipeline::new().min_level(Level::Info);
+        assert!(pipeline.push("web", "GET /api/users/17 200"));
+        assert!(pipeline.push("api", "GET /api/users/42 200"));
+        assert!(!pipeline.push("web", "DEBUG cache warm"));
+        assert!(pipeline.push_event(SourceEvent::Record {
+            source: "journald/sshd".into(),
+            line: "session opened for user root".into(),
+            level: Some(Level::Warn),
+            fields: Vec::new(),
+        }));
+        assert!(!pipeline.push_event(SourceEvent::Status {
+            source: "web".into(),
+            status: crate::ingest::SourceStatus::Running,
+        }));
+        assert_eq!((pipeline.lines(), pipeline.dropped()), (3, 1));
+
+        let snapshot = pipeline.snapshot();
+        assert_eq!(snapshot.len(), 2);
+        assert_eq!(snapshot[0].count, 2);
+        assert_eq!(snapshot[0].sources, vec!["api", "web"]);
+        assert_eq!(snapshot[1].level, Level::Warn);
+
+        pipeline.reset();
+        assert!(pipeline.snapshot().is_empty());
+    }
+}
@@ -0,0 +1,212 @@
+//! Live config reload: the TUI polls the config files and re-applies
+//! profiles, themes, alert rules, redaction, keys and workspaces when it
+//! changes.
+//! Sources, patterns and the layout are left alone.
+
+use std::path::Path;
+use std::time::{Duration, Instant, SystemTime};
+
+use anyhow::Result;
+
+use crate::alerts;
+use crate::app::App;
+use crate::config::{LayerFile, LayeredConfig};
+use crate::redact::Redactor;
+use crate::theme::Theme;
+use crate::tui::keymap::Keymap;
+use crate::workspace;
+
+/// How often the config file is checked for changes.
+const CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Notices changes to the config layer files by their modification time
+/// and size.
+#[derive(Debug)]
+pub struct ConfigWatcher {
+    files: Vec<LayerFile>,
+    stamps: Vec<Option<(SystemTime, u64)>>,
+    last_check: Instant,
+}
+
+fn stamp(path: &Path) -> Option<(SystemTime, u64)> {
+    let meta = std::fs::metadata(path).ok()?;
+    Some((meta.modified().ok()?, meta.len()))
+}
+
+impl ConfigWatcher {
+    pub fn new(files: Vec<LayerFile>) -> Self {
+        ConfigWatcher {
+            stamps: files.iter().map(|f| stamp(&f.path)).collect(),
+            files,
+            last_check: Instant::now(),
+        }
+    }
+
+    pub fn files(&self) -> &[LayerFile] {
+        &self.files
+    }
+
+    /// Whether a file changed (or appeared) since the last call (or
+    /// `mark_seen`). Checks at most once per interval; a deleted file is
+    /// not a change.
+    pub fn changed_at(&mut self, now: Instant) -> bool {
+        if now.saturating_duration_since(self.last_check) < CHECK_INTERVAL {
+            return false;
+        }
+        self.last_check = now;
+        let mut changed = false;
+        for (file, seen) in self.files.iter().zip(&mut self.stamps) {
+            let current = stamp(&file.path);
+            if current.is_some() && current != *seen {
+                *seen = current;
+                changed = true;
+            }
+        }
+        changed
+    }
+
+    /// Take the files as they are now as seen, e.g. after writing one
+    /// ourselves.
+    pub fn mark_seen(&mut self) {
+        self.stamps = self.files.iter().map(|f| stamp(&f.path)).collect();
+    }
+}
+
+impl App {
+    pub fn poll_config(&mut self) {
+        self.poll_config_at(Instant::now());
+    }
+
+    /// Reload the config if its file changed. A broken file leaves the
+    /// previous settings active and is reported until it is fixed.
+    pub fn poll_config_at(&mut self, now: Instant) {
+        let Some(ref mut watcher) = self.config_watcher else {
+            return;
+        };
+        if !watcher.changed_at(now) {
+            return;
+        }
+        match self.reload_config() {
+            Ok(()) => {
+                self.config_error = None;
+                self.show_toast("Reloaded config".to_string(), false);
+            }
+            Err(e) => {
+                self.show_toast(format!("Config not reloaded: {:#}", e), true);
+                self.config_error = Some(format!("{:#}", e));
+            }
+        }
+    }
+
+    /// Re-read and merge the watched config files and apply everything but
+    /// the layout. Nothing changes unless the merged config is valid.
+    pub fn reload_config(&mut self) -> Result<()> {
+        let Some(ref watcher) = self.config_watcher else {
+            return Ok(());
+        };
+        let mut cfg = LayeredConfig::load(watcher.files(), std::env::vars())?.config()?;
+        let themes = cfg.custom_themes()?;
+        let rules = alerts::compile_rules(&cfg.alerts)?;
+        let redactor = Redactor::from_config(&cfg.redaction)?;
+        let keymap = Keymap::from_config(&cfg.keys)?;
+        let workspaces = std::mem::take(&mut cfg.workspaces);
+
+        let default_profile = cfg.default_profile.clone();
+        let profiles = cfg.into_profiles(&themes);
+        workspace::check(&workspaces, &profiles, &themes)?;
+        let current = self.profile().name.clone();
+        self.profiles = profiles;
+        self.profile_index = [Some(current), default_profile]
+            .into_iter()
+            .flatten()
+            .find_map(|name| self.profiles.iter().position(|p| p.name == name))
+            .unwrap_or(0);
+        // Pick up edits to a theme chosen with `t` or --theme
+        if let Some(ref theme) = self.theme_override {
+            self.theme_override = Theme::by_name(&theme.name, &themes);
+        }
+        self.custom_themes = themes;
+        self.alerts.set_rules(rules);
+        self.redactor = redactor;
+        self.keymap = keymap;
+        self.workspaces = workspaces;
+        self.needs_clear = true;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ConfigLayer;
+    use crate::ingest::SourceEvent;
+    use crate::tui::keymap::Action;
+
+    fn write(path: &Path, content: &str) {
+        std::fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn reload_applies_settings_and_keeps_patterns() {
+        let path = std::env::temp_dir().join(format!("logradar-reload-{}.toml", std::process::id()));
+        write(&path, "[profiles.default]\nhighlights = [\"timeout\"]\n");
+        let mut app = App::new(None);
+        app.config_watcher = Some(ConfigWatcher::new(vec![LayerFile::new(ConfigLayer::Explicit, path.clone())]));
+        app.process_event(SourceEvent::Log {
+            source: "app".into(),
+            line: "ERROR connection timeout".into(),
+        });
+        assert_eq!(app.store.len(), 1);
+
+        write(
+            &path,
+            r##"
+[profiles.default]
+min_level = "WARN"
+highlights = ["timeout", "refused"]
+
+[themes.reloaded]
+accent = "#ff0000"
+
+[[alerts]]
+name = "errors"
+level = "error"
+
+[keys]
+quit = "ctrl-q"
+"##,
+        );
+        app.poll_config_at(Instant::now() + CHECK_INTERVAL);
+        std::fs::remove_file(&path).ok();
+        assert_eq!(app.config_error, None);
+        assert_eq!(app.profile().highlights, vec!["timeout", "refused"]);
+        assert_eq!(app.min_level(), crate::Level::Warn);
+        assert!(!app.alerts.is_empty());
+        assert_eq!(app.keymap.label(Action::Quit), "Ctrl+q");
+        assert!(app.custom_themes.iter().any(|t| t.name == "reloaded"));
+        assert_eq!(app.store.len(), 1);
+    }
+
+    #[test]
+    fn broken_config_keeps_the_previous_one() {
+        let path = std::env::temp_dir().join(format!("logradar-reload-bad-{}.toml", std::process::id()));
+        write(&path, "[profiles.default]\nhighlights = [\"timeout\"]\n");
+        let mut app = App::new(None);
+        app.config_watcher = Some(ConfigWatcher::new(vec![LayerFile::new(ConfigLayer::Explicit, path.clone())]));
+        app.reload_config().unwrap();
+
+        write(&path, "[profiles.default]\nhighlights = [\"timeout\", \"oops\"\n");
+        let later = Instant::now() + CHECK_INTERVAL;
+        app.poll_config_at(later);
+        assert!(app.config_error.as_deref().is_some_and(|e| e.contains("parsing")));
+        assert!(app.active_toast().is_some_and(|t| t.error));
+        assert_eq!(app.profile().highlights, vec!["timeout"]);
+
+        // Fixing the file clears the error
+        write(&path, "[profiles.default]\nhighlights = [\"timeout\", \"fixed\"]\n");
+        app.poll_config_at(later + CHECK_INTERVAL);
+        std::fs::remove_file(&path).ok();
+        assert_eq!(app.config_error, None);
+        assert_eq!(app.profile().highlights, vec!["timeout", "fixed"]);
+    }
+}
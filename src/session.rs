@@ -0,0 +1,696 @@
+//! Session recording (`--record`) and deterministic replay (`--replay`).
+//!
+//! A recording is NDJSON: a header line, then one line per source event,
+//! pattern reset, counter clear or recorded pattern-store tick, each stamped
+//! with its offset from the start of the session in microseconds. Replaying
+//! feeds them back on a virtual clock, so clustering, rates, sparklines and
+//! spikes come out exactly as they were seen live.
+
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use crate::ingest::{SourceEvent, SourceInfo, SourceStatus};
+
+const FORMAT_VERSION: u32 = 1;
+/// Fastest finite speed; one step above it is "as fast as possible".
+const MAX_FACTOR: f64 = 64.0;
+const MIN_FACTOR: f64 = 0.25;
+/// How often a tick is recorded while no events arrive.
+const IDLE_TICK_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Header {
+    lrec: u32,
+    started_at: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    profile: Option<String>,
+}
+
+/// A keypress that changes the pattern store, recorded so a replay repeats it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionAction {
+    ResetPatterns,
+    ClearCounters,
+}
+
+#[derive(Serialize)]
+struct LineOut<'a> {
+    t: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    event: Option<&'a SourceEvent>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    action: Option<SessionAction>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    tick: bool,
+}
+
+#[derive(Deserialize)]
+struct LineIn {
+    t: u64,
+    #[serde(default)]
+    event: Option<SourceEvent>,
+    #[serde(default)]
+    action: Option<SessionAction>,
+    #[serde(default)]
+    tick: bool,
+}
+
+/// Writes a session file as events arrive.
+pub struct Recorder {
+    out: BufWriter<File>,
+    started: Instant,
+    /// An event was written since the last tick.
+    dirty: bool,
+    last_tick: Option<Instant>,
+}
+
+impl Recorder {
+    pub fn create(path: &str, profile: &str) -> Result<Recorder> {
+        let file = File::create(path).with_context(|| format!("creating {}", path))?;
+        let mut out = BufWriter::new(file);
+        let header = Header {
+            lrec: FORMAT_VERSION,
+            started_at: chrono::Local::now().to_rfc3339(),
+            profile: Some(profile.to_string()),
+        };
+        serde_json::to_writer(&mut out, &header)?;
+        out.write_all(b"\n")?;
+        Ok(Recorder {
+            out,
+            started: Instant::now(),
+            dirty: false,
+            last_tick: None,
+        })
+    }
+
+    pub fn event(&mut self, now: Instant, event: &SourceEvent) -> Result<()> {
+        self.dirty = true;
+        self.write(now, Some(event), None, false)
+    }
+
+    pub fn action(&mut self, now: Instant, action: SessionAction) -> Result<()> {
+        self.dirty = true;
+        self.write(now, None, Some(action), false)
+    }
+
+    /// Ticks decide when windows are pruned and sparkline buckets close, so
+    /// they are recorded too: after any new event, otherwise at most once per
+    /// [`IDLE_TICK_INTERVAL`] rather than every frame. Each recorded tick
+    /// flushes what was buffered since the last.
+    pub fn tick(&mut self, now: Instant) -> Result<()> {
+        let idle = self
+            .last_tick
+            .is_some_and(|t| now.saturating_duration_since(t) < IDLE_TICK_INTERVAL);
+        if !self.dirty && idle {
+            return Ok(());
+        }
+        self.dirty = false;
+        self.last_tick = Some(now);
+        self.write(now, None, None, true)?;
+        self.out.flush()?;
+        Ok(())
+    }
+
+    fn write(
+        &mut self,
+        now: Instant,
+        event: Option<&SourceEvent>,
+        action: Option<SessionAction>,
+        tick: bool,
+    ) -> Result<()> {
+        let line = LineOut {
+            t: now.saturating_duration_since(self.started).as_micros() as u64,
+            event,
+            action,
+            tick,
+        };
+        serde_json::to_writer(&mut self.out, &line)?;
+        self.out.write_all(b"\n")?;
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub enum Entry {
+    Event(SourceEvent),
+    Action(SessionAction),
+    Tick,
+}
+
+/// A session file loaded into memory.
+#[derive(Debug)]
+pub struct Recording {
+    /// Profile the session was recorded with.
+    pub profile: Option<String>,
+    pub entries: Vec<(Duration, Entry)>,
+}
+
+impl Recording {
+    pub fn load(path: &str) -> Result<Recording> {
+        let file = File::open(path).with_context(|| format!("opening {}", path))?;
+        Self::read(BufReader::new(file)).with_context(|| format!("reading {}", path))
+    }
+
+    fn read(reader: impl BufRead) -> Result<Recording> {
+        let mut lines = reader.lines();
+        let header: Header = match lines.next() {
+            Some(line) => serde_json::from_str(&line?).context("not a logradar recording")?,
+            None => bail!("empty recording"),
+        };
+        if header.lrec != FORMAT_VERSION {
+            bail!("unsupported recording version {}", header.lrec);
+        }
+        let mut entries = Vec::new();
+        for (n, line) in lines.enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let parsed: LineIn =
+                serde_json::from_str(&line).with_context(|| format!("line {}", n + 2))?;
+            let at = Duration::from_micros(parsed.t);
+            match (parsed.event, parsed.action) {
+                (Some(event), _) => entries.push((at, Entry::Event(event))),
+                (None, Some(action)) => entries.push((at, Entry::Action(action))),
+                (None, None) if parsed.tick => entries.push((at, Entry::Tick)),
+                (None, None) => {}
+            }
+        }
+        Ok(Recording {
+            profile: header.profile,
+            entries,
+        })
+    }
+
+    pub fn duration(&self) -> Duration {
+        self.entries.last().map_or(Duration::ZERO, |(at, _)| *at)
+    }
+}
+
+/// Playback speed: a multiple of real time, or as fast as possible.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReplaySpeed {
+    Times(f64),
+    Max,
+}
+
+impl ReplaySpeed {
+    /// `1x`, `4`, `0.5x` or `max`.
+    pub fn parse(s: &str) -> Result<ReplaySpeed> {
+        let s = s.trim().to_ascii_lowercase();
+        if s == "max" {
+            return Ok(ReplaySpeed::Max);
+        }
+        match s.trim_end_matches('x').parse::<f64>() {
+            Ok(f) if f > 0.0 && f.is_finite() => Ok(ReplaySpeed::Times(f)),
+            _ => bail!("invalid replay speed '{}': expected e.g. 1x, 10x or max", s),
+        }
+    }
+
+    pub fn faster(self) -> ReplaySpeed {
+        match self {
+            ReplaySpeed::Times(f) if f * 2.0 > MAX_FACTOR => ReplaySpeed::Max,
+            ReplaySpeed::Times(f) => ReplaySpeed::Times(f * 2.0),
+            ReplaySpeed::Max => ReplaySpeed::Max,
+        }
+    }
+
+    pub fn slower(self) -> ReplaySpeed {
+        match self {
+            ReplaySpeed::Times(f) => ReplaySpeed::Times((f / 2.0).max(MIN_FACTOR)),
+            ReplaySpeed::Max => ReplaySpeed::Times(MAX_FACTOR),
+        }
+    }
+
+    pub fn label(self) -> String {
+        match self {
+            ReplaySpeed::Times(f) => format!("{}x", f),
+            ReplaySpeed::Max => "max".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReplayCommand {
+    SetPaused(bool),
+    SetSpeed(ReplaySpeed),
+    /// Jump to an offset; jumping backwards re-plays from the start.
+    Seek(Duration),
+}
+
+pub fn spawn_replay(
+    path: &str,
+    recording: Recording,
+    speed: ReplaySpeed,
+    tx: mpsc::Sender<SourceEvent>,
+) -> (SourceInfo, JoinHandle<()>, mpsc::UnboundedSender<ReplayCommand>) {
+    let id = format!("replay/{}", path);
+    let info = SourceInfo {
+        id: id.clone(),
+        kind: "replay".into(),
+        status: SourceStatus::Starting,
+    };
+    let (control, commands) = mpsc::unbounded_channel();
+    let handle = tokio::spawn(async move {
+        run_replay(recording, speed, &id, tx, commands).await;
+    });
+    (info, handle, control)
+}
+
+async fn run_replay(
+    recording: Recording,
+    mut speed: ReplaySpeed,
+    source_id: &str,
+    tx: mpsc::Sender<SourceEvent>,
+    mut commands: mpsc::UnboundedReceiver<ReplayCommand>,
+) {
+    let end = recording.duration();
+    let entries = recording.entries;
+    let mut next = 0;
+    let mut position = Duration::ZERO;
+    let mut paused = false;
+    // Wall-clock instant that corresponds to `position` at the current speed
+    let mut anchor = Instant::now();
+    let mut finished = false;
+
+    let status = |status| SourceEvent::Status {
+        source: source_id.to_string(),
+        status,
+    };
+    if tx.send(status(SourceStatus::Running)).await.is_err() {
+        return;
+    }
+
+    loop {
+        let done = next >= entries.len();
+        if done && !finished {
+            finished = true;
+            if tx.send(status(SourceStatus::Stopped)).await.is_err() {
+                return;
+            }
+        }
+
+        // Idle until a command arrives when paused or at the end
+        let command = if paused || done {
+            match commands.recv().await {
+                Some(cmd) => Some(cmd),
+                None => return,
+            }
+        } else {
+            let at = entries[next].0;
+            let wait = match speed {
+                ReplaySpeed::Max => Duration::ZERO,
+                ReplaySpeed::Times(f) => at.saturating_sub(position).div_f64(f),
+            };
+            if wait.is_zero() {
+                commands.try_recv().ok()
+            } else {
+                tokio::select! {
+                    cmd = commands.recv() => match cmd {
+                        Some(cmd) => Some(cmd),
+                        None => return,
+                    },
+                    _ = tokio::time::sleep_until((anchor + wait).into()) => None,
+                }
+            }
+        };
+
+        match command {
+            Some(ReplayCommand::SetPaused(p)) => {
+                paused = p;
+                anchor = Instant::now();
+            }
+            Some(ReplayCommand::SetSpeed(s)) => {
+                speed = s;
+                anchor = Instant::now();
+            }
+            Some(ReplayCommand::Seek(target)) => {
+                if target < position {
+                    if tx.send(SourceEvent::ReplayReset).await.is_err() {
+                        return;
+                    }
+                    next = 0;
+                }
+                // Fast-forward without pacing so the state at `target` is exact
+                while next < entries.len() && entries[next].0 <= target {
+                    if tx.send(replayed(&entries[next])).await.is_err() {
+                        return;
+                    }
+                    next += 1;
+                }
+                position = target.min(end);
+                anchor = Instant::now();
+                if finished && next < entries.len() {
+                    finished = false;
+                    if tx.send(status(SourceStatus::Running)).await.is_err() {
+                        return;
+                    }
+                }
+            }
+            None => {
+                if next < entries.len() {
+                    if tx.send(replayed(&entries[next])).await.is_err() {
+                        return;
+                    }
+                    let at = entries[next].0;
+                    if let ReplaySpeed::Times(f) = speed {
+                        anchor += at.saturating_sub(position).div_f64(f);
+                    } else {
+                        anchor = Instant::now();
+                    }
+                    position = at;
+                    next += 1;
+                    // Let the UI breathe when replaying as fast as possible
+                    if speed == ReplaySpeed::Max && next.is_multiple_of(1024) {
+                        tokio::task::yield_now().await;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Wrap a recorded entry for the app. Recorded events are cloned because a
+/// backwards seek plays them again.
+fn replayed((at, entry): &(Duration, Entry)) -> SourceEvent {
+    match entry {
+        Entry::Tick => SourceEvent::ReplayTick { at: *at },
+        Entry::Action(action) => SourceEvent::ReplayAction {
+            at: *at,
+            action: *action,
+        },
+        Entry::Event(event) => SourceEvent::Replayed {
+            at: *at,
+            event: Box::new(event.clone()),
+        },
+    }
+}
+
+/// App-side view of a running replay; also the clock while replaying.
+pub struct ReplayState {
+    control: mpsc::UnboundedSender<ReplayCommand>,
+    /// Wall-clock instant standing in for the start of the recording.
+    base: Instant,
+    pub position: Duration,
+    pub total: Duration,
+    pub speed: ReplaySpeed,
+    pub paused: bool,
+}
+
+impl ReplayState {
+    pub fn new(
+        control: mpsc::UnboundedSender<ReplayCommand>,
+        total: Duration,
+        speed: ReplaySpeed,
+    ) -> Self {
+        ReplayState {
+            control,
+            base: Instant::now(),
+            position: Duration::ZERO,
+            total,
+            speed,
+            paused: false,
+        }
+    }
+
+    /// Virtual "now": the recorded time of the latest replayed entry.
+    pub fn now(&self) -> Instant {
+        self.base + self.position
+    }
+
+    pub fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+        let _ = self.control.send(ReplayCommand::SetPaused(self.paused));
+    }
+
+    pub fn set_speed(&mut self, speed: ReplaySpeed) {
+        self.speed = speed;
+        let _ = self.control.send(ReplayCommand::SetSpeed(speed));
+    }
+
+    /// Seek relative to the current position, clamped to the recording.
+    pub fn seek_by(&mut self, secs: i64) {
+        let delta = Duration::from_secs(secs.unsigned_abs());
+        let target = if secs < 0 {
+            self.position.saturating_sub(delta)
+        } else {
+            (self.position + delta).min(self.total)
+        };
+        let _ = self.control.send(ReplayCommand::Seek(target));
+    }
+
+    pub fn restart(&mut self) {
+        let _ = self.control.send(ReplayCommand::Seek(Duration::ZERO));
+    }
+}
+
+/// `mm:ss` (or `h:mm:ss`) for the status bar.
+pub fn format_offset(d: Duration) -> String {
+    let secs = d.as_secs();
+    if secs >= 3600 {
+        format!("{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+    } else {
+        format!("{:02}:{:02}", secs / 60, secs % 60)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::App;
+
+    fn line(t_secs: f64, event: Option<SourceEvent>) -> String {
+        let out = LineOut {
+            t: (t_secs * 1_000_000.0) as u64,
+            event: event.as_ref(),
+            action: None,
+            tick: event.is_none(),
+        };
+        serde_json::to_string(&out).unwrap()
+    }
+
+    /// Five minutes of a steady heartbeat, then an error burst in the last
+    /// ten seconds (enough to flag a spike), with a store tick every 250ms.
+    fn synthetic_recording() -> String {
+        let mut lines = vec![r#"{"lrec":1,"started_at":"2026-01-01T00:00:00+00:00"}"#.to_string()];
+        lines.push(line(
+            0.0,
+            Some(SourceEvent::Attached {
+                info: SourceInfo {
+                    id: "docker/api".into(),
+                    kind: "docker".into(),
+                    status: SourceStatus::Running,
+                },
+            }),
+        ));
+        let log = |line: String| {
+            Some(SourceEvent::Log {
+                source: "docker/api".into(),
+                line,
+            })
+        };
+        let mut t = 0.0;
+        while t < 300.0 {
+            if ((t * 4.0) as u64).is_multiple_of(40) {
+                lines.push(line(t + 0.01, log(format!("INFO heartbeat seq={}", t as u64))));
+            }
+            if t >= 290.0 {
+                lines.push(line(t + 0.02, log(format!("ERROR upstream timeout after {}ms", t as u64))));
+                lines.push(line(t + 0.03, log(format!("ERROR upstream timeout after {}ms", t as u64 + 1))));
+            }
+            lines.push(line(t + 0.25, None));
+            t += 0.25;
+        }
+        lines.join("\n")
+    }
+
+    type Snapshot = Vec<(String, u64, u64, Vec<u16>, bool)>;
+
+    fn snapshot(app: &App) -> Snapshot {
+        let mut snap: Snapshot = app
+            .store
+            .patterns()
+            .iter()
+            .map(|p| {
+                (
+                    p.canonical.clone(),
+                    p.count_total,
+                    p.rate_1m() as u64,
+                    p.sparkline_buckets.iter().copied().collect(),
+                    p.spike,
+                )
+            })
+            .collect();
+        snap.sort();
+        snap
+    }
+
+    /// Feed replayed events into the app until the replay reports the end.
+    async fn play_to_end(app: &mut App, rx: &mut mpsc::Receiver<SourceEvent>) {
+        loop {
+            let ev = tokio::time::timeout(Duration::from_secs(10), rx.recv())
+                .await
+                .expect("replay stalled")
+                .expect("replay channel closed");
+            let done = matches!(
+                ev,
+                SourceEvent::Status { ref source, status: SourceStatus::Stopped }
+                    if source.starts_with("replay/")
+            );
+            app.process_event(ev);
+            if done {
+                return;
+            }
+        }
+    }
+
+    #[test]
+    fn speeds() {
+        assert_eq!(ReplaySpeed::parse("1x").unwrap(), ReplaySpeed::Times(1.0));
+        assert_eq!(ReplaySpeed::parse("10").unwrap(), ReplaySpeed::Times(10.0));
+        assert_eq!(ReplaySpeed::parse("MAX").unwrap(), ReplaySpeed::Max);
+        assert!(ReplaySpeed::parse("0x").is_err());
+        assert!(ReplaySpeed::parse("fast").is_err());
+        assert_eq!(ReplaySpeed::Times(64.0).faster(), ReplaySpeed::Max);
+        assert_eq!(ReplaySpeed::Max.slower(), ReplaySpeed::Times(64.0));
+        assert_eq!(ReplaySpeed::Times(0.25).slower(), ReplaySpeed::Times(0.25));
+        assert_eq!(format_offset(Duration::from_secs(3725)), "1:02:05");
+        assert_eq!(format_offset(Duration::from_secs(65)), "01:05");
+    }
+
+    #[test]
+    fn record_and_load_roundtrip() {
+        let path = std::env::temp_dir().join(format!("logradar-session-{}.lrec", std::process::id()));
+        let path = path.display().to_string();
+        let mut rec = Recorder::create(&path, "ops").unwrap();
+        let now = Instant::now();
+        rec.event(
+            now,
+            &SourceEvent::Record {
+                source: "otlp/checkout".into(),
+                line: "payment declined".into(),
+                level: Some(crate::parse::Level::Warn),
+                fields: vec![("card.brand".into(), "visa".into())],
+            },
+        )
+        .unwrap();
+        rec.tick(now + Duration::from_millis(50)).unwrap();
+        drop(rec);
+
+        let recording = Recording::load(&path).unwrap();
+        assert_eq!(recording.profile.as_deref(), Some("ops"));
+        assert_eq!(recording.entries.len(), 2);
+        match &recording.entries[0].1 {
+            Entry::Event(SourceEvent::Record { source, level, fields, .. }) => {
+                assert_eq!(source, "otlp/checkout");
+                assert_eq!(*level, Some(crate::parse::Level::Warn));
+                assert_eq!(fields[0].1, "visa");
+            }
+            other => panic!("unexpected entry {:?}", other),
+        }
+        assert!(matches!(recording.entries[1].1, Entry::Tick));
+        assert!(recording.entries[1].0 >= Duration::from_millis(50));
+
+        std::fs::write(&path, "{\"lrec\":99,\"started_at\":\"x\"}\n").unwrap();
+        assert!(Recording::load(&path).is_err());
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn idle_frames_do_not_record_a_tick_each() {
+        let path = std::env::temp_dir().join(format!("logradar-ticks-{}.lrec", std::process::id()));
+        let path = path.display().to_string();
+        let mut rec = Recorder::create(&path, "ops").unwrap();
+        let now = Instant::now();
+        for ms in (0..1000).step_by(16) {
+            rec.tick(now + Duration::from_millis(ms)).unwrap();
+        }
+        let event = SourceEvent::Log {
+            source: "docker/web".into(),
+            line: "GET /".into(),
+        };
+        rec.event(now + Duration::from_millis(1000), &event).unwrap();
+        rec.tick(now + Duration::from_millis(1010)).unwrap();
+        rec.tick(now + Duration::from_millis(1020)).unwrap();
+        rec.tick(now + Duration::from_millis(2100)).unwrap();
+        drop(rec);
+
+        let recording = Recording::load(&path).unwrap();
+        let ticks: Vec<Duration> = recording
+            .entries
+            .iter()
+            .filter(|(_, e)| matches!(e, Entry::Tick))
+            .map(|(at, _)| *at)
+            .collect();
+        // The first frame, the one after the event, then one a second later
+        assert_eq!(ticks.len(), 3);
+        assert!(ticks[1] >= Duration::from_millis(1010));
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn recorded_reset_is_replayed() {
+        let path = std::env::temp_dir().join(format!("logradar-actions-{}.lrec", std::process::id()));
+        let path = path.display().to_string();
+        let mut live = App::new(None);
+        live.recorder = Some(Recorder::create(&path, "default").unwrap());
+        let log = |line: &str| SourceEvent::Log {
+            source: "docker/api".into(),
+            line: line.into(),
+        };
+        live.process_event(log("ERROR upstream timeout"));
+        live.apply_action(SessionAction::ResetPatterns);
+        live.process_event(log("INFO GET /"));
+        live.process_event(log("INFO GET /"));
+        live.recorder.take().unwrap().tick(Instant::now()).unwrap();
+
+        let recording = Recording::load(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert!(matches!(recording.entries[1].1, Entry::Action(SessionAction::ResetPatterns)));
+
+        let mut replayed = App::new(None);
+        let (tx, mut rx) = mpsc::channel(64);
+        replayed.set_tx(tx);
+        replayed.add_replay_source("a.lrec", recording, ReplaySpeed::Max);
+        play_to_end(&mut replayed, &mut rx).await;
+        assert_eq!(replayed.store.len(), 1);
+        assert_eq!(replayed.store.patterns()[0].count_total, 2);
+    }
+
+    #[tokio::test]
+    async fn replay_is_deterministic_across_seeks() {
+        let text = synthetic_recording();
+        let load = || Recording::read(text.as_bytes()).unwrap();
+        assert_eq!(load().duration(), Duration::from_secs(300));
+
+        // Straight through, as fast as possible
+        let mut straight = App::new(None);
+        let (tx, mut rx) = mpsc::channel(1024);
+        straight.set_tx(tx);
+        straight.add_replay_source("s.lrec", load(), ReplaySpeed::Max);
+        play_to_end(&mut straight, &mut rx).await;
+        let expected = snapshot(&straight);
+        assert!(straight.sources.iter().any(|s| s.id == "docker/api"));
+        let error = expected.iter().find(|p| p.0.starts_with("ERROR")).unwrap();
+        assert!(error.4, "error burst should be flagged as a spike");
+
+        // Play to the end, jump back to 30s and let it run out again
+        let mut seeking = App::new(None);
+        let (tx, mut rx) = mpsc::channel(1024);
+        seeking.set_tx(tx);
+        seeking.add_replay_source("s.lrec", load(), ReplaySpeed::Max);
+        play_to_end(&mut seeking, &mut rx).await;
+        seeking.replay.as_mut().unwrap().seek_by(-270);
+        play_to_end(&mut seeking, &mut rx).await;
+
+        assert_eq!(snapshot(&seeking), expected);
+        assert_eq!(seeking.log_count, straight.log_count);
+    }
+}
@@ -1,7 +1,42 @@
+use std::str::FromStr;
+
+use anyhow::{bail, Result};
 use ratatui::style::Color;
 
+use crate::color::ColorDepth;
+use crate::config::ThemeConfig;
 use crate::parse::Level;
 
+const BUILTIN_NAMES: &[&str] = &[
+    "matrix", "nebula", "frostbyte", "ember", "deepwave", "signal", "obsidian", "mono",
+];
+
+/// Lists the color fields once, for `[themes.*]` keys and their lookup.
+macro_rules! color_fields {
+    ($($field:ident),* $(,)?) => {
+        /// Keys a `[themes.*]` table can set, besides `inherit`.
+        pub const COLOR_FIELDS: &[&str] = &[$(stringify!($field)),*];
+
+        impl Theme {
+            fn color_mut(&mut self, field: &str) -> Option<&mut Color> {
+                match field {
+                    $(stringify!($field) => Some(&mut self.$field),)*
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+color_fields!(
+    border, border_focused, title, selected_fg, selected_bg, error, warn, info, debug, trace,
+    accent, badge, status_bar_fg, status_bar_bg, fuzzy_match, text, text_dim, bg, modal_border,
+    modal_bg, modal_title, header_bg, header_fg, header_accent, rate_bar, rate_bar_bg, menu_hover,
+    divider, success, trend_up, trend_down, trend_stable, badge_error_bg, badge_warn_bg,
+    badge_info_bg, badge_debug_bg, count_hot, count_warm, count_cold, sparkline, sparkline_dim,
+    banner_primary, banner_accent, banner_tagline, banner_separator,
+);
+
 #[allow(dead_code)]
 #[derive(Debug, Clone)]
 pub struct Theme {
@@ -59,30 +94,29 @@ pub struct Theme {
 }
 
 impl Theme {
-    /// Ordered list of all available themes.
-    #[allow(dead_code)]
-    pub fn all() -> Vec<Theme> {
-        vec![
-            Self::matrix(),
-            Self::nebula(),
-            Self::frostbyte(),
-            Self::ember(),
-            Self::deepwave(),
-            Self::signal(),
-            Self::obsidian(),
-            Self::mono(),
-        ]
+    /// All theme names in cycle order: the built-ins, then `custom` (the
+    /// config's `[themes.*]`).
+    pub fn all_names(custom: &[Theme]) -> Vec<String> {
+        let mut names: Vec<String> = BUILTIN_NAMES.iter().map(|n| n.to_string()).collect();
+        for theme in custom {
+            if !names.contains(&theme.name) {
+                names.push(theme.name.clone());
+            }
+        }
+        names
     }
 
-    /// All theme names in cycle order.
-    pub fn all_names() -> Vec<&'static str> {
-        vec![
-            "matrix", "nebula", "frostbyte", "ember", "deepwave", "signal", "obsidian", "mono",
-        ]
+    /// Look up a theme by name among the built-ins and `custom`. Returns
+    /// None for unknown names. A config theme named after a built-in
+    /// replaces it.
+    pub fn by_name(name: &str, custom: &[Theme]) -> Option<Theme> {
+        if let Some(theme) = custom.iter().find(|t| t.name == name) {
+            return Some(theme.clone());
+        }
+        Self::builtin(name)
     }
 
-    /// Look up a theme by name. Returns None for unknown names.
-    pub fn by_name(name: &str) -> Option<Theme> {
+    fn builtin(name: &str) -> Option<Theme> {
         match name {
             "matrix" => Some(Self::matrix()),
             "nebula" => Some(Self::nebula()),
@@ -96,12 +130,66 @@ impl Theme {
         }
     }
 
+    /// A `[themes.<name>]` table: the `inherit` theme (or the built-in of
+    /// the same name, else matrix) with its colors overridden.
+    pub fn from_config(name: &str, cfg: &ThemeConfig) -> Result<Theme> {
+        let base = cfg.inherit.as_deref().unwrap_or(if BUILTIN_NAMES.contains(&name) {
+            name
+        } else {
+            "matrix"
+        });
+        let Some(mut theme) = Self::builtin(base) else {
+            bail!(
+                "[themes.{}]: cannot inherit from unknown theme '{}' (built-in: {})",
+                name,
+                base,
+                BUILTIN_NAMES.join(", ")
+            );
+        };
+        theme.name = name.to_string();
+        for (key, value) in &cfg.colors {
+            let Some(slot) = theme.color_mut(key) else {
+                let hint = match closest_field(key) {
+                    Some(field) => format!("did you mean '{}'?", field),
+                    None => format!("expected inherit or one of: {}", COLOR_FIELDS.join(", ")),
+                };
+                bail!("[themes.{}]: unknown key '{}', {}", name, key, hint);
+            };
+            *slot = match parse_color(value) {
+                Some(color) => color,
+                None => bail!(
+                    "[themes.{}]: {} = {} is not a color (use \"#rrggbb\", a name like \"light_blue\", or 0-255)",
+                    name,
+                    key,
+                    value
+                ),
+            };
+        }
+        Ok(theme)
+    }
+
+    /// This theme as a terminal with `depth` colors can show it. Without
+    /// color (`NO_COLOR`) every theme becomes mono in ANSI grays.
+    pub fn for_depth(&self, depth: ColorDepth) -> Theme {
+        let mut theme = match depth {
+            ColorDepth::TrueColor => return self.clone(),
+            ColorDepth::NoColor => Self::mono(),
+            _ => self.clone(),
+        };
+        for field in COLOR_FIELDS {
+            if let Some(color) = theme.color_mut(field) {
+                *color = depth.downsample(*color);
+            }
+        }
+        theme
+    }
+
     /// Return the next theme in the cycle.
-    pub fn next(&self) -> Theme {
-        let names = Self::all_names();
-        let idx = names.iter().position(|&n| n == self.name).unwrap_or(0);
-        let next_name = names[(idx + 1) % names.len()];
-        Self::by_name(next_name).unwrap()
+    pub fn next(&self, custom: &[Theme]) -> Theme {
+        let names = Self::all_names(custom);
+        let idx = names.iter().position(|n| *n == self.name).unwrap_or(0);
+        let next_name = &names[(idx + 1) % names.len()];
+        Self::by_name(next_name, custom).unwrap_or_else(Self::matrix)
     }
 
     // ── matrix ─────────────────────────────────────────────────────
@@ -567,3 +655,104 @@ impl Theme {
         }
     }
 }
+
+/// `"#rrggbb"`, a color name (`"red"`, `"light_blue"`, `"reset"`) or a
+/// 256-color index, as a number or a string.
+fn parse_color(value: &toml::Value) -> Option<Color> {
+    match value {
+        toml::Value::Integer(i) => u8::try_from(*i).ok().map(Color::Indexed),
+        toml::Value::String(s) => Color::from_str(s.trim()).ok(),
+        _ => None,
+    }
+}
+
+/// The color field a misspelt key most likely meant.
+fn closest_field(key: &str) -> Option<&'static str> {
+    COLOR_FIELDS
+        .iter()
+        .map(|field| (edit_distance(key, field), *field))
+        .filter(|(distance, _)| *distance <= 3)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, field)| field)
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn theme_config(toml_str: &str) -> ThemeConfig {
+        toml::from_str(toml_str).unwrap()
+    }
+
+    #[test]
+    fn config_theme_inherits_and_overrides_colors() {
+        let cfg = theme_config(
+            r##"
+inherit = "nebula"
+accent = "#ff8800"
+text = "light_gray"
+border = 238
+"##,
+        );
+        let theme = Theme::from_config("dusk", &cfg).unwrap();
+        assert_eq!(theme.name, "dusk");
+        assert_eq!(theme.accent, Color::Rgb(255, 136, 0));
+        assert_eq!(theme.text, Color::White);
+        assert_eq!(theme.border, Color::Indexed(238));
+        assert_eq!(theme.error, Theme::nebula().error);
+
+        // Named after a built-in: starts from that one
+        let theme = Theme::from_config("ember", &theme_config(r#"accent = "red""#)).unwrap();
+        assert_eq!(theme.info, Theme::ember().info);
+        assert_eq!(theme.accent, Color::Red);
+    }
+
+    #[test]
+    fn config_theme_errors_point_at_the_problem() {
+        let err = Theme::from_config("dusk", &theme_config(r#"acent = "red""#)).unwrap_err();
+        assert_eq!(err.to_string(), "[themes.dusk]: unknown key 'acent', did you mean 'accent'?");
+        let err = Theme::from_config("dusk", &theme_config("text = 300")).unwrap_err();
+        assert!(err.to_string().contains("text = 300 is not a color"), "{}", err);
+        let err = Theme::from_config("dusk", &theme_config(r#"inherit = "dusk""#)).unwrap_err();
+        assert!(err.to_string().contains("unknown theme 'dusk'"), "{}", err);
+    }
+
+    #[test]
+    fn for_depth_maps_every_color() {
+        let theme = Theme::nebula().for_depth(ColorDepth::Ansi256);
+        assert_eq!(theme.name, "nebula");
+        assert_eq!(theme.accent, Color::Indexed(45));
+        assert_eq!(theme.bg, Color::Reset);
+        let theme = Theme::ember().for_depth(ColorDepth::NoColor);
+        assert_eq!(theme.name, "mono");
+        let mut fields = COLOR_FIELDS.iter();
+        assert!(fields.all(|f| matches!(
+            theme.clone().color_mut(f),
+            Some(Color::Black | Color::DarkGray | Color::Gray | Color::White | Color::Reset)
+        )));
+    }
+
+    #[test]
+    fn custom_themes_join_the_cycle() {
+        let custom = vec![Theme::from_config("test-dusk", &ThemeConfig::default()).unwrap()];
+        assert_eq!(Theme::all_names(&custom).last().map(String::as_str), Some("test-dusk"));
+        assert_eq!(Theme::mono().next(&custom).name, "test-dusk");
+        assert_eq!(Theme::by_name("test-dusk", &custom).unwrap().next(&custom).name, "matrix");
+        assert!(Theme::by_name("test-dusk", &[]).is_none());
+    }
+}
@@ -0,0 +1,611 @@
+//! Key handling for the TUI, by mode. Keys are looked up in the keymap and
+//! dispatched as actions; text prompts take their input literally.
+
+use crossterm::event::{self, KeyCode, KeyModifiers};
+
+use crate::app::{self, AppMode, Pane};
+use crate::discovery;
+use crate::export::ExportScope;
+use crate::ingest;
+use crate::session::SessionAction;
+use crate::tui;
+use crate::tui::keymap::Action;
+use crate::tui::source_menu::SourceMenuScreen;
+
+/// Rows moved by page up / page down.
+const PAGE_ROWS: usize = 10;
+
+/// The action bound to `key`. The arrows, Enter and Esc fall back to
+/// navigation when unbound, so no keymap can lock the user in a menu.
+fn resolve(app: &app::App, key: &event::KeyEvent) -> Option<Action> {
+    app.keymap.action(key).or(match key.code {
+        KeyCode::Up => Some(Action::MoveUp),
+        KeyCode::Down => Some(Action::MoveDown),
+        KeyCode::Enter => Some(Action::Select),
+        KeyCode::Esc => Some(Action::Back),
+        _ => None,
+    })
+}
+
+/// Dispatch a key press according to the current mode.
+pub fn handle_key_event(app: &mut app::App, key: event::KeyEvent) {
+    // Search mode: capture all input
+    if app.mode == AppMode::Search {
+        match key.code {
+            KeyCode::Esc => app.exit_search(false),
+            KeyCode::Enter => app.exit_search(true),
+            KeyCode::Backspace => {
+                app.search_query.pop();
+            }
+            KeyCode::Char(c) => app.search_query.push(c),
+            _ => {}
+        }
+        return;
+    }
+
+    // Export path prompt
+    if app.mode == AppMode::Export {
+        match key.code {
+            KeyCode::Esc => {
+                app.export_prompt = None;
+                app.mode = AppMode::Normal;
+            }
+            KeyCode::Enter => app.run_export(),
+            KeyCode::Tab => {
+                if let Some(ref mut prompt) = app.export_prompt {
+                    prompt.cycle_format();
+                }
+            }
+            KeyCode::Backspace => {
+                if let Some(ref mut prompt) = app.export_prompt {
+                    prompt.path.pop();
+                }
+            }
+            KeyCode::Char(c) => {
+                if let Some(ref mut prompt) = app.export_prompt {
+                    prompt.path.push(c);
+                }
+            }
+            _ => {}
+        }
+        return;
+    }
+
+    // Command palette: typed literally, like the prompts above
+    if app.mode == AppMode::Palette {
+        handle_palette_key(app, key);
+        return;
+    }
+
+    // Source menu
+    if app.mode == AppMode::SourceMenu {
+        handle_source_menu_key(app, key);
+        return;
+    }
+
+    let action = resolve(app, &key);
+
+    // Profile picker
+    if app.mode == AppMode::ProfilePicker {
+        match action {
+            Some(Action::Back | Action::Select) => app.mode = AppMode::Normal,
+            Some(Action::MoveUp) => app.prev_profile(),
+            Some(Action::MoveDown) => app.next_profile(),
+            _ => {}
+        }
+        return;
+    }
+
+    // Alerts log
+    if app.mode == AppMode::Alerts {
+        match action {
+            Some(Action::Back | Action::Alerts) => app.toggle_alerts(),
+            Some(Action::MoveUp) => {
+                app.alerts_scroll = app.alerts_scroll.saturating_sub(1);
+            }
+            Some(Action::MoveDown) if app.alerts_scroll + 1 < app.alerts.log.len() => {
+                app.alerts_scroll += 1;
+            }
+            Some(Action::Top) => app.alerts_scroll = 0,
+            Some(Action::Bottom) => app.alerts_scroll = app.alerts.log.len().saturating_sub(1),
+            Some(Action::Quit) => app.should_quit = true,
+            _ => {}
+        }
+        return;
+    }
+
+    // Help overlay
+    if app.mode == AppMode::Help {
+        if let Some(Action::Back | Action::Help | Action::Quit) = action {
+            app.mode = AppMode::Normal;
+            app.needs_clear = true;
+        }
+        return;
+    }
+
+    // Drilldown
+    if app.mode == AppMode::Drilldown {
+        let samples = app.selected_pattern_data().map_or(0, |p| p.samples.len());
+        // `b` stays an alias for back here
+        match action.or((key.code == KeyCode::Char('b')).then_some(Action::Back)) {
+            Some(Action::Back) => {
+                app.mode = AppMode::Normal;
+                app.detail_scroll = 0;
+                app.needs_clear = true;
+            }
+            Some(Action::MoveUp) if app.detail_scroll > 0 => app.detail_scroll -= 1,
+            Some(Action::MoveDown) if app.detail_scroll + 1 < samples => app.detail_scroll += 1,
+            Some(Action::Top) => app.detail_scroll = 0,
+            Some(Action::Bottom) => app.detail_scroll = samples.saturating_sub(1),
+            Some(Action::ToggleNormalized) => app.show_normalized = !app.show_normalized,
+            Some(Action::Zoom) => {
+                app.layout_state.zoomed = !app.layout_state.zoomed;
+                app.needs_clear = true;
+            }
+            Some(Action::Quit) => app.should_quit = true,
+            _ => {}
+        }
+        return;
+    }
+
+    // Normal mode
+    if let Some(action) = action {
+        perform(app, action);
+    }
+}
+
+fn handle_palette_key(app: &mut app::App, key: event::KeyEvent) {
+    let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+    match key.code {
+        KeyCode::Esc => {
+            app.mode = AppMode::Normal;
+            app.needs_clear = true;
+        }
+        KeyCode::Enter => tui::palette::submit(app),
+        KeyCode::Tab => tui::palette::complete(app),
+        KeyCode::Up => app.palette.selected = app.palette.selected.saturating_sub(1),
+        KeyCode::Char('p') if ctrl => app.palette.selected = app.palette.selected.saturating_sub(1),
+        KeyCode::Down => move_palette_down(app),
+        KeyCode::Char('n') if ctrl => move_palette_down(app),
+        KeyCode::Backspace => {
+            app.palette.input.pop();
+            app.palette.selected = 0;
+        }
+        KeyCode::Char(c) if !ctrl => {
+            app.palette.input.push(c);
+            app.palette.selected = 0;
+        }
+        _ => {}
+    }
+}
+
+fn move_palette_down(app: &mut app::App) {
+    let count = tui::palette::suggestions(app).len();
+    if app.palette.selected + 1 < count {
+        app.palette.selected += 1;
+    }
+}
+
+/// Run a normal-mode action.
+pub fn perform(app: &mut app::App, action: Action) {
+    match action {
+        Action::Back => {
+            // Clear active search filter or source filter
+            if !app.search_query.is_empty() {
+                app.search_query.clear();
+                app.selected_pattern = 0;
+                app.needs_clear = true;
+            } else if app.active_source_filter.is_some() {
+                app.active_source_filter = None;
+                app.selected_pattern = 0;
+                app.needs_clear = true;
+            }
+        }
+        Action::Quit => app.should_quit = true,
+        Action::Help => app.mode = AppMode::Help,
+        Action::Search => app.enter_search(),
+        Action::ExportView => app.open_export(ExportScope::View),
+        Action::ExportSamples => app.open_export(ExportScope::Samples),
+        Action::AddSource => app.open_source_menu(),
+        Action::Pause => app.paused = !app.paused,
+        Action::ProfilePicker => app.mode = AppMode::ProfilePicker,
+        Action::Alerts => app.toggle_alerts(),
+        Action::ResetPatterns => app.apply_action(SessionAction::ResetPatterns),
+        Action::ClearCounters => app.apply_action(SessionAction::ClearCounters),
+        Action::ToggleNormalized => app.show_normalized = !app.show_normalized,
+        Action::CycleTheme => app.toggle_theme(),
+        Action::ShrinkPane => app.layout_state.resize(app.active_pane, false),
+        Action::GrowPane => app.layout_state.resize(app.active_pane, true),
+        Action::ToggleSources => app.toggle_sources_pane(),
+        Action::Zoom => {
+            app.layout_state.zoomed = !app.layout_state.zoomed;
+            app.needs_clear = true;
+        }
+        Action::CycleLayout => {
+            app.layout_state.cycle_orientation();
+            let name = app.layout_state.orientation.name();
+            app.show_toast(format!("Layout: {}", name), false);
+            app.needs_clear = true;
+        }
+        Action::SaveLayout => app.save_layout(),
+        Action::Palette => app.open_palette(),
+        Action::SaveWorkspace => {
+            app.open_palette();
+            app.palette.input = "save_workspace ".to_string();
+        }
+        Action::ReplayPause
+        | Action::ReplayFaster
+        | Action::ReplaySlower
+        | Action::ReplayBack
+        | Action::ReplayForward
+        | Action::ReplayRestart => handle_replay_action(app, action),
+        Action::NextPane => app.next_pane(),
+        Action::PrevPane => app.prev_pane(),
+        Action::MoveUp => app.move_up(),
+        Action::MoveDown => app.move_down(),
+        Action::PageUp => (0..PAGE_ROWS).for_each(|_| app.move_up()),
+        Action::PageDown => (0..PAGE_ROWS).for_each(|_| app.move_down()),
+        Action::Top => app.move_top(),
+        Action::Bottom => app.move_bottom(),
+        Action::Select => {
+            if app.active_pane == Pane::Patterns && app.selected_pattern_data().is_some() {
+                app.mode = AppMode::Drilldown;
+                app.detail_scroll = 0;
+                app.needs_clear = true;
+            } else if app.active_pane == Pane::Sources {
+                app.activate_selected_source();
+            }
+        }
+    }
+}
+
+/// Replay transport: pause, speed and seek. Ignored outside a replay.
+fn handle_replay_action(app: &mut app::App, action: Action) {
+    let Some(ref mut replay) = app.replay else {
+        return;
+    };
+    match action {
+        Action::ReplayPause => replay.toggle_pause(),
+        Action::ReplayFaster => replay.set_speed(replay.speed.faster()),
+        Action::ReplaySlower => replay.set_speed(replay.speed.slower()),
+        Action::ReplayBack => replay.seek_by(-10),
+        Action::ReplayForward => replay.seek_by(10),
+        Action::ReplayRestart => replay.restart(),
+        _ => {}
+    }
+}
+
+fn handle_source_menu_key(app: &mut app::App, key: event::KeyEvent) {
+    let screen = app.source_menu.screen;
+
+    // Lists navigate through the keymap; text inputs and screen-specific
+    // keys (Space, r, f, ...) are taken literally
+    let text_input = matches!(
+        screen,
+        SourceMenuScreen::FileInput | SourceMenuScreen::SshInput | SourceMenuScreen::CommandInput
+    );
+    let mut key = key;
+    if !text_input {
+        key.code = match resolve(app, &key) {
+            Some(Action::MoveUp) => KeyCode::Up,
+            Some(Action::MoveDown) => KeyCode::Down,
+            Some(Action::Select) => KeyCode::Enter,
+            Some(Action::Back) => KeyCode::Esc,
+            _ => key.code,
+        };
+    }
+
+    match screen {
+        SourceMenuScreen::MainMenu => match key.code {
+            KeyCode::Esc => {
+                app.mode = AppMode::Normal;
+                app.needs_clear = true;
+            }
+            KeyCode::Up
+                if app.source_menu.main_cursor > 0 =>
+            {
+                app.source_menu.main_cursor -= 1;
+            }
+            KeyCode::Down
+                if app.source_menu.main_cursor < tui::source_menu::MAIN_MENU_ITEMS.len() - 1 =>
+            {
+                app.source_menu.main_cursor += 1;
+            }
+            KeyCode::Enter => {
+                match app.source_menu.main_cursor {
+                    0 => {
+                        // Docker Container — results already pre-fetched
+                        app.source_menu.screen = SourceMenuScreen::DockerDiscovery;
+                        app.source_menu.discovery_cursor = 0;
+                        app.source_menu.selected.clear();
+                    }
+                    1 => {
+                        // File (tail)
+                        app.source_menu.screen = SourceMenuScreen::FileInput;
+                        app.source_menu.text_input.clear();
+                    }
+                    2 => {
+                        // Azure Container App — results already pre-fetched
+                        app.source_menu.screen = SourceMenuScreen::AzureDiscovery;
+                        app.source_menu.discovery_cursor = 0;
+                        app.source_menu.selected.clear();
+                    }
+                    3 => {
+                        // Kubernetes — results already pre-fetched
+                        app.source_menu.screen = SourceMenuScreen::KubernetesDiscovery;
+                        app.source_menu.discovery_cursor = 0;
+                        app.source_menu.selected.clear();
+                    }
+                    4 => {
+                        // journald — units already pre-fetched
+                        app.source_menu.screen = SourceMenuScreen::JournaldDiscovery;
+                        app.source_menu.discovery_cursor = 0;
+                        app.source_menu.selected.clear();
+                    }
+                    5 => {
+                        // SSH (remote file or journal)
+                        app.source_menu.screen = SourceMenuScreen::SshInput;
+                        app.source_menu.text_input.clear();
+                        app.source_menu.ssh_error = None;
+                    }
+                    6 => {
+                        // Custom Command
+                        app.source_menu.screen = SourceMenuScreen::CommandInput;
+                        app.source_menu.text_input.clear();
+                    }
+                    _ => {}
+                }
+            }
+            _ => {}
+        },
+        SourceMenuScreen::DockerDiscovery => match key.code {
+            KeyCode::Esc => {
+                app.source_menu.screen = SourceMenuScreen::MainMenu;
+            }
+            KeyCode::Up
+                if app.source_menu.discovery_cursor > 0 =>
+            {
+                app.source_menu.discovery_cursor -= 1;
+            }
+            KeyCode::Down => {
+                let count = app.source_menu.docker_containers.len();
+                if count > 0 && app.source_menu.discovery_cursor < count - 1 {
+                    app.source_menu.discovery_cursor += 1;
+                }
+            }
+            KeyCode::Char(' ') => {
+                app.source_menu.toggle_selection();
+            }
+            KeyCode::Char('r') => {
+                app.source_menu.docker_loading = true;
+                app.source_menu.docker_error = None;
+                app.source_menu.docker_containers.clear();
+                app.source_menu.selected.clear();
+                app.source_menu.discovery_cursor = 0;
+                if let Some(dtx) = app.discovery_tx.clone() {
+                    discovery::discover_docker(dtx);
+                }
+            }
+            KeyCode::Enter => {
+                app.spawn_selected_docker_sources();
+                app.mode = AppMode::Normal;
+                app.needs_clear = true;
+            }
+            _ => {}
+        },
+        SourceMenuScreen::AzureDiscovery => match key.code {
+            KeyCode::Esc => {
+                app.source_menu.screen = SourceMenuScreen::MainMenu;
+            }
+            KeyCode::Up
+                if app.source_menu.discovery_cursor > 0 =>
+            {
+                app.source_menu.discovery_cursor -= 1;
+            }
+            KeyCode::Down => {
+                let count = app.source_menu.discovery_item_count();
+                if count > 0 && app.source_menu.discovery_cursor < count - 1 {
+                    app.source_menu.discovery_cursor += 1;
+                }
+            }
+            KeyCode::Char('f') => {
+                app.source_menu.cycle_azure_subscription_filter();
+            }
+            KeyCode::Char('s') => {
+                app.source_menu.azure_system_logs = !app.source_menu.azure_system_logs;
+            }
+            KeyCode::Char(' ') => {
+                app.source_menu.toggle_selection();
+            }
+            KeyCode::Char('r') => {
+                app.source_menu.azure_loading = true;
+                app.source_menu.azure_error = None;
+                app.source_menu.azure_apps.clear();
+                app.source_menu.selected.clear();
+                app.source_menu.discovery_cursor = 0;
+                if let Some(dtx) = app.discovery_tx.clone() {
+                    discovery::discover_azure(dtx);
+                }
+            }
+            KeyCode::Char('v') | KeyCode::Right => {
+                app.open_azure_revisions();
+            }
+            KeyCode::Enter => {
+                app.spawn_selected_azure_sources();
+                app.mode = AppMode::Normal;
+                app.needs_clear = true;
+            }
+            _ => {}
+        },
+        SourceMenuScreen::AzureRevisions => match key.code {
+            KeyCode::Esc | KeyCode::Left => {
+                app.source_menu.screen = SourceMenuScreen::AzureDiscovery;
+            }
+            KeyCode::Up
+                if app.source_menu.revision_cursor > 0 =>
+            {
+                app.source_menu.revision_cursor -= 1;
+            }
+            KeyCode::Down => {
+                let count = app.source_menu.azure_revisions.len();
+                if count > 0 && app.source_menu.revision_cursor < count - 1 {
+                    app.source_menu.revision_cursor += 1;
+                }
+            }
+            KeyCode::Char('r') => {
+                app.open_azure_revisions();
+            }
+            KeyCode::Enter => {
+                app.spawn_selected_azure_revision();
+                app.mode = AppMode::Normal;
+                app.needs_clear = true;
+            }
+            _ => {}
+        },
+        SourceMenuScreen::KubernetesDiscovery => match key.code {
+            KeyCode::Esc => {
+                app.source_menu.screen = SourceMenuScreen::MainMenu;
+            }
+            KeyCode::Up
+                if app.source_menu.discovery_cursor > 0 =>
+            {
+                app.source_menu.discovery_cursor -= 1;
+            }
+            KeyCode::Down => {
+                let count = app.source_menu.kube_targets.len();
+                if count > 0 && app.source_menu.discovery_cursor < count - 1 {
+                    app.source_menu.discovery_cursor += 1;
+                }
+            }
+            KeyCode::Char(' ') => {
+                app.source_menu.toggle_selection();
+            }
+            KeyCode::Char('r') => {
+                app.source_menu.kube_loading = true;
+                app.source_menu.kube_error = None;
+                app.source_menu.kube_targets.clear();
+                app.source_menu.selected.clear();
+                app.source_menu.discovery_cursor = 0;
+                if let Some(dtx) = app.discovery_tx.clone() {
+                    discovery::discover_kubernetes(dtx);
+                }
+            }
+            KeyCode::Enter => {
+                app.spawn_selected_kubernetes_sources();
+                app.mode = AppMode::Normal;
+                app.needs_clear = true;
+            }
+            _ => {}
+        },
+        SourceMenuScreen::JournaldDiscovery => match key.code {
+            KeyCode::Esc => {
+                app.source_menu.screen = SourceMenuScreen::MainMenu;
+            }
+            KeyCode::Up
+                if app.source_menu.discovery_cursor > 0 =>
+            {
+                app.source_menu.discovery_cursor -= 1;
+            }
+            KeyCode::Down => {
+                let count = app.source_menu.journald_units.len();
+                if count > 0 && app.source_menu.discovery_cursor < count - 1 {
+                    app.source_menu.discovery_cursor += 1;
+                }
+            }
+            KeyCode::Char(' ') => {
+                app.source_menu.toggle_selection();
+            }
+            KeyCode::Char('a') => {
+                // Follow the whole journal
+                app.add_journald_source(ingest::journald::JournalFilter::default());
+                app.mode = AppMode::Normal;
+                app.needs_clear = true;
+            }
+            KeyCode::Char('r') => {
+                app.source_menu.journald_loading = true;
+                app.source_menu.journald_error = None;
+                app.source_menu.journald_units.clear();
+                app.source_menu.selected.clear();
+                app.source_menu.discovery_cursor = 0;
+                if let Some(dtx) = app.discovery_tx.clone() {
+                    discovery::discover_journald(dtx);
+                }
+            }
+            KeyCode::Enter => {
+                app.spawn_selected_journald_sources();
+                app.mode = AppMode::Normal;
+                app.needs_clear = true;
+            }
+            _ => {}
+        },
+        SourceMenuScreen::FileInput => match key.code {
+            KeyCode::Esc => {
+                app.source_menu.screen = SourceMenuScreen::MainMenu;
+            }
+            KeyCode::Backspace => {
+                app.source_menu.text_input.pop();
+            }
+            KeyCode::Enter => {
+                let path = app.source_menu.text_input.trim().to_string();
+                if !path.is_empty() {
+                    app.add_file_source(path);
+                }
+                app.mode = AppMode::Normal;
+                app.needs_clear = true;
+            }
+            KeyCode::Char(c) => {
+                app.source_menu.text_input.push(c);
+            }
+            _ => {}
+        },
+        SourceMenuScreen::SshInput => match key.code {
+            KeyCode::Esc => {
+                app.source_menu.screen = SourceMenuScreen::MainMenu;
+            }
+            KeyCode::Backspace => {
+                app.source_menu.text_input.pop();
+            }
+            KeyCode::Enter => {
+                let spec = app.source_menu.text_input.trim().to_string();
+                if spec.is_empty() {
+                    app.mode = AppMode::Normal;
+                    app.needs_clear = true;
+                } else {
+                    // Keep the screen open on a bad spec so it can be fixed
+                    match ingest::ssh::SshTarget::parse(&spec) {
+                        Ok(target) => {
+                            app.add_ssh_source(target);
+                            app.mode = AppMode::Normal;
+                            app.needs_clear = true;
+                        }
+                        Err(e) => app.source_menu.ssh_error = Some(e.to_string()),
+                    }
+                }
+            }
+            KeyCode::Char(c) => {
+                app.source_menu.text_input.push(c);
+            }
+            _ => {}
+        },
+        SourceMenuScreen::CommandInput => match key.code {
+            KeyCode::Esc => {
+                app.source_menu.screen = SourceMenuScreen::MainMenu;
+            }
+            KeyCode::Backspace => {
+                app.source_menu.text_input.pop();
+            }
+            KeyCode::Enter => {
+                let cmd = app.source_menu.text_input.trim().to_string();
+                if !cmd.is_empty() {
+                    app.add_command_source(cmd);
+                }
+                app.mode = AppMode::Normal;
+                app.needs_clear = true;
+            }
+            KeyCode::Char(c) => {
+                app.source_menu.text_input.push(c);
+            }
+            _ => {}
+        },
+    }
+}
@@ -0,0 +1,555 @@
+//! Named actions and the keys bound to them: the built-in defaults, the
+//! `vim` / `emacs` presets, and overrides from `[keys]` in the config.
+
+use std::collections::HashMap;
+
+use anyhow::{bail, Context, Result};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::config::{KeyList, KeysConfig};
+
+pub const PRESETS: &[&str] = &["default", "vim", "emacs"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    Quit,
+    Help,
+    Search,
+    ExportView,
+    ExportSamples,
+    AddSource,
+    Pause,
+    ProfilePicker,
+    Alerts,
+    ResetPatterns,
+    ClearCounters,
+    ToggleNormalized,
+    CycleTheme,
+    NextPane,
+    PrevPane,
+    MoveUp,
+    MoveDown,
+    PageUp,
+    PageDown,
+    Top,
+    Bottom,
+    Select,
+    Back,
+    ShrinkPane,
+    GrowPane,
+    Zoom,
+    ToggleSources,
+    CycleLayout,
+    SaveLayout,
+    SaveWorkspace,
+    Palette,
+    ReplayPause,
+    ReplayFaster,
+    ReplaySlower,
+    ReplayBack,
+    ReplayForward,
+    ReplayRestart,
+}
+
+impl Action {
+    /// Every action, in the order the help overlay lists them.
+    pub const ALL: &'static [Action] = &[
+        Action::NextPane,
+        Action::PrevPane,
+        Action::MoveUp,
+        Action::MoveDown,
+        Action::PageUp,
+        Action::PageDown,
+        Action::Top,
+        Action::Bottom,
+        Action::Select,
+        Action::Back,
+        Action::Search,
+        Action::Palette,
+        Action::ExportView,
+        Action::ExportSamples,
+        Action::AddSource,
+        Action::ToggleNormalized,
+        Action::CycleTheme,
+        Action::Pause,
+        Action::ResetPatterns,
+        Action::ClearCounters,
+        Action::ProfilePicker,
+        Action::Alerts,
+        Action::ShrinkPane,
+        Action::GrowPane,
+        Action::Zoom,
+        Action::ToggleSources,
+        Action::CycleLayout,
+        Action::SaveLayout,
+        Action::SaveWorkspace,
+        Action::ReplayPause,
+        Action::ReplayFaster,
+        Action::ReplaySlower,
+        Action::ReplayBack,
+        Action::ReplayForward,
+        Action::ReplayRestart,
+        Action::Help,
+        Action::Quit,
+    ];
+
+    /// The name used in `[keys]`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::Quit => "quit",
+            Action::Help => "help",
+            Action::Search => "search",
+            Action::ExportView => "export_view",
+            Action::ExportSamples => "export_samples",
+            Action::AddSource => "add_source",
+            Action::Pause => "pause",
+            Action::ProfilePicker => "profile_picker",
+            Action::Alerts => "alerts",
+            Action::ResetPatterns => "reset_patterns",
+            Action::ClearCounters => "clear_counters",
+            Action::ToggleNormalized => "toggle_normalized",
+            Action::CycleTheme => "cycle_theme",
+            Action::NextPane => "next_pane",
+            Action::PrevPane => "prev_pane",
+            Action::MoveUp => "move_up",
+            Action::MoveDown => "move_down",
+            Action::PageUp => "page_up",
+            Action::PageDown => "page_down",
+            Action::Top => "top",
+            Action::Bottom => "bottom",
+            Action::Select => "select",
+            Action::Back => "back",
+            Action::ShrinkPane => "shrink_pane",
+            Action::GrowPane => "grow_pane",
+            Action::Zoom => "zoom",
+            Action::ToggleSources => "toggle_sources",
+            Action::CycleLayout => "cycle_layout",
+            Action::SaveLayout => "save_layout",
+            Action::SaveWorkspace => "save_workspace",
+            Action::Palette => "palette",
+            Action::ReplayPause => "replay_pause",
+            Action::ReplayFaster => "replay_faster",
+            Action::ReplaySlower => "replay_slower",
+            Action::ReplayBack => "replay_back",
+            Action::ReplayForward => "replay_forward",
+            Action::ReplayRestart => "replay_restart",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Action::Quit => "Quit",
+            Action::Help => "Toggle help",
+            Action::Search => "Search patterns",
+            Action::ExportView => "Export view",
+            Action::ExportSamples => "Export selected samples",
+            Action::AddSource => "Add source (interactive)",
+            Action::Pause => "Pause / resume ingest",
+            Action::ProfilePicker => "Profile picker",
+            Action::Alerts => "Alerts log",
+            Action::ResetPatterns => "Reset all patterns",
+            Action::ClearCounters => "Clear counters",
+            Action::ToggleNormalized => "Toggle normalized / raw",
+            Action::CycleTheme => "Cycle theme",
+            Action::NextPane => "Next pane",
+            Action::PrevPane => "Previous pane",
+            Action::MoveUp => "Move up",
+            Action::MoveDown => "Move down",
+            Action::PageUp => "Page up",
+            Action::PageDown => "Page down",
+            Action::Top => "First row",
+            Action::Bottom => "Last row",
+            Action::Select => "Drilldown / filter by source",
+            Action::Back => "Back / clear filter",
+            Action::ShrinkPane => "Shrink focused pane",
+            Action::GrowPane => "Grow focused pane",
+            Action::Zoom => "Zoom focused pane",
+            Action::ToggleSources => "Hide / show Sources pane",
+            Action::CycleLayout => "Layout: auto / side by side / stacked",
+            Action::SaveLayout => "Save layout to config",
+            Action::SaveWorkspace => "Save sources as a workspace",
+            Action::Palette => "Command palette",
+            Action::ReplayPause => "Replay: pause / resume",
+            Action::ReplayFaster => "Replay: faster",
+            Action::ReplaySlower => "Replay: slower",
+            Action::ReplayBack => "Replay: seek -10s",
+            Action::ReplayForward => "Replay: seek +10s",
+            Action::ReplayRestart => "Replay: restart",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Action> {
+        Action::ALL.iter().copied().find(|a| a.name() == name)
+    }
+
+    fn default_keys(self) -> &'static [&'static str] {
+        match self {
+            Action::Quit => &["q"],
+            Action::Help => &["?"],
+            Action::Search => &["/"],
+            Action::ExportView => &["e"],
+            Action::ExportSamples => &["E"],
+            Action::AddSource => &["a"],
+            Action::Pause => &["p"],
+            Action::ProfilePicker => &["P"],
+            Action::Alerts => &["A"],
+            // A lone `r` is too easy to hit for a wipe with no undo
+            Action::ResetPatterns => &["R"],
+            Action::ClearCounters => &["c"],
+            Action::ToggleNormalized => &["n"],
+            Action::CycleTheme => &["t"],
+            Action::NextPane => &["tab"],
+            Action::PrevPane => &["shift-tab"],
+            Action::MoveUp => &["up", "k"],
+            Action::MoveDown => &["down", "j"],
+            Action::PageUp => &["pageup"],
+            Action::PageDown => &["pagedown"],
+            Action::Top => &["g"],
+            Action::Bottom => &["G"],
+            Action::Select => &["enter"],
+            Action::Back => &["esc"],
+            Action::ShrinkPane => &["<"],
+            Action::GrowPane => &[">"],
+            Action::Zoom => &["z"],
+            Action::ToggleSources => &["S"],
+            Action::CycleLayout => &["L"],
+            Action::SaveLayout => &["W"],
+            Action::SaveWorkspace => &["w"],
+            Action::Palette => &[":", "ctrl-p"],
+            Action::ReplayPause => &["space"],
+            Action::ReplayFaster => &["+", "="],
+            Action::ReplaySlower => &["-"],
+            Action::ReplayBack => &["["],
+            Action::ReplayForward => &["]"],
+            Action::ReplayRestart => &["home"],
+        }
+    }
+}
+
+/// Bindings a preset changes on top of the defaults.
+fn preset_keys(preset: &str) -> &'static [(Action, &'static [&'static str])] {
+    match preset {
+        "vim" => &[
+            (Action::PrevPane, &["shift-tab", "h"]),
+            (Action::NextPane, &["tab", "l"]),
+            (Action::PageUp, &["pageup", "ctrl-u", "ctrl-b"]),
+            (Action::PageDown, &["pagedown", "ctrl-d", "ctrl-f"]),
+        ],
+        "emacs" => &[
+            (Action::MoveUp, &["up", "k", "ctrl-p"]),
+            (Action::MoveDown, &["down", "j", "ctrl-n"]),
+            (Action::PageUp, &["pageup", "alt-v"]),
+            (Action::PageDown, &["pagedown", "ctrl-v"]),
+            (Action::Top, &["g", "alt-<"]),
+            (Action::Bottom, &["G", "alt->"]),
+            (Action::Search, &["/", "ctrl-s"]),
+            (Action::Back, &["esc", "ctrl-g"]),
+            (Action::NextPane, &["tab", "ctrl-o"]),
+            (Action::Quit, &["q", "ctrl-c"]),
+            // Ctrl-P moves up here
+            (Action::Palette, &[":", "alt-x"]),
+        ],
+        _ => &[],
+    }
+}
+
+/// A key with the modifiers that matter. Shift is folded into the character
+/// (`A`, `<`) so it is only kept for non-character keys.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyBinding {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyBinding {
+    pub fn from_event(key: &KeyEvent) -> Self {
+        let mut modifiers = key.modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SHIFT);
+        let code = match key.code {
+            KeyCode::Char(c) => {
+                modifiers.remove(KeyModifiers::SHIFT);
+                // Terminals report Ctrl+letter in either case
+                if modifiers.contains(KeyModifiers::CONTROL) {
+                    KeyCode::Char(c.to_ascii_lowercase())
+                } else {
+                    KeyCode::Char(c)
+                }
+            }
+            KeyCode::BackTab => {
+                modifiers.remove(KeyModifiers::SHIFT);
+                KeyCode::BackTab
+            }
+            other => other,
+        };
+        KeyBinding { code, modifiers }
+    }
+
+    /// Parse `q`, `G`, `ctrl-r`, `C-r`, `alt-v`, `M-<`, `shift-tab`, `f5`,
+    /// `pagedown` and the like.
+    pub fn parse(spec: &str) -> Result<Self> {
+        let mut rest = spec;
+        let mut modifiers = KeyModifiers::NONE;
+        loop {
+            if rest.chars().count() == 1 {
+                break;
+            }
+            let lower = rest.to_ascii_lowercase();
+            let prefix = ["ctrl-", "ctrl+", "c-"]
+                .iter()
+                .map(|p| (p, KeyModifiers::CONTROL))
+                .chain(["alt-", "alt+", "m-"].iter().map(|p| (p, KeyModifiers::ALT)))
+                .chain(["shift-", "shift+", "s-"].iter().map(|p| (p, KeyModifiers::SHIFT)))
+                .find(|(p, _)| lower.starts_with(**p));
+            match prefix {
+                Some((p, m)) => {
+                    modifiers |= m;
+                    rest = &rest[p.len()..];
+                }
+                None => break,
+            }
+        }
+        let mut chars = rest.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => {
+                if modifiers.contains(KeyModifiers::SHIFT) {
+                    modifiers.remove(KeyModifiers::SHIFT);
+                    KeyCode::Char(c.to_ascii_uppercase())
+                } else if modifiers.contains(KeyModifiers::CONTROL) {
+                    KeyCode::Char(c.to_ascii_lowercase())
+                } else {
+                    KeyCode::Char(c)
+                }
+            }
+            _ => match rest.to_ascii_lowercase().as_str() {
+                "space" => KeyCode::Char(' '),
+                "tab" if modifiers.contains(KeyModifiers::SHIFT) => {
+                    modifiers.remove(KeyModifiers::SHIFT);
+                    KeyCode::BackTab
+                }
+                "tab" => KeyCode::Tab,
+                "backtab" => KeyCode::BackTab,
+                "enter" | "return" => KeyCode::Enter,
+                "esc" | "escape" => KeyCode::Esc,
+                "backspace" => KeyCode::Backspace,
+                "delete" | "del" => KeyCode::Delete,
+                "insert" | "ins" => KeyCode::Insert,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" | "pgup" => KeyCode::PageUp,
+                "pagedown" | "pgdn" => KeyCode::PageDown,
+                f if f.starts_with('f') && f[1..].parse::<u8>().is_ok_and(|n| (1..=12).contains(&n)) => {
+                    KeyCode::F(f[1..].parse().unwrap())
+                }
+                _ => bail!("unknown key '{}'", spec),
+            },
+        };
+        Ok(KeyBinding { code, modifiers })
+    }
+
+    /// How the help overlay shows the key.
+    pub fn label(&self) -> String {
+        let key = match self.code {
+            KeyCode::Char(' ') => "Space".to_string(),
+            KeyCode::Char(c) => c.to_string(),
+            KeyCode::Tab => "Tab".into(),
+            KeyCode::BackTab => "Shift+Tab".into(),
+            KeyCode::Enter => "Enter".into(),
+            KeyCode::Esc => "Esc".into(),
+            KeyCode::Backspace => "Backspace".into(),
+            KeyCode::Delete => "Delete".into(),
+            KeyCode::Insert => "Insert".into(),
+            KeyCode::Up => "Up".into(),
+            KeyCode::Down => "Down".into(),
+            KeyCode::Left => "Left".into(),
+            KeyCode::Right => "Right".into(),
+            KeyCode::Home => "Home".into(),
+            KeyCode::End => "End".into(),
+            KeyCode::PageUp => "PgUp".into(),
+            KeyCode::PageDown => "PgDn".into(),
+            KeyCode::F(n) => format!("F{}", n),
+            other => format!("{:?}", other),
+        };
+        let mut label = String::new();
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            label.push_str("Ctrl+");
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            label.push_str("Alt+");
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            label.push_str("Shift+");
+        }
+        label.push_str(&key);
+        label
+    }
+}
+
+pub struct Keymap {
+    pub preset: String,
+    bindings: HashMap<Action, Vec<KeyBinding>>,
+    lookup: HashMap<KeyBinding, Action>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::preset("default").unwrap()
+    }
+}
+
+impl Keymap {
+    /// The defaults with `name`'s changes applied.
+    pub fn preset(name: &str) -> Result<Self> {
+        if !PRESETS.contains(&name) {
+            bail!("keys: unknown preset '{}' (available: {})", name, PRESETS.join(", "));
+        }
+        let mut keymap = Keymap {
+            preset: name.to_string(),
+            bindings: HashMap::new(),
+            lookup: HashMap::new(),
+        };
+        for &action in Action::ALL {
+            keymap.bind_specs(action, action.default_keys())?;
+        }
+        for &(action, keys) in preset_keys(name) {
+            keymap.bind_specs(action, keys)?;
+        }
+        Ok(keymap)
+    }
+
+    /// The preset named in `[keys]`, with the config's bindings on top.
+    pub fn from_config(cfg: &KeysConfig) -> Result<Self> {
+        let mut keymap = Self::preset(cfg.preset.as_deref().unwrap_or("default"))?;
+        for (name, keys) in &cfg.bindings {
+            let Some(action) = Action::from_name(name) else {
+                bail!("keys: unknown action '{}'", name);
+            };
+            let specs: Vec<&str> = match keys {
+                KeyList::One(key) => vec![key.as_str()],
+                KeyList::Many(keys) => keys.iter().map(String::as_str).collect(),
+            };
+            keymap
+                .bind_specs(action, &specs)
+                .with_context(|| format!("keys.{}", name))?;
+        }
+        Ok(keymap)
+    }
+
+    fn bind_specs(&mut self, action: Action, specs: &[&str]) -> Result<()> {
+        let keys = specs.iter().map(|s| KeyBinding::parse(s)).collect::<Result<Vec<_>>>()?;
+        self.bind(action, keys);
+        Ok(())
+    }
+
+    /// Replace `action`'s keys. A key already bound elsewhere moves here.
+    pub fn bind(&mut self, action: Action, keys: Vec<KeyBinding>) {
+        for old in self.bindings.remove(&action).unwrap_or_default() {
+            self.lookup.remove(&old);
+        }
+        for key in &keys {
+            if let Some(previous) = self.lookup.insert(*key, action) {
+                if let Some(list) = self.bindings.get_mut(&previous) {
+                    list.retain(|k| k != key);
+                }
+            }
+        }
+        self.bindings.insert(action, keys);
+    }
+
+    pub fn action(&self, key: &KeyEvent) -> Option<Action> {
+        self.lookup.get(&KeyBinding::from_event(key)).copied()
+    }
+
+    pub fn keys(&self, action: Action) -> &[KeyBinding] {
+        self.bindings.get(&action).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// `action`'s keys for display, e.g. `Up / k`; empty when unbound.
+    pub fn label(&self, action: Action) -> String {
+        self.keys(action)
+            .iter()
+            .map(KeyBinding::label)
+            .collect::<Vec<_>>()
+            .join(" / ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    #[test]
+    fn parses_key_specs() {
+        let cases = [
+            ("q", KeyCode::Char('q'), KeyModifiers::NONE),
+            ("G", KeyCode::Char('G'), KeyModifiers::NONE),
+            ("-", KeyCode::Char('-'), KeyModifiers::NONE),
+            ("ctrl-r", KeyCode::Char('r'), KeyModifiers::CONTROL),
+            ("C-R", KeyCode::Char('r'), KeyModifiers::CONTROL),
+            ("M-<", KeyCode::Char('<'), KeyModifiers::ALT),
+            ("shift-tab", KeyCode::BackTab, KeyModifiers::NONE),
+            ("shift-a", KeyCode::Char('A'), KeyModifiers::NONE),
+            ("PageDown", KeyCode::PageDown, KeyModifiers::NONE),
+            ("f5", KeyCode::F(5), KeyModifiers::NONE),
+            ("space", KeyCode::Char(' '), KeyModifiers::NONE),
+        ];
+        for (spec, code, modifiers) in cases {
+            assert_eq!(KeyBinding::parse(spec).unwrap(), KeyBinding { code, modifiers }, "{}", spec);
+        }
+        assert!(KeyBinding::parse("hyper-x").is_err());
+        assert!(KeyBinding::parse("f13").is_err());
+        assert_eq!(KeyBinding::parse("ctrl-alt-x").unwrap().label(), "Ctrl+Alt+x");
+    }
+
+    #[test]
+    fn events_match_regardless_of_shift_reporting() {
+        let keymap = Keymap::default();
+        let shifted = press(KeyCode::Char('A'), KeyModifiers::SHIFT);
+        assert_eq!(keymap.action(&shifted), Some(Action::Alerts));
+        let back_tab = press(KeyCode::BackTab, KeyModifiers::SHIFT);
+        assert_eq!(keymap.action(&back_tab), Some(Action::PrevPane));
+        assert_eq!(keymap.action(&press(KeyCode::Char('>'), KeyModifiers::SHIFT)), Some(Action::GrowPane));
+        assert_eq!(keymap.action(&press(KeyCode::Char('x'), KeyModifiers::NONE)), None);
+        // Resetting needs the capital
+        assert_eq!(keymap.action(&press(KeyCode::Char('r'), KeyModifiers::NONE)), None);
+        assert_eq!(keymap.action(&press(KeyCode::Char('R'), KeyModifiers::SHIFT)), Some(Action::ResetPatterns));
+        assert_eq!(keymap.label(Action::MoveUp), "Up / k");
+    }
+
+    #[test]
+    fn presets_and_config_overrides() {
+        let vim = Keymap::preset("vim").unwrap();
+        assert_eq!(vim.action(&press(KeyCode::Char('r'), KeyModifiers::NONE)), None);
+        assert_eq!(vim.action(&press(KeyCode::Char('R'), KeyModifiers::SHIFT)), Some(Action::ResetPatterns));
+        assert_eq!(vim.action(&press(KeyCode::Char('d'), KeyModifiers::CONTROL)), Some(Action::PageDown));
+        let emacs = Keymap::preset("emacs").unwrap();
+        assert_eq!(emacs.action(&press(KeyCode::Char('n'), KeyModifiers::CONTROL)), Some(Action::MoveDown));
+        assert!(Keymap::preset("helix").is_err());
+
+        let cfg: KeysConfig = toml::from_str(
+            r#"
+preset = "vim"
+clear_counters = ["x", "ctrl-l"]
+toggle_normalized = "c"
+"#,
+        )
+        .unwrap();
+        let keymap = Keymap::from_config(&cfg).unwrap();
+        assert_eq!(keymap.preset, "vim");
+        // `c` moved from clear_counters to toggle_normalized
+        assert_eq!(keymap.label(Action::ClearCounters), "x / Ctrl+l");
+        assert_eq!(keymap.action(&press(KeyCode::Char('c'), KeyModifiers::NONE)), Some(Action::ToggleNormalized));
+        assert_eq!(keymap.action(&press(KeyCode::Char('n'), KeyModifiers::NONE)), None);
+
+        let bad: KeysConfig = toml::from_str(r#"nuke = "x""#).unwrap();
+        assert!(Keymap::from_config(&bad).is_err());
+        let bad: KeysConfig = toml::from_str(r#"quit = "hyper-q""#).unwrap();
+        assert!(Keymap::from_config(&bad).is_err());
+    }
+}
@@ -0,0 +1,230 @@
+//! Pane arrangement: sizes, the hidden Sources pane, zoom and the stacked
+//! layout for narrow terminals.
+
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+
+use crate::app::Pane;
+use crate::config::{LayoutConfig, Orientation};
+
+/// Smallest share a pane can be resized or dragged to, in percent.
+const MIN_PANE_PERCENT: u16 = 10;
+/// How much `<` / `>` move a pane border, in percent.
+const RESIZE_STEP: u16 = 4;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LayoutState {
+    /// Shares of Sources, the middle pane and the right pane; sums to 100.
+    pub sizes: [u16; 3],
+    pub show_sources: bool,
+    pub orientation: Orientation,
+    pub stack_below: u16,
+    /// The focused pane fills the whole body.
+    pub zoomed: bool,
+}
+
+impl Default for LayoutState {
+    fn default() -> Self {
+        Self::from_config(&LayoutConfig::default())
+    }
+}
+
+fn pane_index(pane: Pane) -> usize {
+    match pane {
+        Pane::Sources => 0,
+        Pane::Patterns => 1,
+        Pane::Details => 2,
+    }
+}
+
+impl LayoutState {
+    /// Sizes are scaled to sum to 100; each pane keeps at least the minimum.
+    pub fn from_config(cfg: &LayoutConfig) -> Self {
+        let raw = [cfg.sources, cfg.patterns, cfg.details].map(|s| s.max(1) as u32);
+        let total: u32 = raw.iter().sum();
+        let mut sizes = raw.map(|s| ((s * 100 / total) as u16).max(MIN_PANE_PERCENT));
+        // Rounding and the minimum land on the largest pane
+        let largest = (0..3).max_by_key(|&i| sizes[i]).unwrap_or(1);
+        let others: u16 = (0..3).filter(|&i| i != largest).map(|i| sizes[i]).sum();
+        sizes[largest] = 100 - others;
+        LayoutState {
+            sizes,
+            show_sources: cfg.show_sources,
+            orientation: cfg.orientation,
+            stack_below: cfg.stack_below,
+            zoomed: false,
+        }
+    }
+
+    pub fn to_config(&self) -> LayoutConfig {
+        LayoutConfig {
+            sources: self.sizes[0],
+            patterns: self.sizes[1],
+            details: self.sizes[2],
+            show_sources: self.show_sources,
+            orientation: self.orientation,
+            stack_below: self.stack_below,
+        }
+    }
+
+    /// Whether panes are stacked top to bottom in a body `width` columns wide.
+    pub fn is_vertical(&self, width: u16) -> bool {
+        match self.orientation {
+            Orientation::Auto => width < self.stack_below,
+            Orientation::Horizontal => false,
+            Orientation::Vertical => true,
+        }
+    }
+
+    fn visible(&self) -> Vec<usize> {
+        (0..3).filter(|&i| i != 0 || self.show_sources).collect()
+    }
+
+    /// Areas for Sources, the middle pane and the right pane. Hidden panes
+    /// get an empty area. `focused` is the pane a zoom keeps.
+    pub fn split(&self, body: Rect, focused: Pane) -> [Rect; 3] {
+        let mut rects = [Rect::new(body.x, body.y, 0, 0); 3];
+        if self.zoomed {
+            rects[pane_index(focused)] = body;
+            return rects;
+        }
+        let visible = self.visible();
+        let total: u32 = visible.iter().map(|&i| self.sizes[i] as u32).sum();
+        let direction = if self.is_vertical(body.width) {
+            Direction::Vertical
+        } else {
+            Direction::Horizontal
+        };
+        let chunks = Layout::default()
+            .direction(direction)
+            .constraints(
+                visible
+                    .iter()
+                    .map(|&i| Constraint::Ratio(self.sizes[i] as u32, total))
+                    .collect::<Vec<_>>(),
+            )
+            .split(body);
+        for (chunk, &i) in chunks.iter().zip(&visible) {
+            rects[i] = *chunk;
+        }
+        rects
+    }
+
+    /// Grow (or shrink) `pane` by one step, trading space with its neighbour.
+    pub fn resize(&mut self, pane: Pane, grow: bool) {
+        let i = pane_index(pane);
+        let neighbours: &[usize] = match i {
+            0 => &[1, 2],
+            1 => &[2, 0],
+            _ => &[1, 0],
+        };
+        let donor_ok = |sizes: &[u16; 3], j: usize| sizes[j] >= MIN_PANE_PERCENT + RESIZE_STEP;
+        let neighbour = neighbours
+            .iter()
+            .copied()
+            .filter(|&j| j != 0 || self.show_sources)
+            .find(|&j| !grow || donor_ok(&self.sizes, j));
+        let Some(j) = neighbour else {
+            return;
+        };
+        let (from, to) = if grow { (j, i) } else { (i, j) };
+        if donor_ok(&self.sizes, from) {
+            self.sizes[from] -= RESIZE_STEP;
+            self.sizes[to] += RESIZE_STEP;
+        }
+    }
+
+    /// Move pane border `border` (0 = before the middle pane, 1 = after it)
+    /// to `percent` of the body.
+    pub fn drag(&mut self, border: usize, percent: u16) {
+        let lead = if self.show_sources { self.sizes[0] } else { 0 };
+        let total = lead + self.sizes[1] + self.sizes[2];
+        let pos = percent.min(100) * total / 100;
+        if border == 0 {
+            let span = self.sizes[0] + self.sizes[1];
+            let sources = pos.clamp(MIN_PANE_PERCENT, span - MIN_PANE_PERCENT);
+            self.sizes[0] = sources;
+            self.sizes[1] = span - sources;
+        } else {
+            let split = pos.clamp(lead + MIN_PANE_PERCENT, total - MIN_PANE_PERCENT);
+            self.sizes[1] = split - lead;
+            self.sizes[2] = total - split;
+        }
+    }
+
+    pub fn cycle_orientation(&mut self) {
+        self.orientation = match self.orientation {
+            Orientation::Auto => Orientation::Horizontal,
+            Orientation::Horizontal => Orientation::Vertical,
+            Orientation::Vertical => Orientation::Auto,
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_hides_zooms_and_stacks() {
+        let body = Rect::new(0, 1, 200, 40);
+        let mut layout = LayoutState::default();
+        let [sources, middle, right] = layout.split(body, Pane::Patterns);
+        assert_eq!((sources.width, sources.height), (36, 40));
+        assert_eq!(middle.x, 36);
+        assert_eq!(right.x + right.width, 200);
+
+        layout.show_sources = false;
+        let [sources, middle, right] = layout.split(body, Pane::Patterns);
+        assert!(sources.is_empty());
+        assert_eq!(middle.x, 0);
+        assert_eq!(middle.width + right.width, 200);
+
+        layout.zoomed = true;
+        assert_eq!(layout.split(body, Pane::Details), [Rect::new(0, 1, 0, 0), Rect::new(0, 1, 0, 0), body]);
+
+        layout.zoomed = false;
+        let narrow = Rect::new(0, 1, 80, 40);
+        let [_, middle, right] = layout.split(narrow, Pane::Patterns);
+        assert_eq!((middle.width, right.width), (80, 80));
+        assert_eq!(right.y, middle.y + middle.height);
+        layout.orientation = Orientation::Horizontal;
+        assert!(!layout.is_vertical(80));
+    }
+
+    #[test]
+    fn resize_trades_with_neighbours_within_limits() {
+        let mut layout = LayoutState::default();
+        layout.resize(Pane::Details, true);
+        assert_eq!(layout.sizes, [18, 48, 34]);
+        layout.resize(Pane::Sources, false);
+        assert_eq!(layout.sizes, [14, 52, 34]);
+        for _ in 0..20 {
+            layout.resize(Pane::Sources, false);
+        }
+        assert_eq!(layout.sizes[0], 10);
+        for _ in 0..30 {
+            layout.resize(Pane::Patterns, true);
+        }
+        assert_eq!(layout.sizes, [10, 80, 10]);
+        assert_eq!(layout.sizes.iter().sum::<u16>(), 100);
+    }
+
+    #[test]
+    fn config_sizes_are_normalized() {
+        let layout = LayoutState::from_config(&LayoutConfig {
+            sources: 1,
+            patterns: 2,
+            details: 2,
+            ..LayoutConfig::default()
+        });
+        assert_eq!(layout.sizes, [20, 40, 40]);
+        let layout = LayoutState::from_config(&LayoutConfig {
+            sources: 0,
+            patterns: 90,
+            details: 10,
+            ..LayoutConfig::default()
+        });
+        assert_eq!(layout.sizes, [10, 80, 10]);
+        assert_eq!(layout.to_config().patterns, 80);
+    }
+}
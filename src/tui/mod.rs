@@ -1,2 +1,89 @@
+pub mod input;
+pub mod keymap;
+pub mod layout;
+pub mod mouse;
+pub mod palette;
 pub mod source_menu;
 pub mod ui;
+
+use std::io::Write as _;
+use std::time::Duration;
+
+use anyhow::Result;
+use crossterm::event::{self, DisableMouseCapture, EnableMouseCapture, Event};
+use crossterm::terminal::{self, EnterAlternateScreen, LeaveAlternateScreen};
+use ratatui::backend::CrosstermBackend;
+use tokio::sync::mpsc;
+
+use crate::app::App;
+use crate::discovery::DiscoveryResult;
+use crate::ingest::SourceEvent;
+
+/// Take over the terminal and run the dashboard until the user quits.
+pub fn run(
+    mut app: App,
+    mut rx: mpsc::Receiver<SourceEvent>,
+    mut discovery_rx: mpsc::Receiver<DiscoveryResult>,
+) -> Result<()> {
+    // Setup terminal
+    terminal::enable_raw_mode()?;
+    let mut stdout = std::io::stdout();
+    crossterm::execute!(stdout, EnterAlternateScreen)?;
+    if app.mouse {
+        crossterm::execute!(stdout, EnableMouseCapture)?;
+    }
+    let backend = CrosstermBackend::new(stdout);
+    let mut terminal = ratatui::Terminal::new(backend)?;
+
+    let tick_rate = Duration::from_millis(50);
+
+    loop {
+        if app.needs_clear {
+            terminal.clear()?;
+            app.needs_clear = false;
+        }
+        app.update_filtered_view();
+        terminal.draw(|f| ui::render(f, &mut app))?;
+        if std::mem::take(&mut app.bell_pending) {
+            let backend = terminal.backend_mut();
+            backend.write_all(b"\x07")?;
+            backend.flush()?;
+        }
+
+        if event::poll(tick_rate)? {
+            match event::read()? {
+                Event::Key(key) => input::handle_key_event(&mut app, key),
+                Event::Mouse(mouse) => mouse::handle_mouse_event(&mut app, mouse),
+                _ => {}
+            }
+        }
+
+        // Drain log events (non-blocking)
+        while let Ok(ev) = rx.try_recv() {
+            app.process_event(ev);
+        }
+
+        // Drain discovery results
+        while let Ok(result) = discovery_rx.try_recv() {
+            app.handle_discovery_result(result);
+        }
+
+        app.tick();
+        app.poll_config();
+        app.tick_count += 1;
+
+        if app.should_quit {
+            break;
+        }
+    }
+
+    // Restore terminal
+    terminal::disable_raw_mode()?;
+    if app.mouse {
+        crossterm::execute!(terminal.backend_mut(), DisableMouseCapture)?;
+    }
+    crossterm::execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    terminal.show_cursor()?;
+
+    Ok(())
+}
@@ -0,0 +1,335 @@
+//! Mouse handling: click to focus and select, wheel to scroll, drag the
+//! borders between panes to resize them.
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::layout::Rect;
+
+use crate::app::{App, AppMode, Pane};
+use crate::tui::input;
+use crate::tui::keymap::Action;
+use crate::tui::source_menu::SourceMenuScreen;
+
+/// Where the last frame drew each region, so clicks can be mapped back to
+/// rows. Filled in by `ui::render`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PaneLayout {
+    pub body: Rect,
+    pub sources: Rect,
+    pub middle: Rect,
+    pub right: Rect,
+    /// Panes are stacked top to bottom.
+    pub vertical: bool,
+    /// The source menu popup, while it is open.
+    pub menu: Option<Rect>,
+}
+
+fn contains(area: Rect, col: u16, row: u16) -> bool {
+    col >= area.x && col < area.x + area.width && row >= area.y && row < area.y + area.height
+}
+
+/// Row inside a bordered block, or None on the border itself.
+fn inner_row(area: Rect, row: u16) -> Option<usize> {
+    if row > area.y && row + 1 < area.y + area.height {
+        Some((row - area.y - 1) as usize)
+    } else {
+        None
+    }
+}
+
+/// The border (0 = before the middle pane, 1 = after it) under the pointer.
+fn border_at(layout: &PaneLayout, col: u16, row: u16) -> Option<usize> {
+    if !contains(layout.body, col, row) {
+        return None;
+    }
+    let (pos, middle, right) = if layout.vertical {
+        (row, layout.middle.y, layout.right.y)
+    } else {
+        (col, layout.middle.x, layout.right.x)
+    };
+    // Each border is two cells: the edges of the panes on either side
+    let near = |edge: u16| pos == edge || pos + 1 == edge;
+    if !layout.sources.is_empty() && !layout.middle.is_empty() && near(middle) {
+        Some(0)
+    } else if !layout.middle.is_empty() && !layout.right.is_empty() && near(right) {
+        Some(1)
+    } else {
+        None
+    }
+}
+
+/// Move pane border `border` to the pointer.
+fn drag_border(app: &mut App, border: usize, col: u16, row: u16) {
+    let body = app.layout.body;
+    let (pos, start, len) = if app.layout.vertical {
+        (row, body.y, body.height)
+    } else {
+        (col, body.x, body.width)
+    };
+    if len == 0 {
+        return;
+    }
+    let percent = (pos.saturating_sub(start) as u32 * 100 / len as u32) as u16;
+    app.layout_state.drag(border, percent);
+}
+
+fn key(code: KeyCode) -> KeyEvent {
+    KeyEvent::new(code, KeyModifiers::NONE)
+}
+
+/// Dispatch a mouse event according to the current mode.
+pub fn handle_mouse_event(app: &mut App, event: MouseEvent) {
+    let (col, row) = (event.column, event.row);
+    match event.kind {
+        MouseEventKind::Down(MouseButton::Left) => click(app, col, row),
+        MouseEventKind::Drag(MouseButton::Left) => {
+            if let Some(border) = app.dragging {
+                drag_border(app, border, col, row);
+            }
+        }
+        MouseEventKind::Up(MouseButton::Left) => app.dragging = None,
+        MouseEventKind::ScrollUp => scroll(app, col, row, true),
+        MouseEventKind::ScrollDown => scroll(app, col, row, false),
+        _ => {}
+    }
+}
+
+fn click(app: &mut App, col: u16, row: u16) {
+    match app.mode {
+        AppMode::SourceMenu => {
+            match app.layout.menu {
+                Some(menu) if contains(menu, col, row) => click_source_menu(app, menu, col, row),
+                // Clicking outside the popup backs out of it, like Esc
+                _ => input::handle_key_event(app, key(KeyCode::Esc)),
+            }
+            return;
+        }
+        AppMode::Help => {
+            app.mode = AppMode::Normal;
+            app.needs_clear = true;
+            return;
+        }
+        // Prompts own the keyboard; leave them alone
+        AppMode::Search | AppMode::Export | AppMode::Palette => return,
+        _ => {}
+    }
+
+    if let Some(border) = border_at(&app.layout, col, row) {
+        app.dragging = Some(border);
+        return;
+    }
+
+    let layout = app.layout;
+    if contains(layout.sources, col, row) {
+        if app.mode != AppMode::Normal {
+            return;
+        }
+        app.active_pane = Pane::Sources;
+        if let Some(i) = inner_row(layout.sources, row) {
+            if i < app.visible_source_rows().len() {
+                // A second click on the selected row filters / collapses it
+                if i == app.selected_source {
+                    app.activate_selected_source();
+                }
+                app.selected_source = i;
+            }
+        }
+    } else if contains(layout.middle, col, row) {
+        click_middle(app, layout.middle, row);
+    } else if contains(layout.right, col, row) && app.mode == AppMode::Normal {
+        app.active_pane = Pane::Details;
+    }
+}
+
+fn click_middle(app: &mut App, area: Rect, row: u16) {
+    let Some(i) = inner_row(area, row) else {
+        return;
+    };
+    match app.mode {
+        AppMode::Normal => {
+            app.active_pane = Pane::Patterns;
+            // Row 0 is the table header
+            let Some(i) = i.checked_sub(1) else {
+                return;
+            };
+            // The table scrolls just far enough to keep the selection visible
+            let visible = (area.height as usize).saturating_sub(3).max(1);
+            let offset = app.selected_pattern.saturating_sub(visible - 1);
+            let index = offset + i;
+            if index >= app.filtered_view.len() {
+                return;
+            }
+            if index == app.selected_pattern {
+                input::perform(app, Action::Select);
+            } else {
+                app.selected_pattern = index;
+                app.detail_scroll = 0;
+            }
+        }
+        AppMode::Drilldown if app.selected_pattern_data().is_some_and(|p| i < p.samples.len()) => {
+            app.detail_scroll = i;
+        }
+        AppMode::ProfilePicker if i < app.profiles.len() => {
+            if i == app.profile_index {
+                app.mode = AppMode::Normal;
+            }
+            app.profile_index = i;
+        }
+        _ => {}
+    }
+}
+
+fn click_source_menu(app: &mut App, menu: Rect, col: u16, row: u16) {
+    let Some(i) = inner_row(menu, row) else {
+        return;
+    };
+    let menu_state = &mut app.source_menu;
+    match menu_state.screen {
+        SourceMenuScreen::MainMenu => {
+            if i < crate::tui::source_menu::MAIN_MENU_ITEMS.len() {
+                menu_state.main_cursor = i;
+                input::handle_key_event(app, key(KeyCode::Enter));
+            }
+        }
+        SourceMenuScreen::DockerDiscovery
+        | SourceMenuScreen::AzureDiscovery
+        | SourceMenuScreen::KubernetesDiscovery
+        | SourceMenuScreen::JournaldDiscovery => {
+            if i >= menu_state.discovery_item_count() {
+                return;
+            }
+            // Columns: border, "▸ " cursor, then the "[ ] " checkbox
+            let on_checkbox = (menu.x + 3..menu.x + 7).contains(&col);
+            let was_cursor = menu_state.discovery_cursor == i;
+            menu_state.discovery_cursor = i;
+            if on_checkbox || was_cursor {
+                menu_state.toggle_selection();
+            }
+        }
+        SourceMenuScreen::AzureRevisions => {
+            if i >= menu_state.azure_revisions.len() {
+                return;
+            }
+            if menu_state.revision_cursor == i {
+                input::handle_key_event(app, key(KeyCode::Enter));
+            } else {
+                menu_state.revision_cursor = i;
+            }
+        }
+        SourceMenuScreen::FileInput | SourceMenuScreen::SshInput | SourceMenuScreen::CommandInput => {}
+    }
+}
+
+fn scroll(app: &mut App, col: u16, row: u16, up: bool) {
+    let arrow = key(if up { KeyCode::Up } else { KeyCode::Down });
+    match app.mode {
+        AppMode::SourceMenu
+        | AppMode::Alerts
+        | AppMode::Drilldown
+        | AppMode::ProfilePicker => {
+            input::handle_key_event(app, arrow);
+            return;
+        }
+        AppMode::Normal => {}
+        _ => return,
+    }
+
+    // Scroll whichever pane is under the pointer, without moving focus
+    let layout = app.layout;
+    let pane = if contains(layout.sources, col, row) {
+        Pane::Sources
+    } else if contains(layout.middle, col, row) {
+        Pane::Patterns
+    } else if contains(layout.right, col, row) {
+        Pane::Details
+    } else {
+        return;
+    };
+    let focused = std::mem::replace(&mut app.active_pane, pane);
+    if up {
+        app.move_up();
+    } else {
+        app.move_down();
+    }
+    app.active_pane = focused;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn app_with_layout() -> App {
+        let mut app = App::new(None);
+        app.layout = PaneLayout {
+            body: Rect::new(0, 1, 100, 30),
+            sources: Rect::new(0, 1, 18, 30),
+            middle: Rect::new(18, 1, 52, 30),
+            right: Rect::new(70, 1, 30, 30),
+            vertical: false,
+            menu: None,
+        };
+        app
+    }
+
+    fn mouse(kind: MouseEventKind, column: u16, row: u16) -> MouseEvent {
+        MouseEvent {
+            kind,
+            column,
+            row,
+            modifiers: KeyModifiers::NONE,
+        }
+    }
+
+    #[test]
+    fn click_focuses_and_wheel_scrolls_under_pointer() {
+        let mut app = app_with_layout();
+        handle_mouse_event(&mut app, mouse(MouseEventKind::Down(MouseButton::Left), 80, 10));
+        assert_eq!(app.active_pane, Pane::Details);
+
+        // The wheel scrolls Details without taking focus from Sources
+        app.active_pane = Pane::Sources;
+        handle_mouse_event(&mut app, mouse(MouseEventKind::ScrollDown, 80, 10));
+        handle_mouse_event(&mut app, mouse(MouseEventKind::ScrollDown, 80, 10));
+        assert_eq!(app.detail_scroll, 2);
+        assert_eq!(app.active_pane, Pane::Sources);
+    }
+
+    #[test]
+    fn dragging_a_border_resizes_panes() {
+        let mut app = app_with_layout();
+        assert_eq!(app.layout_state.sizes, [18, 52, 30]);
+        handle_mouse_event(&mut app, mouse(MouseEventKind::Down(MouseButton::Left), 18, 5));
+        assert_eq!(app.dragging, Some(0));
+        handle_mouse_event(&mut app, mouse(MouseEventKind::Drag(MouseButton::Left), 30, 5));
+        assert_eq!(app.layout_state.sizes, [30, 40, 30]);
+        // Clamped so the middle pane keeps its minimum width
+        handle_mouse_event(&mut app, mouse(MouseEventKind::Drag(MouseButton::Left), 99, 5));
+        assert_eq!(app.layout_state.sizes, [60, 10, 30]);
+        handle_mouse_event(&mut app, mouse(MouseEventKind::Up(MouseButton::Left), 99, 5));
+        assert_eq!(app.dragging, None);
+
+        handle_mouse_event(&mut app, mouse(MouseEventKind::Down(MouseButton::Left), 70, 5));
+        handle_mouse_event(&mut app, mouse(MouseEventKind::Drag(MouseButton::Left), 80, 5));
+        assert_eq!(app.layout_state.sizes, [60, 20, 20]);
+    }
+
+    #[test]
+    fn source_menu_clicks_select_and_toggle() {
+        let mut app = app_with_layout();
+        app.mode = AppMode::SourceMenu;
+        app.source_menu.screen = SourceMenuScreen::JournaldDiscovery;
+        app.source_menu.journald_units = vec!["a.service".into(), "b.service".into()];
+        let menu = Rect::new(20, 5, 60, 20);
+        app.layout.menu = Some(menu);
+
+        // Clicking the label moves the cursor; clicking the checkbox toggles
+        handle_mouse_event(&mut app, mouse(MouseEventKind::Down(MouseButton::Left), 40, 7));
+        assert_eq!(app.source_menu.discovery_cursor, 1);
+        assert!(app.source_menu.selected.is_empty());
+        handle_mouse_event(&mut app, mouse(MouseEventKind::Down(MouseButton::Left), 24, 6));
+        assert_eq!(app.source_menu.discovery_cursor, 0);
+        assert!(app.source_menu.selected.contains(&0));
+
+        handle_mouse_event(&mut app, mouse(MouseEventKind::Down(MouseButton::Left), 5, 2));
+        assert_eq!(app.source_menu.screen, SourceMenuScreen::MainMenu);
+    }
+}
@@ -0,0 +1,509 @@
+//! The command palette: fuzzy-matched named actions plus commands that
+//! take arguments (`theme ember`, `source add file /var/log/x`,
+//! `export json out.json`, `level warn`), with history.
+
+use anyhow::{bail, Context, Result};
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+
+use crate::app::{App, AppMode, Pane};
+use crate::export::{ExportFormat, ExportPrompt, ExportScope};
+use crate::ingest::{Source, SOURCE_KINDS};
+use crate::parse::Level;
+use crate::theme::Theme;
+use crate::tui::input;
+use crate::tui::keymap::Action;
+
+/// Most commands kept in the history.
+const HISTORY_LEN: usize = 100;
+
+#[derive(Debug, Clone, Default)]
+pub struct PaletteState {
+    pub input: String,
+    /// Highlighted suggestion.
+    pub selected: usize,
+    /// Commands run, oldest first.
+    pub history: Vec<String>,
+}
+
+/// A command that takes arguments.
+struct Command {
+    name: &'static str,
+    usage: &'static str,
+    description: &'static str,
+    /// Can't run without an argument.
+    needs_args: bool,
+}
+
+const COMMANDS: &[Command] = &[
+    Command {
+        name: "theme",
+        usage: "<name>",
+        description: "Switch theme",
+        needs_args: true,
+    },
+    Command {
+        name: "profile",
+        usage: "<name>",
+        description: "Switch profile",
+        needs_args: true,
+    },
+    Command {
+        name: "level",
+        usage: "<trace|debug|info|warn|error|profile>",
+        description: "Minimum level to keep (profile = the profile's)",
+        needs_args: true,
+    },
+    Command {
+        name: "source",
+        usage: "add <kind> <target>",
+        description: "Add a source, e.g. source add file /var/log/syslog",
+        needs_args: true,
+    },
+    Command {
+        name: "export",
+        usage: "[samples] [json|csv|md] [path]",
+        description: "Export the view (or the selected samples) now",
+        needs_args: false,
+    },
+    Command {
+        name: "workspace",
+        usage: "<name>",
+        description: "Open a [workspaces] entry: its sources, profile and theme",
+        needs_args: true,
+    },
+    Command {
+        name: "save_workspace",
+        usage: "<name>",
+        description: "Save the running sources as a workspace",
+        needs_args: true,
+    },
+    Command {
+        name: "filter",
+        usage: "[source]",
+        description: "Show one source's patterns (none clears)",
+        needs_args: false,
+    },
+    Command {
+        name: "search",
+        usage: "[query]",
+        description: "Filter patterns (no query opens search)",
+        needs_args: false,
+    },
+];
+
+/// Actions reachable under their own name. Search and SaveWorkspace are
+/// commands taking arguments.
+fn palette_actions() -> impl Iterator<Item = Action> {
+    Action::ALL
+        .iter()
+        .copied()
+        .filter(|a| !matches!(a, Action::Search | Action::Palette | Action::SaveWorkspace))
+}
+
+/// One row of the palette.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Suggestion {
+    /// The command line it stands for.
+    pub text: String,
+    pub usage: &'static str,
+    pub description: String,
+    /// Characters of `text` matched by the input.
+    pub matched: Vec<usize>,
+    /// Runs as is; otherwise Enter completes it and waits for arguments.
+    pub complete: bool,
+}
+
+fn command(name: &str) -> Option<&'static Command> {
+    COMMANDS.iter().find(|c| c.name == name)
+}
+
+fn is_command(name: &str) -> bool {
+    command(name).is_some() || palette_actions().any(|a| a.name() == name)
+}
+
+/// Rows for the current input: history then every command when empty,
+/// matching commands while typing a name, argument values after it.
+pub fn suggestions(app: &App) -> Vec<Suggestion> {
+    let input = app.palette.input.trim_start();
+    let matcher = SkimMatcherV2::default();
+
+    let Some((name, rest)) = input.split_once(' ') else {
+        let mut rows = Vec::new();
+        if input.is_empty() {
+            let mut seen = Vec::new();
+            for line in app.palette.history.iter().rev() {
+                if !seen.contains(line) {
+                    seen.push(line.clone());
+                    rows.push(Suggestion {
+                        text: line.clone(),
+                        usage: "",
+                        description: "history".to_string(),
+                        matched: Vec::new(),
+                        complete: true,
+                    });
+                }
+            }
+        }
+        let mut commands: Vec<(i64, Suggestion)> = COMMANDS
+            .iter()
+            .map(|c| (c.name, c.usage, c.description.to_string(), !c.needs_args))
+            .chain(palette_actions().map(|a| {
+                let keys = app.keymap.label(a);
+                let description = if keys.is_empty() {
+                    a.description().to_string()
+                } else {
+                    format!("{} ({})", a.description(), keys)
+                };
+                (a.name(), "", description, true)
+            }))
+            .filter_map(|(name, usage, description, complete)| {
+                let (score, matched) = if input.is_empty() {
+                    (0, Vec::new())
+                } else {
+                    matcher.fuzzy_indices(name, input)?
+                };
+                Some((
+                    score,
+                    Suggestion {
+                        text: name.to_string(),
+                        usage,
+                        description,
+                        matched,
+                        complete,
+                    },
+                ))
+            })
+            .collect();
+        commands.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+        rows.extend(commands.into_iter().map(|(_, s)| s));
+        return rows;
+    };
+
+    // Completing an argument: everything typed so far stays as the prefix
+    let args: Vec<&str> = rest.split_whitespace().collect();
+    let (done, partial) = if rest.is_empty() || rest.ends_with(' ') {
+        (&args[..], "")
+    } else {
+        (&args[..args.len() - 1], args[args.len() - 1])
+    };
+    let (candidates, complete) = arg_candidates(app, name, done);
+    let prefix = std::iter::once(name)
+        .chain(done.iter().copied())
+        .collect::<Vec<_>>()
+        .join(" ");
+    let mut rows: Vec<(i64, Suggestion)> = candidates
+        .into_iter()
+        .filter_map(|value| {
+            let (score, matched) = if partial.is_empty() {
+                (0, Vec::new())
+            } else {
+                matcher.fuzzy_indices(&value, partial)?
+            };
+            let offset = prefix.chars().count() + 1;
+            Some((
+                score,
+                Suggestion {
+                    text: format!("{} {}", prefix, value),
+                    usage: "",
+                    description: String::new(),
+                    matched: matched.into_iter().map(|i| i + offset).collect(),
+                    complete,
+                },
+            ))
+        })
+        .collect();
+    rows.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    rows.into_iter().map(|(_, s)| s).collect()
+}
+
+/// Values for the next argument of `name` after `done`, and whether the
+/// command can run once one is picked.
+fn arg_candidates(app: &App, name: &str, done: &[&str]) -> (Vec<String>, bool) {
+    let strings = |values: &[&str]| values.iter().map(|v| v.to_string()).collect();
+    match (name, done) {
+        ("theme", []) => (Theme::all_names(&app.custom_themes), true),
+        ("profile", []) => (app.profiles.iter().map(|p| p.name.clone()).collect(), true),
+        ("level", []) => (strings(&["trace", "debug", "info", "warn", "error", "profile"]), true),
+        ("source", []) => (strings(&["add"]), false),
+        ("source", ["add"]) => (strings(SOURCE_KINDS), false),
+        ("export", []) => (strings(&["samples", "json", "csv", "md"]), true),
+        ("export", ["samples"]) => (strings(&["json", "csv", "md"]), true),
+        ("workspace" | "save_workspace", []) => (app.workspaces.keys().cloned().collect(), true),
+        ("filter", []) => (app.sources.iter().map(|s| s.id.clone()).collect(), true),
+        _ => (Vec::new(), true),
+    }
+}
+
+impl App {
+    pub fn open_palette(&mut self) {
+        self.palette.input.clear();
+        self.palette.selected = 0;
+        self.mode = AppMode::Palette;
+    }
+}
+
+/// Enter: run the typed command, or the highlighted suggestion. A command
+/// still missing its arguments is completed instead, and the palette stays.
+pub fn submit(app: &mut App) {
+    let typed = app.palette.input.trim().to_string();
+    let name = typed.split_whitespace().next().unwrap_or("");
+    let line = if is_command(name) {
+        typed.clone()
+    } else {
+        match suggestions(app).into_iter().nth(app.palette.selected) {
+            Some(s) if !s.complete => {
+                app.palette.input = format!("{} ", s.text);
+                app.palette.selected = 0;
+                return;
+            }
+            Some(s) => s.text,
+            None => typed.clone(),
+        }
+    };
+    let mut words = line.split_whitespace();
+    let name = words.next().unwrap_or("");
+    if command(name).is_some_and(|c| c.needs_args) && words.next().is_none() {
+        app.palette.input = format!("{} ", name);
+        app.palette.selected = 0;
+        return;
+    }
+
+    app.mode = AppMode::Normal;
+    app.needs_clear = true;
+    if line.is_empty() {
+        return;
+    }
+    app.palette.history.retain(|h| *h != line);
+    app.palette.history.push(line.clone());
+    if app.palette.history.len() > HISTORY_LEN {
+        app.palette.history.remove(0);
+    }
+    if let Err(e) = execute(app, &line) {
+        app.show_toast(format!("{:#}", e), true);
+    }
+}
+
+/// Tab: replace the input with the highlighted suggestion.
+pub fn complete(app: &mut App) {
+    if let Some(s) = suggestions(app).into_iter().nth(app.palette.selected) {
+        let takes_args = command(&s.text).is_some() || !s.complete;
+        app.palette.input = if takes_args { format!("{} ", s.text) } else { s.text };
+        app.palette.selected = 0;
+    }
+}
+
+/// Run one command line.
+pub fn execute(app: &mut App, line: &str) -> Result<()> {
+    let mut words = line.split_whitespace();
+    let Some(name) = words.next() else {
+        return Ok(());
+    };
+    let args: Vec<&str> = words.collect();
+    match name {
+        "theme" => {
+            let [name] = args[..] else {
+                bail!("usage: theme <name>");
+            };
+            let theme = Theme::by_name(name, &app.custom_themes).with_context(|| {
+                let names = Theme::all_names(&app.custom_themes);
+                format!("unknown theme '{}' (available: {})", name, names.join(", "))
+            })?;
+            app.theme_override = Some(theme);
+        }
+        "profile" => {
+            let [name] = args[..] else {
+                bail!("usage: profile <name>");
+            };
+            let index = app
+                .profiles
+                .iter()
+                .position(|p| p.name == name)
+                .with_context(|| format!("unknown profile '{}'", name))?;
+            app.profile_index = index;
+        }
+        "level" => {
+            let [name] = args[..] else {
+                bail!("usage: level <trace|debug|info|warn|error|profile>");
+            };
+            app.level_override = if name == "profile" {
+                None
+            } else {
+                Some(Level::from_name(name).with_context(|| format!("unknown level '{}'", name))?)
+            };
+            app.show_toast(format!("Keeping {} and above", app.min_level()), false);
+        }
+        "source" => {
+            let ["add", kind, ref target @ ..] = args[..] else {
+                bail!("usage: source add <kind> <target>");
+            };
+            let source = Source::parse(kind, &target.join(" "))?;
+            app.add_source(source);
+        }
+        "workspace" => {
+            let [name] = args[..] else {
+                bail!("usage: workspace <name>");
+            };
+            app.open_workspace(name)?;
+        }
+        "save_workspace" => {
+            let [name] = args[..] else {
+                bail!("usage: save_workspace <name>");
+            };
+            app.save_workspace(name)?;
+        }
+        "export" => {
+            let mut args = &args[..];
+            let scope = match args.first() {
+                Some(&"samples") => {
+                    args = &args[1..];
+                    ExportScope::Samples
+                }
+                _ => ExportScope::View,
+            };
+            if scope == ExportScope::Samples && app.selected_pattern_data().is_none() {
+                bail!("No pattern selected");
+            }
+            let mut prompt = ExportPrompt::new(scope);
+            let format = args.first().and_then(|a| ExportFormat::from_name(a));
+            if let Some(format) = format {
+                args = &args[1..];
+                while prompt.effective_format() != format {
+                    prompt.cycle_format();
+                }
+            }
+            match args {
+                [] => {}
+                [path] => {
+                    // The path's extension would otherwise silently win
+                    match (format, ExportFormat::from_path(path)) {
+                        (Some(format), Some(ext)) if ext != format => bail!(
+                            "{} is a .{} path but the format is {}",
+                            path,
+                            ext.extension(),
+                            format.label()
+                        ),
+                        _ => {}
+                    }
+                    prompt.path = path.to_string();
+                }
+                _ => bail!("usage: export [samples] [json|csv|md] [path]"),
+            }
+            app.export_prompt = Some(prompt);
+            app.run_export();
+        }
+        "filter" => {
+            app.active_source_filter = match args[..] {
+                [] => None,
+                [wanted] => {
+                    let source = app
+                        .sources
+                        .iter()
+                        .find(|s| s.id == wanted)
+                        .or_else(|| app.sources.iter().find(|s| s.id.contains(wanted)))
+                        .with_context(|| format!("no source matching '{}'", wanted))?;
+                    Some(source.id.clone())
+                }
+                _ => bail!("usage: filter [source]"),
+            };
+            app.selected_pattern = 0;
+        }
+        "search" => {
+            if args.is_empty() {
+                app.enter_search();
+            } else {
+                app.search_query = args.join(" ");
+                app.active_pane = Pane::Patterns;
+                app.selected_pattern = 0;
+            }
+        }
+        _ => {
+            let action = Action::from_name(name)
+                .filter(|a| palette_actions().any(|p| p == *a))
+                .with_context(|| format!("unknown command '{}'", name))?;
+            if !args.is_empty() {
+                bail!("{} takes no arguments", name);
+            }
+            input::perform(app, action);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn type_line(app: &mut App, line: &str) {
+        app.open_palette();
+        app.palette.input = line.to_string();
+    }
+
+    #[test]
+    fn suggestions_fuzzy_match_commands_and_arguments() {
+        let mut app = App::new(None);
+        type_line(&mut app, "rstpat");
+        assert_eq!(suggestions(&app)[0].text, "reset_patterns");
+
+        type_line(&mut app, "theme emb");
+        let rows = suggestions(&app);
+        assert_eq!(rows[0].text, "theme ember");
+        assert_eq!(rows[0].matched, vec![6, 7, 8]);
+
+        type_line(&mut app, "source add ");
+        let rows = suggestions(&app);
+        assert_eq!(rows.len(), SOURCE_KINDS.len());
+        assert!(!rows[0].complete);
+
+        // An empty input lists history first, newest first and deduplicated
+        app.palette.history = vec!["level warn".into(), "zoom".into(), "level warn".into()];
+        type_line(&mut app, "");
+        let rows = suggestions(&app);
+        assert_eq!(rows[0].text, "level warn");
+        assert_eq!(rows[1].text, "zoom");
+        assert_eq!(rows[2].description, "Switch theme");
+    }
+
+    #[test]
+    fn submit_runs_commands_and_records_history() {
+        let mut app = App::new(None);
+        type_line(&mut app, "level warn");
+        submit(&mut app);
+        assert_eq!(app.mode, AppMode::Normal);
+        assert_eq!(app.min_level(), Level::Warn);
+
+        // A fuzzy name runs the highlighted match
+        type_line(&mut app, "togsrc");
+        submit(&mut app);
+        assert!(!app.layout_state.show_sources);
+
+        // Missing arguments complete instead of running
+        type_line(&mut app, "thm");
+        submit(&mut app);
+        assert_eq!(app.mode, AppMode::Palette);
+        assert_eq!(app.palette.input, "theme ");
+
+        type_line(&mut app, "theme nope");
+        submit(&mut app);
+        assert!(app.active_toast().is_some_and(|t| t.error && t.message.contains("unknown theme")));
+        assert_eq!(app.palette.history, vec!["level warn", "toggle_sources", "theme nope"]);
+
+        execute(&mut app, "level profile").unwrap();
+        assert_eq!(app.level_override, None);
+        assert!(execute(&mut app, "zoom now").is_err());
+        assert!(execute(&mut app, "source add ftp x").is_err());
+    }
+
+    #[test]
+    fn export_command_picks_format_and_path() {
+        let mut app = App::new(None);
+        let path = std::env::temp_dir().join(format!("logradar-palette-{}.out", std::process::id()));
+        execute(&mut app, &format!("export csv {}", path.display())).unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+        assert!(content.starts_with("pattern,level,count"), "{}", content);
+        assert!(execute(&mut app, "export samples").is_err());
+        let err = execute(&mut app, "export json out.csv").unwrap_err().to_string();
+        assert!(err.contains("out.csv is a .csv path but the format is JSON"), "{}", err);
+    }
+}
@@ -1,13 +1,19 @@
 use std::collections::HashSet;
 
-use crate::discovery::{AzureContainerApp, DockerContainer};
+use crate::discovery::{AzureContainerApp, DockerContainer, KubeTarget};
+use crate::ingest::azure::AzureRevision;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SourceMenuScreen {
     MainMenu,
     DockerDiscovery,
     AzureDiscovery,
+    /// Revision picker for one Azure app (reached with `v` from AzureDiscovery).
+    AzureRevisions,
+    KubernetesDiscovery,
+    JournaldDiscovery,
     FileInput,
+    SshInput,
     CommandInput,
 }
 
@@ -15,6 +21,9 @@ pub const MAIN_MENU_ITEMS: &[&str] = &[
     "Docker Container",
     "File (tail)",
     "Azure Container App",
+    "Kubernetes",
+    "journald (systemd)",
+    "SSH (remote file/journald)",
     "Custom Command",
 ];
 
@@ -26,10 +35,32 @@ pub struct SourceMenuState {
     pub text_input: String,
     pub docker_containers: Vec<DockerContainer>,
     pub azure_apps: Vec<AzureContainerApp>,
+    pub kube_targets: Vec<KubeTarget>,
+    pub journald_units: Vec<String>,
+    /// Only show Azure apps from this subscription id (None = all).
+    pub azure_subscription_filter: Option<String>,
+    /// Also stream each added app's system logs as a separate source.
+    pub azure_system_logs: bool,
+    pub azure_revision_app: Option<AzureContainerApp>,
+    pub azure_revisions: Vec<AzureRevision>,
+    pub revision_cursor: usize,
+    pub azure_revisions_loading: bool,
+    pub azure_revisions_error: Option<String>,
     pub docker_loading: bool,
     pub azure_loading: bool,
+    pub kube_loading: bool,
+    pub journald_loading: bool,
     pub docker_error: Option<String>,
     pub azure_error: Option<String>,
+    pub kube_error: Option<String>,
+    pub journald_error: Option<String>,
+    pub ssh_error: Option<String>,
+}
+
+impl Default for SourceMenuState {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl SourceMenuState {
@@ -42,10 +73,24 @@ impl SourceMenuState {
             text_input: String::new(),
             docker_containers: Vec::new(),
             azure_apps: Vec::new(),
+            kube_targets: Vec::new(),
+            journald_units: Vec::new(),
+            azure_subscription_filter: None,
+            azure_system_logs: false,
+            azure_revision_app: None,
+            azure_revisions: Vec::new(),
+            revision_cursor: 0,
+            azure_revisions_loading: false,
+            azure_revisions_error: None,
             docker_loading: false,
             azure_loading: false,
+            kube_loading: false,
+            journald_loading: false,
             docker_error: None,
             azure_error: None,
+            kube_error: None,
+            journald_error: None,
+            ssh_error: None,
         }
     }
 
@@ -57,20 +102,80 @@ impl SourceMenuState {
         self.text_input.clear();
         self.docker_containers.clear();
         self.azure_apps.clear();
+        self.kube_targets.clear();
+        self.journald_units.clear();
+        // azure_system_logs is a preference and survives re-opening the menu
+        self.azure_subscription_filter = None;
+        self.azure_revision_app = None;
+        self.azure_revisions.clear();
+        self.revision_cursor = 0;
+        self.azure_revisions_loading = false;
+        self.azure_revisions_error = None;
         self.docker_loading = false;
         self.azure_loading = false;
+        self.kube_loading = false;
+        self.journald_loading = false;
         self.docker_error = None;
         self.azure_error = None;
+        self.kube_error = None;
+        self.journald_error = None;
+        self.ssh_error = None;
     }
 
     pub fn discovery_item_count(&self) -> usize {
         match self.screen {
             SourceMenuScreen::DockerDiscovery => self.docker_containers.len(),
-            SourceMenuScreen::AzureDiscovery => self.azure_apps.len(),
+            SourceMenuScreen::AzureDiscovery => self.visible_azure_apps().len(),
+            SourceMenuScreen::KubernetesDiscovery => self.kube_targets.len(),
+            SourceMenuScreen::JournaldDiscovery => self.journald_units.len(),
             _ => 0,
         }
     }
 
+    /// Azure apps under the current subscription filter; the cursor and
+    /// selection index into this list.
+    pub fn visible_azure_apps(&self) -> Vec<&AzureContainerApp> {
+        self.azure_apps
+            .iter()
+            .filter(|a| {
+                self.azure_subscription_filter
+                    .as_ref()
+                    .is_none_or(|sub| &a.subscription_id == sub)
+            })
+            .collect()
+    }
+
+    /// `(id, name)` subscriptions among the discovered apps, by name then
+    /// id, one entry per id.
+    pub fn azure_subscriptions(&self) -> Vec<(String, String)> {
+        let mut subs: Vec<(String, String)> = self
+            .azure_apps
+            .iter()
+            .map(|a| (a.subscription_id.clone(), a.subscription_name.clone()))
+            .collect();
+        subs.sort_by(|a, b| (&a.1, &a.0).cmp(&(&b.1, &b.0)));
+        let mut seen = HashSet::new();
+        subs.retain(|(id, _)| seen.insert(id.clone()));
+        subs
+    }
+
+    /// Step the subscription filter: all → each subscription in turn → all.
+    pub fn cycle_azure_subscription_filter(&mut self) {
+        let subs = self.azure_subscriptions();
+        let position = self
+            .azure_subscription_filter
+            .as_ref()
+            .and_then(|current| subs.iter().position(|(id, _)| id == current));
+        let next = match position {
+            None if self.azure_subscription_filter.is_none() => subs.first(),
+            None => None,
+            Some(i) => subs.get(i + 1),
+        };
+        self.azure_subscription_filter = next.map(|(id, _)| id.clone());
+        self.discovery_cursor = 0;
+        self.selected.clear();
+    }
+
     pub fn toggle_selection(&mut self) {
         let count = self.discovery_item_count();
         if count > 0 && self.discovery_cursor < count {
@@ -82,3 +187,44 @@ impl SourceMenuState {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn app(name: &str, subscription_id: &str, subscription_name: &str) -> AzureContainerApp {
+        AzureContainerApp {
+            name: name.into(),
+            resource_group: "rg".into(),
+            subscription_id: subscription_id.into(),
+            subscription_name: subscription_name.into(),
+            provisioning_state: "Succeeded".into(),
+        }
+    }
+
+    #[test]
+    fn subscription_filter_cycles_back_to_all_with_duplicate_names() {
+        let mut menu = SourceMenuState::new();
+        menu.azure_apps = vec![
+            app("a", "sub-2", "Prod"),
+            app("b", "sub-1", "Prod"),
+            app("c", "sub-3", "Dev"),
+            // Same id under another name
+            app("d", "sub-2", "Production"),
+            app("e", "sub-1", "Prod"),
+        ];
+        let subs = menu.azure_subscriptions();
+        let ids: Vec<&str> = subs.iter().map(|(id, _)| id.as_str()).collect();
+        assert_eq!(ids, vec!["sub-3", "sub-1", "sub-2"]);
+
+        let mut seen = Vec::new();
+        for _ in 0..4 {
+            menu.cycle_azure_subscription_filter();
+            seen.push(menu.azure_subscription_filter.clone());
+        }
+        assert_eq!(
+            seen,
+            vec![Some("sub-3".into()), Some("sub-1".into()), Some("sub-2".into()), None]
+        );
+    }
+}
@@ -9,9 +9,16 @@ use ratatui::{
 use chrono::Local;
 
 use crate::app::{App, AppMode, Pane};
+use crate::color::ColorDepth;
+use crate::discovery::KubeTargetKind;
+use crate::export::ExportScope;
 use crate::ingest::SourceStatus;
+use crate::session;
 use crate::theme::Theme;
-use crate::tui::source_menu::{SourceMenuScreen, MAIN_MENU_ITEMS};
+use crate::tui::keymap::Action;
+use crate::tui::mouse::PaneLayout;
+use crate::tui::palette;
+use crate::tui::source_menu::{SourceMenuScreen, SourceMenuState, MAIN_MENU_ITEMS};
 
 const SPINNER_CHARS: &[char] = &['◐', '◓', '◑', '◒'];
 
@@ -26,7 +33,7 @@ fn header_height(app: &App, terminal_height: u16) -> u16 {
 }
 
 pub fn render(f: &mut Frame, app: &mut App) {
-    let theme = app.theme().clone();
+    let theme = app.theme().for_depth(app.color_depth);
     let h = header_height(app, f.size().height);
 
     let main_chunks = Layout::default()
@@ -38,50 +45,64 @@ pub fn render(f: &mut Frame, app: &mut App) {
         ])
         .split(f.size());
 
-    let body_chunks = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([
-            Constraint::Percentage(18),
-            Constraint::Percentage(52),
-            Constraint::Percentage(30),
-        ])
-        .split(main_chunks[1]);
+    // Overlays and the picker / alerts views live in the middle pane, so a
+    // zoom keeps that one while they are open
+    let zoom_target = match app.mode {
+        AppMode::Normal
+        | AppMode::Search
+        | AppMode::Export
+        | AppMode::Help
+        | AppMode::SourceMenu
+        | AppMode::Palette => app.active_pane,
+        _ => Pane::Patterns,
+    };
+    let body_chunks = app.layout_state.split(main_chunks[1], zoom_target);
+    app.layout = PaneLayout {
+        body: main_chunks[1],
+        sources: body_chunks[0],
+        middle: body_chunks[1],
+        right: body_chunks[2],
+        vertical: app.layout_state.is_vertical(main_chunks[1].width),
+        menu: None,
+    };
 
     render_header(f, main_chunks[0], app, &theme);
 
-    if app.mode == AppMode::Help {
+    if !body_chunks[0].is_empty() {
         render_sources(f, body_chunks[0], app, &theme);
-        render_patterns(f, body_chunks[1], app, &theme);
-        render_details(f, body_chunks[2], app, &theme);
-        render_status_bar(f, main_chunks[2], app, &theme);
-        let help_area = centered_rect(60, 80, f.size());
-        render_help(f, help_area, &theme);
-        return;
     }
-
-    render_sources(f, body_chunks[0], app, &theme);
-
-    match app.mode {
-        AppMode::ProfilePicker => {
-            render_profile_picker(f, body_chunks[1], app, &theme);
-            render_details(f, body_chunks[2], app, &theme);
+    if !body_chunks[1].is_empty() {
+        match app.mode {
+            AppMode::ProfilePicker => render_profile_picker(f, body_chunks[1], app, &theme),
+            AppMode::Alerts => render_alerts(f, body_chunks[1], app, &theme),
+            AppMode::Drilldown => render_drilldown(f, body_chunks[1], app, &theme),
+            _ => render_patterns(f, body_chunks[1], app, &theme),
         }
-        AppMode::Drilldown => {
-            render_drilldown(f, body_chunks[1], app, &theme);
-            render_drilldown_detail(f, body_chunks[2], app, &theme);
-        }
-        _ => {
-            render_patterns(f, body_chunks[1], app, &theme);
-            render_details(f, body_chunks[2], app, &theme);
+    }
+    if !body_chunks[2].is_empty() {
+        match app.mode {
+            AppMode::Drilldown => render_drilldown_detail(f, body_chunks[2], app, &theme),
+            _ => render_details(f, body_chunks[2], app, &theme),
         }
     }
 
     render_status_bar(f, main_chunks[2], app, &theme);
 
+    if app.mode == AppMode::Help {
+        let help_area = centered_rect(60, 80, f.size());
+        render_help(f, help_area, app, &theme);
+    }
+
     if app.mode == AppMode::SourceMenu {
         let menu_area = centered_rect(60, 70, f.size());
+        app.layout.menu = Some(menu_area);
         render_source_menu(f, menu_area, app, &theme);
     }
+
+    if app.mode == AppMode::Palette {
+        let palette_area = centered_rect(60, 60, f.size());
+        render_palette(f, palette_area, app, &theme);
+    }
 }
 
 fn pane_block<'a>(title: &str, focused: bool, theme: &Theme) -> Block<'a> {
@@ -114,10 +135,10 @@ fn render_sources(f: &mut Frame, area: Rect, app: &App, theme: &Theme) {
     let rows = app.visible_source_rows();
 
     if rows.is_empty() {
-        let msg = if app.sources.is_empty() {
-            "No sources (a=add)"
-        } else {
-            "No sources"
+        let add_key = app.keymap.keys(Action::AddSource).first().map(|k| k.label());
+        let msg = match add_key {
+            Some(key) if app.sources.is_empty() => format!("No sources ({}=add)", key),
+            _ => "No sources".to_string(),
         };
         let empty = Paragraph::new(Span::styled(
             msg,
@@ -211,7 +232,7 @@ fn render_sources(f: &mut Frame, area: Rect, app: &App, theme: &Theme) {
                     _ => theme.text_dim,
                 };
                 // Show just the name part after the kind prefix
-                let display_name = src.id.split('/').nth(1).unwrap_or(&src.id);
+                let display_name = source_display_name(&src.id);
                 ListItem::new(Line::from(vec![
                     Span::raw("  "),
                     Span::styled(format!("{} ", marker), Style::default().fg(marker_color)),
@@ -229,10 +250,41 @@ fn render_sources(f: &mut Frame, area: Rect, app: &App, theme: &Theme) {
     f.render_widget(list, area);
 }
 
+/// Strip the `kind/` prefix from a source id (`k8s/prod/api-1/web` → `prod/api-1/web`).
+fn source_display_name(id: &str) -> &str {
+    id.split_once('/').map_or(id, |(_, rest)| rest)
+}
+
+/// Azure discovery title with the current subscription filter and
+/// system-log toggle spelled out next to their keys.
+fn azure_discovery_title(menu: &SourceMenuState) -> String {
+    let subscription = menu
+        .azure_subscription_filter
+        .as_ref()
+        .and_then(|id| {
+            menu.azure_subscriptions()
+                .into_iter()
+                .find(|(sub_id, _)| sub_id == id)
+                .map(|(_, name)| name)
+        })
+        .unwrap_or_else(|| "all".to_string());
+    let system = if menu.azure_system_logs { "on" } else { "off" };
+    format!(
+        " Azure Container Apps (Space=select, Enter=add, v=revisions, f=subscription: {}, s=system logs: {}, r=refresh) ",
+        subscription, system
+    )
+}
+
 fn provider_label(kind: &str) -> &str {
     match kind {
         "docker" => "Docker",
         "azure" => "Azure",
+        "kubernetes" => "Kubernetes",
+        "journald" => "journald",
+        "syslog" => "Syslog",
+        "http" => "HTTP",
+        "otlp" => "OTLP",
+        "replay" => "Replay",
         "command" => "Command",
         "file" => "File",
         _ => kind,
@@ -245,10 +297,7 @@ fn render_patterns(f: &mut Frame, area: Rect, app: &App, theme: &Theme) {
     let source_tag = app
         .active_source_filter
         .as_ref()
-        .map(|s| {
-            let short = s.split('/').nth(1).unwrap_or(s);
-            format!(" [{}]", short)
-        })
+        .map(|s| format!(" [{}]", source_display_name(s)))
         .unwrap_or_default();
     let title = if app.mode == AppMode::Search {
         format!("Patterns{} [/{}]", source_tag, app.search_query)
@@ -266,7 +315,11 @@ fn render_patterns(f: &mut Frame, area: Rect, app: &App, theme: &Theme) {
     let inner = block.inner(area);
 
     if app.filtered_view.is_empty() {
-        let msg = if app.store.len() == 0 {
+        let add_hint = match app.keymap.keys(Action::AddSource).first() {
+            Some(key) => format!("Press '{}' to add a source", key.label()),
+            None => String::new(),
+        };
+        let msg = if app.store.is_empty() {
             vec![
                 Line::from(""),
                 Line::from(Span::styled(
@@ -279,7 +332,7 @@ fn render_patterns(f: &mut Frame, area: Rect, app: &App, theme: &Theme) {
                 )),
                 Line::from(""),
                 Line::from(Span::styled(
-                    "Press 'a' to add a source",
+                    add_hint,
                     Style::default().fg(theme.accent),
                 )),
             ]
@@ -348,8 +401,6 @@ fn render_patterns(f: &mut Frame, area: Rect, app: &App, theme: &Theme) {
             let rate = p.rate_1m();
             let rate_str = if rate >= 100.0 {
                 format!("{:.0}", rate)
-            } else if rate >= 10.0 {
-                format!("{:.1}", rate)
             } else if rate > 0.0 {
                 format!("{:.1}", rate)
             } else {
@@ -488,7 +539,7 @@ fn render_details(f: &mut Frame, area: Rect, app: &App, theme: &Theme) {
             let src_list: Vec<&String> = pattern.sources.iter().collect();
             let src_str = src_list
                 .iter()
-                .map(|s| s.split('/').nth(1).unwrap_or(s))
+                .map(|s| source_display_name(s))
                 .collect::<Vec<_>>()
                 .join(", ");
             lines.push(Line::from(vec![
@@ -497,6 +548,14 @@ fn render_details(f: &mut Frame, area: Rect, app: &App, theme: &Theme) {
             ]));
         }
 
+        // Structured attributes (OTLP etc.)
+        for (key, value) in &pattern.fields {
+            lines.push(Line::from(vec![
+                Span::styled(format!("{} ", key), label_style),
+                Span::styled(value.clone(), Style::default().fg(theme.text_dim)),
+            ]));
+        }
+
         // --- Divider ---
         lines.push(Line::from(Span::styled(divider_str.clone(), divider_style)));
 
@@ -644,8 +703,62 @@ fn render_status_bar(f: &mut Frame, area: Rect, app: &App, theme: &Theme) {
         return;
     }
 
+    if let (AppMode::Export, Some(prompt)) = (app.mode, app.export_prompt.as_ref()) {
+        let what = match prompt.scope {
+            ExportScope::View => format!("{} patterns", app.filtered_view.len()),
+            ExportScope::Samples => "samples".to_string(),
+        };
+        let line = Line::from(vec![
+            Span::styled(
+                format!(" Export {} [{}] ", what, prompt.effective_format().label()),
+                style.add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                prompt.path.clone(),
+                Style::default()
+                    .fg(theme.accent)
+                    .bg(theme.status_bar_bg),
+            ),
+            Span::styled(
+                "█",
+                Style::default()
+                    .fg(theme.accent)
+                    .bg(theme.status_bar_bg),
+            ),
+            Span::styled("  Tab=format Enter=write Esc=cancel", style),
+        ]);
+        let bar = Paragraph::new(line).style(style);
+        f.render_widget(bar, area);
+        return;
+    }
+
     let paused = if app.paused { " PAUSED " } else { "" };
-    let profile_name = app.profile().name.clone();
+    let redacting = if app.redactor.is_some() { " REDACT " } else { "" };
+    let session = if let Some(ref replay) = app.replay {
+        format!(
+            " {} {}/{} {} ",
+            if replay.paused { "❚❚" } else { "▶" },
+            session::format_offset(replay.position),
+            session::format_offset(replay.total),
+            replay.speed.label(),
+        )
+    } else if app.recorder.is_some() {
+        " ● REC ".to_string()
+    } else if let Some(ref err) = app.record_error {
+        format!(" {} ", err)
+    } else {
+        String::new()
+    };
+    // Show when themes are being mapped down to fewer colors
+    let theme_label = match app.color_depth {
+        ColorDepth::TrueColor => theme.name.clone(),
+        ColorDepth::NoColor => format!("{} (no color)", theme.name),
+        depth => format!("{} ({} colors)", theme.name, depth.name()),
+    };
+    let profile_name = match app.level_override {
+        Some(level) => format!("{} ≥{}", app.profile().name, level),
+        None => app.profile().name.clone(),
+    };
 
     // Calculate total ingest rate
     let total_rate: f64 = app.source_rates.values().map(|ts| ts.len() as f64).sum();
@@ -656,7 +769,7 @@ fn render_status_bar(f: &mut Frame, area: Rect, app: &App, theme: &Theme) {
         theme.text_dim
     };
 
-    let line = Line::from(vec![
+    let mut line = Line::from(vec![
         Span::styled(
             " logradar ",
             Style::default()
@@ -680,7 +793,7 @@ fn render_status_bar(f: &mut Frame, area: Rect, app: &App, theme: &Theme) {
         ),
         Span::styled(" │ ", style),
         Span::styled(
-            theme.name.clone(),
+            theme_label,
             Style::default()
                 .fg(theme.accent)
                 .bg(theme.status_bar_bg),
@@ -689,6 +802,12 @@ fn render_status_bar(f: &mut Frame, area: Rect, app: &App, theme: &Theme) {
             format!(" │ {} ", profile_name),
             style,
         ),
+        Span::styled(
+            redacting.to_string(),
+            Style::default()
+                .fg(theme.success)
+                .bg(theme.status_bar_bg),
+        ),
         Span::styled(
             paused.to_string(),
             Style::default()
@@ -696,7 +815,49 @@ fn render_status_bar(f: &mut Frame, area: Rect, app: &App, theme: &Theme) {
                 .bg(theme.status_bar_bg)
                 .add_modifier(Modifier::BOLD),
         ),
+        Span::styled(
+            session,
+            Style::default()
+                .fg(if app.record_error.is_some() { theme.error } else { theme.header_accent })
+                .bg(theme.status_bar_bg)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(
+            // The previous config stays active until the file parses again
+            if app.config_error.is_some() { " CONFIG ERROR " } else { "" },
+            Style::default()
+                .fg(theme.error)
+                .bg(theme.status_bar_bg)
+                .add_modifier(Modifier::BOLD),
+        ),
         Span::styled("│ ", style),
+    ]);
+    if let Some(banner) = app.active_alert_banner() {
+        line.spans.push(Span::styled(
+            format!(" ALERT {} ", banner.message),
+            Style::default()
+                .fg(theme.status_bar_bg)
+                .bg(theme.error)
+                .add_modifier(Modifier::BOLD),
+        ));
+        line.spans.push(Span::styled(" A=alerts", style));
+        let bar = Paragraph::new(line).style(style);
+        f.render_widget(bar, area);
+        return;
+    }
+    if let Some(toast) = app.active_toast() {
+        line.spans.push(Span::styled(
+            toast.message.clone(),
+            Style::default()
+                .fg(if toast.error { theme.error } else { theme.success })
+                .bg(theme.status_bar_bg)
+                .add_modifier(Modifier::BOLD),
+        ));
+        let bar = Paragraph::new(line).style(style);
+        f.render_widget(bar, area);
+        return;
+    }
+    line.spans.extend([
         Span::styled("?", Style::default().fg(theme.accent).bg(theme.status_bar_bg).add_modifier(Modifier::BOLD)),
         Span::styled("=help ", Style::default().fg(theme.status_bar_fg).bg(theme.status_bar_bg)),
         Span::styled("a", Style::default().fg(theme.accent).bg(theme.status_bar_bg).add_modifier(Modifier::BOLD)),
@@ -841,7 +1002,7 @@ fn render_source_menu(f: &mut Frame, area: Rect, app: &App, theme: &Theme) {
                 .border_style(Style::default().fg(theme.modal_border))
                 .style(Style::default().bg(theme.modal_bg))
                 .title(Span::styled(
-                    " Azure Container Apps (Space=select, Enter=add, r=refresh) ",
+                    azure_discovery_title(menu),
                     Style::default()
                         .fg(theme.modal_title)
                         .add_modifier(Modifier::BOLD),
@@ -867,7 +1028,8 @@ fn render_source_menu(f: &mut Frame, area: Rect, app: &App, theme: &Theme) {
                 return;
             }
 
-            if menu.azure_apps.is_empty() {
+            let apps = menu.visible_azure_apps();
+            if apps.is_empty() {
                 let p = Paragraph::new(Span::styled(
                     "No Azure Container Apps found",
                     Style::default().fg(theme.text_dim),
@@ -877,8 +1039,13 @@ fn render_source_menu(f: &mut Frame, area: Rect, app: &App, theme: &Theme) {
                 return;
             }
 
-            let items: Vec<ListItem> = menu
-                .azure_apps
+            let name_width = apps.iter().map(|a| a.name.chars().count()).max().unwrap_or(0);
+            let sub_width = apps
+                .iter()
+                .map(|a| a.subscription_name.chars().count())
+                .max()
+                .unwrap_or(0);
+            let items: Vec<ListItem> = apps
                 .iter()
                 .enumerate()
                 .map(|(i, a)| {
@@ -905,9 +1072,9 @@ fn render_source_menu(f: &mut Frame, area: Rect, app: &App, theme: &Theme) {
                             checkbox.to_string(),
                             Style::default().fg(theme.success),
                         ),
-                        Span::styled(a.name.clone(), style),
+                        Span::styled(format!("{:<name_width$}", a.name), style),
                         Span::styled(
-                            format!("  ({})", a.resource_group),
+                            format!("  {:<sub_width$}  {}", a.subscription_name, a.resource_group),
                             Style::default().fg(theme.text_dim),
                         ),
                     ]))
@@ -917,6 +1084,276 @@ fn render_source_menu(f: &mut Frame, area: Rect, app: &App, theme: &Theme) {
             let list = List::new(items).block(block);
             f.render_widget(list, area);
         }
+        SourceMenuScreen::AzureRevisions => {
+            let app_name = menu
+                .azure_revision_app
+                .as_ref()
+                .map(|a| a.name.as_str())
+                .unwrap_or("");
+            let block = Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .border_style(Style::default().fg(theme.modal_border))
+                .style(Style::default().bg(theme.modal_bg))
+                .title(Span::styled(
+                    format!(" {} revisions (Enter=stream all replicas, Esc=back, r=refresh) ", app_name),
+                    Style::default()
+                        .fg(theme.modal_title)
+                        .add_modifier(Modifier::BOLD),
+                ));
+
+            if menu.azure_revisions_loading {
+                let p = Paragraph::new(Span::styled(
+                    "Listing revisions...",
+                    Style::default().fg(theme.text_dim),
+                ))
+                .block(block);
+                f.render_widget(p, area);
+                return;
+            }
+
+            if let Some(ref err) = menu.azure_revisions_error {
+                let p = Paragraph::new(Span::styled(
+                    err.clone(),
+                    Style::default().fg(theme.error),
+                ))
+                .block(block);
+                f.render_widget(p, area);
+                return;
+            }
+
+            if menu.azure_revisions.is_empty() {
+                let p = Paragraph::new(Span::styled(
+                    "No revisions found",
+                    Style::default().fg(theme.text_dim),
+                ))
+                .block(block);
+                f.render_widget(p, area);
+                return;
+            }
+
+            let items: Vec<ListItem> = menu
+                .azure_revisions
+                .iter()
+                .enumerate()
+                .map(|(i, r)| {
+                    let cursor = if i == menu.revision_cursor {
+                        "▸ "
+                    } else {
+                        "  "
+                    };
+                    let style = if i == menu.revision_cursor {
+                        Style::default()
+                            .fg(theme.selected_fg)
+                            .bg(theme.selected_bg)
+                    } else if r.active {
+                        Style::default().fg(theme.text)
+                    } else {
+                        Style::default().fg(theme.text_dim)
+                    };
+                    let (state, state_color) = if r.active {
+                        ("active  ", theme.success)
+                    } else {
+                        ("inactive", theme.text_dim)
+                    };
+                    ListItem::new(Line::from(vec![
+                        Span::styled(cursor, Style::default().fg(theme.accent)),
+                        Span::styled(format!("{} ", state), Style::default().fg(state_color)),
+                        Span::styled(r.name.clone(), style),
+                        Span::styled(
+                            format!(
+                                "  {}% traffic, {} replica{}, {}  {}",
+                                r.traffic_weight,
+                                r.replicas,
+                                if r.replicas == 1 { "" } else { "s" },
+                                r.running_state,
+                                r.created.get(..16).unwrap_or(&r.created),
+                            ),
+                            Style::default().fg(theme.text_dim),
+                        ),
+                    ]))
+                })
+                .collect();
+
+            let list = List::new(items).block(block);
+            f.render_widget(list, area);
+        }
+        SourceMenuScreen::KubernetesDiscovery => {
+            let block = Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .border_style(Style::default().fg(theme.modal_border))
+                .style(Style::default().bg(theme.modal_bg))
+                .title(Span::styled(
+                    " Kubernetes (Space=select, Enter=add, r=refresh) ",
+                    Style::default()
+                        .fg(theme.modal_title)
+                        .add_modifier(Modifier::BOLD),
+                ));
+
+            if menu.kube_loading {
+                let p = Paragraph::new(Span::styled(
+                    "Discovering namespaces, deployments and pods...",
+                    Style::default().fg(theme.text_dim),
+                ))
+                .block(block);
+                f.render_widget(p, area);
+                return;
+            }
+
+            if let Some(ref err) = menu.kube_error {
+                let p = Paragraph::new(Span::styled(
+                    err.clone(),
+                    Style::default().fg(theme.error),
+                ))
+                .block(block);
+                f.render_widget(p, area);
+                return;
+            }
+
+            if menu.kube_targets.is_empty() {
+                let p = Paragraph::new(Span::styled(
+                    "No pods found",
+                    Style::default().fg(theme.text_dim),
+                ))
+                .block(block);
+                f.render_widget(p, area);
+                return;
+            }
+
+            let items: Vec<ListItem> = menu
+                .kube_targets
+                .iter()
+                .enumerate()
+                .map(|(i, t)| {
+                    let checkbox = if menu.selected.contains(&i) {
+                        "[x] "
+                    } else {
+                        "[ ] "
+                    };
+                    let cursor = if i == menu.discovery_cursor {
+                        "▸ "
+                    } else {
+                        "  "
+                    };
+                    let style = if i == menu.discovery_cursor {
+                        Style::default()
+                            .fg(theme.selected_fg)
+                            .bg(theme.selected_bg)
+                    } else {
+                        Style::default().fg(theme.text)
+                    };
+                    let (label, detail) = match t.kind {
+                        KubeTargetKind::Namespace => {
+                            (format!("{} (all pods)", t.namespace), String::new())
+                        }
+                        KubeTargetKind::Deployment => (
+                            format!("  deploy/{}", t.name),
+                            format!("  {} ready", t.status),
+                        ),
+                        KubeTargetKind::Pod => {
+                            (format!("  pod/{}", t.name), format!("  {}", t.status))
+                        }
+                    };
+                    let label_style = if t.kind == KubeTargetKind::Namespace {
+                        style.add_modifier(Modifier::BOLD)
+                    } else {
+                        style
+                    };
+                    ListItem::new(Line::from(vec![
+                        Span::styled(cursor, Style::default().fg(theme.accent)),
+                        Span::styled(
+                            checkbox.to_string(),
+                            Style::default().fg(theme.success),
+                        ),
+                        Span::styled(label, label_style),
+                        Span::styled(detail, Style::default().fg(theme.text_dim)),
+                    ]))
+                })
+                .collect();
+
+            let list = List::new(items).block(block);
+            f.render_widget(list, area);
+        }
+        SourceMenuScreen::JournaldDiscovery => {
+            let block = Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .border_style(Style::default().fg(theme.modal_border))
+                .style(Style::default().bg(theme.modal_bg))
+                .title(Span::styled(
+                    " systemd Units (Space=select, Enter=add, a=all, r=refresh) ",
+                    Style::default()
+                        .fg(theme.modal_title)
+                        .add_modifier(Modifier::BOLD),
+                ));
+
+            if menu.journald_loading {
+                let p = Paragraph::new(Span::styled(
+                    "Listing journal units...",
+                    Style::default().fg(theme.text_dim),
+                ))
+                .block(block);
+                f.render_widget(p, area);
+                return;
+            }
+
+            if let Some(ref err) = menu.journald_error {
+                let p = Paragraph::new(Span::styled(
+                    err.clone(),
+                    Style::default().fg(theme.error),
+                ))
+                .block(block);
+                f.render_widget(p, area);
+                return;
+            }
+
+            if menu.journald_units.is_empty() {
+                let p = Paragraph::new(Span::styled(
+                    "No units found in the journal",
+                    Style::default().fg(theme.text_dim),
+                ))
+                .block(block);
+                f.render_widget(p, area);
+                return;
+            }
+
+            let items: Vec<ListItem> = menu
+                .journald_units
+                .iter()
+                .enumerate()
+                .map(|(i, unit)| {
+                    let checkbox = if menu.selected.contains(&i) {
+                        "[x] "
+                    } else {
+                        "[ ] "
+                    };
+                    let cursor = if i == menu.discovery_cursor {
+                        "▸ "
+                    } else {
+                        "  "
+                    };
+                    let style = if i == menu.discovery_cursor {
+                        Style::default()
+                            .fg(theme.selected_fg)
+                            .bg(theme.selected_bg)
+                    } else {
+                        Style::default().fg(theme.text)
+                    };
+                    ListItem::new(Line::from(vec![
+                        Span::styled(cursor, Style::default().fg(theme.accent)),
+                        Span::styled(
+                            checkbox.to_string(),
+                            Style::default().fg(theme.success),
+                        ),
+                        Span::styled(unit.clone(), style),
+                    ]))
+                })
+                .collect();
+
+            let list = List::new(items).block(block);
+            f.render_widget(list, area);
+        }
         SourceMenuScreen::FileInput => {
             let block = Block::default()
                 .borders(Borders::ALL)
@@ -942,6 +1379,43 @@ fn render_source_menu(f: &mut Frame, area: Rect, app: &App, theme: &Theme) {
             let p = Paragraph::new(text).block(block);
             f.render_widget(p, area);
         }
+        SourceMenuScreen::SshInput => {
+            let block = Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .border_style(Style::default().fg(theme.modal_border))
+                .style(Style::default().bg(theme.modal_bg))
+                .title(Span::styled(
+                    " SSH Target (Enter to add, Esc to cancel) ",
+                    Style::default()
+                        .fg(theme.modal_title)
+                        .add_modifier(Modifier::BOLD),
+                ));
+
+            let mut lines = vec![
+                Line::from(vec![
+                    Span::styled("ssh ", Style::default().fg(theme.accent)),
+                    Span::styled(
+                        menu.text_input.clone(),
+                        Style::default().fg(theme.text),
+                    ),
+                    Span::styled("█", Style::default().fg(theme.accent)),
+                ]),
+                Line::from(Span::styled(
+                    "user@host:/var/log/app.log  or  host:journald[:unit,...]",
+                    Style::default().fg(theme.text_dim),
+                )),
+            ];
+            if let Some(ref err) = menu.ssh_error {
+                lines.push(Line::from(Span::styled(
+                    err.clone(),
+                    Style::default().fg(theme.error),
+                )));
+            }
+
+            let p = Paragraph::new(lines).block(block);
+            f.render_widget(p, area);
+        }
         SourceMenuScreen::CommandInput => {
             let block = Block::default()
                 .borders(Borders::ALL)
@@ -1200,7 +1674,8 @@ fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
         .split(popup_layout[1])[1]
 }
 
-fn render_help(f: &mut Frame, area: Rect, theme: &Theme) {
+/// Help overlay, generated from the active keymap so it always matches.
+fn render_help(f: &mut Frame, area: Rect, app: &App, theme: &Theme) {
     f.render_widget(Clear, area);
     let block = Block::default()
         .borders(Borders::ALL)
@@ -1208,12 +1683,20 @@ fn render_help(f: &mut Frame, area: Rect, theme: &Theme) {
         .border_style(Style::default().fg(theme.modal_border))
         .style(Style::default().bg(theme.modal_bg))
         .title(Span::styled(
-            " Help ",
+            format!(" Help ({} keys) ", app.keymap.preset),
             Style::default()
                 .fg(theme.modal_title)
                 .add_modifier(Modifier::BOLD),
         ));
-    let help_text = vec![
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let bindings: Vec<(String, &str)> = Action::ALL
+        .iter()
+        .map(|&action| (app.keymap.label(action), action.description()))
+        .filter(|(keys, _)| !keys.is_empty())
+        .collect();
+    let mut help_text = vec![
         Line::from(Span::styled(
             "Keybindings",
             Style::default()
@@ -1221,25 +1704,105 @@ fn render_help(f: &mut Frame, area: Rect, theme: &Theme) {
                 .add_modifier(Modifier::BOLD),
         )),
         Line::from(""),
-        help_line("Tab/Shift+Tab", "Switch panes", theme),
-        help_line("j/k or Up/Down", "Navigate", theme),
-        help_line("Enter", "Drilldown / lock search filter", theme),
-        help_line("b", "Back from drilldown", theme),
-        help_line("/", "Search patterns", theme),
-        help_line("Esc", "Clear filter / exit overlay", theme),
-        help_line("a", "Add source (interactive)", theme),
-        help_line("n", "Toggle normalized / raw", theme),
-        help_line("t", "Toggle color / mono theme", theme),
-        help_line("p", "Pause / resume ingest", theme),
-        help_line("r", "Reset all patterns", theme),
-        help_line("c", "Clear counters", theme),
-        help_line("P", "Profile picker", theme),
-        help_line("q", "Quit", theme),
-        help_line("?", "Toggle help", theme),
     ];
+    help_text.extend(bindings.iter().map(|(keys, desc)| help_line(keys, desc, theme)));
+
+    // Spill into a second column when the list doesn't fit
+    if help_text.len() > inner.height as usize {
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(inner);
+        let right = help_text.split_off(help_text.len().div_ceil(2));
+        f.render_widget(Paragraph::new(help_text), columns[0]);
+        f.render_widget(Paragraph::new(right), columns[1]);
+    } else {
+        f.render_widget(Paragraph::new(help_text), inner);
+    }
+}
 
-    let paragraph = Paragraph::new(help_text).block(block);
-    f.render_widget(paragraph, area);
+/// Command palette: the input line over the matching commands.
+fn render_palette(f: &mut Frame, area: Rect, app: &App, theme: &Theme) {
+    f.render_widget(Clear, area);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(theme.modal_border))
+        .style(Style::default().bg(theme.modal_bg))
+        .title(Span::styled(
+            " Command (Tab=complete Enter=run Esc=close) ",
+            Style::default()
+                .fg(theme.modal_title)
+                .add_modifier(Modifier::BOLD),
+        ));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+    if inner.height < 3 {
+        return;
+    }
+
+    let input = Line::from(vec![
+        Span::styled(": ", Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)),
+        Span::styled(app.palette.input.clone(), Style::default().fg(theme.text)),
+        Span::styled("█", Style::default().fg(theme.accent)),
+    ]);
+    f.render_widget(Paragraph::new(input), Rect { height: 1, ..inner });
+
+    let list_area = Rect {
+        y: inner.y + 2,
+        height: inner.height - 2,
+        ..inner
+    };
+    let suggestions = palette::suggestions(app);
+    if suggestions.is_empty() {
+        f.render_widget(
+            Paragraph::new(Span::styled("No matching command", Style::default().fg(theme.text_dim))),
+            list_area,
+        );
+        return;
+    }
+    // Scroll just far enough to keep the selection visible
+    let visible = (list_area.height as usize).max(1);
+    let offset = app.palette.selected.saturating_sub(visible - 1);
+    let width = suggestions
+        .iter()
+        .map(|s| s.text.chars().count() + s.usage.chars().count() + 1)
+        .max()
+        .unwrap_or(0)
+        .min(list_area.width as usize / 2);
+    let items: Vec<ListItem> = suggestions
+        .iter()
+        .enumerate()
+        .skip(offset)
+        .take(visible)
+        .map(|(i, s)| {
+            let selected = i == app.palette.selected;
+            let mut spans = vec![Span::styled(
+                if selected { "▸ " } else { "  " },
+                Style::default().fg(theme.accent),
+            )];
+            spans.extend(highlight_matches(&s.text, &s.matched, theme));
+            let shown = s.text.chars().count();
+            let usage = if s.usage.is_empty() {
+                String::new()
+            } else {
+                format!(" {}", s.usage)
+            };
+            spans.push(Span::styled(usage.clone(), Style::default().fg(theme.text_dim)));
+            let pad = width.saturating_sub(shown + usage.chars().count());
+            spans.push(Span::styled(
+                format!("{}  {}", " ".repeat(pad), s.description),
+                Style::default().fg(theme.text_dim),
+            ));
+            let line = Line::from(spans);
+            if selected {
+                ListItem::new(line).style(Style::default().bg(theme.selected_bg))
+            } else {
+                ListItem::new(line)
+            }
+        })
+        .collect();
+    f.render_widget(List::new(items), list_area);
 }
 
 fn help_line<'a>(key: &'a str, desc: &'a str, theme: &Theme) -> Line<'a> {
@@ -1292,6 +1855,55 @@ fn render_profile_picker(f: &mut Frame, area: Rect, app: &App, theme: &Theme) {
     f.render_widget(list, area);
 }
 
+fn render_alerts(f: &mut Frame, area: Rect, app: &App, theme: &Theme) {
+    let title = if app.alerts.is_empty() {
+        "Alerts (no [[alerts]] rules configured)".to_string()
+    } else {
+        format!("Alerts ({} fired, Esc to close)", app.alerts.log.len())
+    };
+    let block = pane_block(&title, true, theme);
+
+    let items: Vec<ListItem> = app
+        .alerts
+        .log
+        .iter()
+        .skip(app.alerts_scroll)
+        .map(|alert| {
+            ListItem::new(vec![
+                Line::from(vec![
+                    Span::styled(
+                        alert.at.format("%H:%M:%S ").to_string(),
+                        Style::default().fg(theme.text_dim),
+                    ),
+                    Span::styled(
+                        format!("{} ", alert.level.short()),
+                        Style::default()
+                            .fg(theme.level_color(alert.level))
+                            .add_modifier(Modifier::BOLD),
+                    ),
+                    Span::styled(
+                        alert.rule.clone(),
+                        Style::default()
+                            .fg(theme.accent)
+                            .add_modifier(Modifier::BOLD),
+                    ),
+                    Span::styled(
+                        format!("  {}", alert.outcome.join(", ")),
+                        Style::default().fg(theme.text_dim),
+                    ),
+                ]),
+                Line::from(Span::styled(
+                    format!("  {}", alert.pattern),
+                    Style::default().fg(theme.text),
+                )),
+            ])
+        })
+        .collect();
+
+    let list = List::new(items).block(block);
+    f.render_widget(list, area);
+}
+
 fn highlight_matches<'a>(text: &str, indices: &[usize], theme: &Theme) -> Vec<Span<'a>> {
     let chars: Vec<char> = text.chars().collect();
     let mut spans = Vec::new();
@@ -1380,9 +1992,7 @@ fn render_spark<'a>(
     // Left-pad with zeros if needed
     let total = buckets.len() + 1; // +1 for current
     let pad = WIDTH.saturating_sub(total);
-    for _ in 0..pad {
-        values.push(0);
-    }
+    values.resize(pad, 0);
     // Add completed buckets (skip oldest if more than WIDTH-1)
     let skip = if buckets.len() + 1 > WIDTH {
         buckets.len() + 1 - WIDTH
@@ -1511,7 +2121,7 @@ fn highlight_sample_terms<'a>(text: &str, theme: &Theme) -> Vec<Span<'a>> {
             spans.push(Span::styled(word.to_string(), warn_style));
         } else if trimmed.chars().all(|c| c.is_ascii_digit() || c == '.' || c == '-' || c == ':')
             && trimmed.chars().any(|c| c.is_ascii_digit())
-            && trimmed.len() > 0
+            && !trimmed.is_empty()
         {
             if !buf.is_empty() {
                 spans.push(Span::styled(std::mem::take(&mut buf), text_style));
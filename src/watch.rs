@@ -0,0 +1,384 @@
+//! Headless `watch` mode: sources, clustering and alert rules without the TUI.
+//!
+//! Fired alerts are written as they happen, and a summary (new patterns,
+//! spikes, top movers) every `--interval`. The run ends on Ctrl-C / SIGTERM,
+//! after `--duration`, or once every source has finished, and reports whether
+//! any alert fired so the caller can exit non-zero.
+
+use std::fmt::Write as _;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use tokio::sync::mpsc;
+
+use crate::alerts::FiredAlert;
+use crate::app::App;
+use crate::ingest::SourceEvent;
+use crate::parse::Level;
+use crate::pattern::{Pattern, PatternStore};
+
+/// Exit status when at least one alert fired (1 is left for errors).
+pub const ALERT_EXIT_CODE: i32 = 2;
+const TICK: Duration = Duration::from_millis(250);
+/// Entries per list in a summary (spikes are always listed in full).
+const TOP_N: usize = 10;
+/// How long to wait for alert commands and webhooks before exiting.
+const SETTLE_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum WatchFormat {
+    Text,
+    Json,
+}
+
+pub struct WatchOptions {
+    pub interval: Duration,
+    pub format: WatchFormat,
+    pub output: Option<String>,
+    pub duration: Option<Duration>,
+    pub fail_fast: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PatternSummary {
+    pub id: String,
+    pub level: &'static str,
+    pub pattern: String,
+    pub count: u64,
+    /// Lines since the previous summary.
+    pub delta: u64,
+    pub rate_1m: f64,
+}
+
+/// Level as written in watch output (`???` isn't useful to a log shipper).
+fn level_name(level: Level) -> &'static str {
+    match level {
+        Level::Unknown => "UNKNOWN",
+        level => level.as_str(),
+    }
+}
+
+impl PatternSummary {
+    fn new(p: &Pattern, delta: u64) -> Self {
+        PatternSummary {
+            id: p.id(),
+            level: level_name(p.level),
+            pattern: p.canonical.clone(),
+            count: p.count_total,
+            delta,
+            rate_1m: p.rate_1m(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Summary {
+    pub at: String,
+    pub lines: u64,
+    pub patterns: usize,
+    pub new_patterns: Vec<PatternSummary>,
+    pub spikes: Vec<PatternSummary>,
+    pub top_movers: Vec<PatternSummary>,
+    /// Alerts fired since the previous summary.
+    pub alerts: u64,
+}
+
+/// Remembers pattern counts between summaries to work out what changed.
+#[derive(Default)]
+pub struct Summarizer {
+    counts: Vec<u64>,
+    alerts: u64,
+}
+
+impl Summarizer {
+    pub fn summarize(&mut self, store: &PatternStore, lines: u64, alerts_fired: u64) -> Summary {
+        let patterns = store.patterns();
+        let known = self.counts.len();
+        let mut new_patterns = Vec::new();
+        let mut movers = Vec::new();
+        for (idx, p) in patterns.iter().enumerate() {
+            let before = self.counts.get(idx).copied().unwrap_or(0);
+            let delta = p.count_total.saturating_sub(before);
+            if idx >= known {
+                new_patterns.push(PatternSummary::new(p, delta));
+            } else if delta > 0 {
+                movers.push(PatternSummary::new(p, delta));
+            }
+        }
+        new_patterns.sort_by_key(|p| std::cmp::Reverse(p.count));
+        new_patterns.truncate(TOP_N);
+        movers.sort_by_key(|p| std::cmp::Reverse(p.delta));
+        movers.truncate(TOP_N);
+        let spikes = patterns
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| p.spike)
+            .map(|(idx, p)| {
+                let before = self.counts.get(idx).copied().unwrap_or(0);
+                PatternSummary::new(p, p.count_total.saturating_sub(before))
+            })
+            .collect();
+
+        self.counts = patterns.iter().map(|p| p.count_total).collect();
+        let alerts = alerts_fired - self.alerts;
+        self.alerts = alerts_fired;
+        Summary {
+            at: chrono::Local::now().to_rfc3339(),
+            lines,
+            patterns: patterns.len(),
+            new_patterns,
+            spikes,
+            top_movers: movers,
+            alerts,
+        }
+    }
+}
+
+pub fn render_summary(format: WatchFormat, summary: &Summary) -> String {
+    match format {
+        WatchFormat::Json => {
+            let mut value = serde_json::to_value(summary).unwrap_or_default();
+            value["type"] = "summary".into();
+            value.to_string() + "\n"
+        }
+        WatchFormat::Text => {
+            let mut out = format!(
+                "{} summary: {} lines, {} patterns, {} new, {} spiking, {} alerts\n",
+                summary.at,
+                summary.lines,
+                summary.patterns,
+                summary.new_patterns.len(),
+                summary.spikes.len(),
+                summary.alerts,
+            );
+            let rows = [
+                ("new", &summary.new_patterns),
+                ("spike", &summary.spikes),
+                ("mover", &summary.top_movers),
+            ];
+            for (label, list) in rows {
+                for p in list {
+                    let _ = writeln!(
+                        out,
+                        "  {:<6} {:<5} +{:<6} {:>6.0}/m  {}",
+                        label, p.level, p.delta, p.rate_1m, p.pattern
+                    );
+                }
+            }
+            out
+        }
+    }
+}
+
+pub fn render_alert(format: WatchFormat, alert: &FiredAlert) -> String {
+    match format {
+        WatchFormat::Json => {
+            serde_json::json!({
+                "type": "alert",
+                "at": alert.at.to_rfc3339(),
+                "rule": alert.rule,
+                "level": level_name(alert.level),
+                "pattern": alert.pattern,
+            })
+            .to_string()
+                + "\n"
+        }
+        WatchFormat::Text => format!(
+            "{} ALERT {} [{}] {}\n",
+            alert.at.to_rfc3339(),
+            alert.rule,
+            level_name(alert.level),
+            alert.pattern
+        ),
+    }
+}
+
+fn open_output(path: Option<&str>) -> Result<Box<dyn Write>> {
+    match path {
+        Some(path) => {
+            let file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .with_context(|| format!("opening {}", path))?;
+            Ok(Box::new(file))
+        }
+        None => Ok(Box::new(std::io::stdout())),
+    }
+}
+
+async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        if let Ok(mut term) = signal(SignalKind::terminate()) {
+            tokio::select! {
+                _ = tokio::signal::ctrl_c() => {}
+                _ = term.recv() => {}
+            }
+            return;
+        }
+    }
+    let _ = tokio::signal::ctrl_c().await;
+}
+
+/// Run until stopped; returns whether any alert fired.
+pub async fn run(
+    mut app: App,
+    mut rx: mpsc::Receiver<SourceEvent>,
+    options: WatchOptions,
+) -> Result<bool> {
+    let mut out = open_output(options.output.as_deref())?;
+    let started = Instant::now();
+    let mut last_summary = started;
+    let mut summarizer = Summarizer::default();
+    let mut reported = 0;
+    let mut ticker = tokio::time::interval(TICK);
+    let shutdown = shutdown_signal();
+    tokio::pin!(shutdown);
+
+    loop {
+        tokio::select! {
+            _ = ticker.tick() => {}
+            _ = &mut shutdown => break,
+        }
+        while let Ok(ev) = rx.try_recv() {
+            app.process_event(ev);
+        }
+        app.tick();
+        reported = report_alerts(&mut out, &app, reported, options.format)?;
+
+        if options.fail_fast && reported > 0 {
+            break;
+        }
+        if last_summary.elapsed() >= options.interval {
+            last_summary = Instant::now();
+            let summary = summarizer.summarize(&app.store, app.log_count, app.alerts.fired());
+            write_out(&mut out, &render_summary(options.format, &summary))?;
+        }
+        if options.duration.is_some_and(|d| started.elapsed() >= d) {
+            break;
+        }
+        let finished = !app.handles.is_empty() && app.handles.values().all(|h| h.is_finished());
+        if finished {
+            break;
+        }
+    }
+
+    // Whatever arrived before the stop still counts
+    while let Ok(ev) = rx.try_recv() {
+        app.process_event(ev);
+    }
+    app.check_alerts_now();
+    report_alerts(&mut out, &app, reported, options.format)?;
+    let summary = summarizer.summarize(&app.store, app.log_count, app.alerts.fired());
+    write_out(&mut out, &render_summary(options.format, &summary))?;
+    app.alerts.settle(SETTLE_TIMEOUT).await;
+    Ok(app.alerts.fired() > 0)
+}
+
+/// Write alerts fired since `reported`, oldest first; returns the new total.
+fn report_alerts(out: &mut dyn Write, app: &App, reported: u64, format: WatchFormat) -> Result<u64> {
+    let fired = app.alerts.fired();
+    let new = (fired - reported) as usize;
+    for alert in app.alerts.log.iter().take(new).rev() {
+        write_out(out, &render_alert(format, alert))?;
+    }
+    Ok(fired)
+}
+
+fn write_out(out: &mut dyn Write, text: &str) -> Result<()> {
+    out.write_all(text.as_bytes())?;
+    out.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::LogEvent;
+
+    fn ingest(store: &mut PatternStore, normalized: &str, level: Level, n: usize) {
+        for _ in 0..n {
+            store.ingest(&LogEvent {
+                level,
+                source: "file/app.log".into(),
+                raw: normalized.into(),
+                normalized: normalized.into(),
+                fields: Vec::new(),
+            });
+        }
+    }
+
+    #[test]
+    fn summaries_track_new_patterns_and_movers() {
+        let mut store = PatternStore::new();
+        let mut summarizer = Summarizer::default();
+        ingest(&mut store, "GET /api/<NUM> <NUM>", Level::Info, 5);
+        let first = summarizer.summarize(&store, 5, 0);
+        assert_eq!(first.new_patterns.len(), 1);
+        assert!(first.top_movers.is_empty());
+
+        ingest(&mut store, "GET /api/<NUM> <NUM>", Level::Info, 3);
+        ingest(&mut store, "db timeout after <NUM>ms", Level::Error, 2);
+        let second = summarizer.summarize(&store, 10, 1);
+        assert_eq!(second.new_patterns[0].pattern, "db timeout after <NUM>ms");
+        assert_eq!(second.top_movers[0].delta, 3);
+        assert_eq!(second.top_movers[0].count, 8);
+        assert_eq!(second.alerts, 1);
+
+        let text = render_summary(WatchFormat::Text, &second);
+        assert!(text.contains(" summary: 10 lines, 2 patterns, 1 new, 0 spiking, 1 alerts\n"));
+        assert!(text.contains("  new    ERROR +2"));
+        assert!(text.contains("  mover  INFO  +3"));
+        let json: serde_json::Value =
+            serde_json::from_str(&render_summary(WatchFormat::Json, &second)).unwrap();
+        assert_eq!(json["type"], "summary");
+        assert_eq!(json["top_movers"][0]["pattern"], "GET /api/<NUM> <NUM>");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn watch_reports_alerts_and_stops_when_sources_finish() {
+        use crate::alerts::AlertEngine;
+        use crate::config::AlertConfig;
+
+        let out = std::env::temp_dir().join(format!("logradar-watch-{}.ndjson", std::process::id()));
+        let _ = std::fs::remove_file(&out);
+        let rule: AlertConfig = toml::from_str("name = \"oom\"\npattern = \"out of memory\"").unwrap();
+        let mut app = App::new(None);
+        app.alerts = AlertEngine::from_config(&[rule]).unwrap();
+        let (tx, rx) = mpsc::channel(1024);
+        app.set_tx(tx);
+        app.add_command_source("printf 'ERROR worker 3 out of memory\\nINFO ok\\n'".into());
+
+        let options = WatchOptions {
+            interval: Duration::from_secs(3600),
+            format: WatchFormat::Json,
+            output: Some(out.display().to_string()),
+            duration: Some(Duration::from_secs(10)),
+            fail_fast: false,
+        };
+        let alerted = tokio::time::timeout(Duration::from_secs(15), run(app, rx, options))
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(alerted);
+
+        let lines: Vec<serde_json::Value> = std::fs::read_to_string(&out)
+            .unwrap()
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(lines[0]["type"], "alert");
+        assert_eq!(lines[0]["rule"], "oom");
+        let summary = lines.last().unwrap();
+        assert_eq!(summary["type"], "summary");
+        assert_eq!(summary["lines"], 2);
+        assert_eq!(summary["alerts"], 1);
+        let _ = std::fs::remove_file(&out);
+    }
+}
@@ -0,0 +1,357 @@
+//! Named workspaces: `[workspaces.<name>]` tables listing sources to start
+//! together with a profile and theme, opened with `--workspace` or the
+//! `workspace` palette command and saved from the running set of sources.
+
+use std::collections::BTreeMap;
+
+use anyhow::{bail, Context, Result};
+
+use crate::app::App;
+use crate::config::{self, WorkspaceConfig, WorkspaceSource};
+use crate::ingest::azure::ContainerAppRef;
+use crate::ingest::journald::JournalFilter;
+use crate::ingest::{self, Source};
+use crate::profile::Profile;
+use crate::theme::Theme;
+
+/// What a workspace source starts.
+#[derive(Debug)]
+pub enum Launch {
+    Source(Source),
+    Azure {
+        app: ContainerAppRef,
+        revision: Option<String>,
+        system_logs: bool,
+    },
+}
+
+impl WorkspaceSource {
+    /// The single source kind set on this entry.
+    pub fn kind(&self) -> Result<&'static str> {
+        let kinds = [
+            ("docker", self.docker.is_some()),
+            ("cmd", self.cmd.is_some()),
+            ("file", self.file.is_some()),
+            ("ssh", self.ssh.is_some()),
+            ("k8s", self.k8s.is_some()),
+            ("journald", self.journald.is_some()),
+            ("syslog", self.syslog.is_some()),
+            ("http", self.http.is_some()),
+            ("otlp", self.otlp.is_some()),
+            ("azure", self.azure.is_some()),
+        ];
+        let set: Vec<&str> = kinds.iter().filter(|(_, set)| *set).map(|(kind, _)| *kind).collect();
+        let kind = match set[..] {
+            [kind] => kind,
+            [] => bail!("source needs one of: {}, azure", ingest::SOURCE_KINDS.join(", ")),
+            _ => bail!("source sets more than one kind: {}", set.join(", ")),
+        };
+        let extras = [
+            ("priority", self.priority.is_some(), "journald"),
+            ("since", self.since.is_some(), "journald"),
+            ("resource_group", self.resource_group.is_some(), "azure"),
+            ("subscription", self.subscription.is_some(), "azure"),
+            ("revision", self.revision.is_some(), "azure"),
+            ("system_logs", self.system_logs, "azure"),
+        ];
+        if let Some((key, _, owner)) = extras.iter().find(|(_, set, owner)| *set && *owner != kind) {
+            bail!("'{}' only applies to {} sources", key, owner);
+        }
+        Ok(kind)
+    }
+
+    pub fn launch(&self) -> Result<Launch> {
+        let kind = self.kind()?;
+        let spec = match kind {
+            "journald" => {
+                return Ok(Launch::Source(Source::Journald(JournalFilter {
+                    units: self.journald.clone().unwrap_or_default(),
+                    priority: self.priority.clone(),
+                    since: self.since.clone(),
+                })));
+            }
+            "azure" => {
+                let (Some(resource_group), Some(subscription)) = (&self.resource_group, &self.subscription)
+                else {
+                    bail!("azure source needs resource_group and subscription");
+                };
+                return Ok(Launch::Azure {
+                    app: ContainerAppRef {
+                        subscription_id: subscription.clone(),
+                        resource_group: resource_group.clone(),
+                        name: self.azure.clone().unwrap_or_default(),
+                    },
+                    revision: self.revision.clone(),
+                    system_logs: self.system_logs,
+                });
+            }
+            _ => [&self.docker, &self.cmd, &self.file, &self.ssh, &self.k8s, &self.syslog, &self.http, &self.otlp]
+                .into_iter()
+                .find_map(Option::as_deref)
+                .unwrap_or_default(),
+        };
+        Ok(Launch::Source(Source::parse(kind, spec)?))
+    }
+
+    /// The entry that starts `source` again.
+    pub fn from_source(source: &Source) -> WorkspaceSource {
+        let mut entry = WorkspaceSource::default();
+        match source {
+            Source::Docker(container) => entry.docker = Some(container.clone()),
+            Source::Command(cmd) => entry.cmd = Some(cmd.clone()),
+            Source::File(path) => entry.file = Some(path.clone()),
+            Source::Ssh(target) => entry.ssh = Some(target.spec()),
+            Source::Kubernetes(selector) => entry.k8s = Some(selector.spec()),
+            Source::Journald(filter) => {
+                entry.journald = Some(filter.units.clone());
+                entry.priority = filter.priority.clone();
+                entry.since = filter.since.clone();
+            }
+            Source::Syslog(listen) => entry.syslog = Some(listen.label()),
+            Source::Http(addr) => entry.http = Some(addr.to_string()),
+            Source::Otlp(addr) => entry.otlp = Some(addr.to_string()),
+        }
+        entry
+    }
+
+    pub fn azure(
+        app: &ContainerAppRef,
+        revision: Option<String>,
+        system_logs: bool,
+    ) -> WorkspaceSource {
+        WorkspaceSource {
+            azure: Some(app.name.clone()),
+            resource_group: Some(app.resource_group.clone()),
+            subscription: Some(app.subscription_id.clone()),
+            revision,
+            system_logs,
+            ..Default::default()
+        }
+    }
+}
+
+/// Validate every workspace: its sources, profile and theme (a built-in or
+/// one of `themes`).
+pub fn check(
+    workspaces: &BTreeMap<String, WorkspaceConfig>,
+    profiles: &[Profile],
+    themes: &[Theme],
+) -> Result<()> {
+    for (name, workspace) in workspaces {
+        let context = || format!("[workspaces.{}]", name);
+        for source in &workspace.sources {
+            source.launch().with_context(context)?;
+        }
+        if let Some(ref profile) = workspace.profile {
+            if !profiles.iter().any(|p| &p.name == profile) {
+                bail!("{}: unknown profile '{}'", context(), profile);
+            }
+        }
+        if let Some(ref theme) = workspace.theme {
+            if Theme::by_name(theme, themes).is_none() {
+                bail!("{}: unknown theme '{}'", context(), theme);
+            }
+        }
+    }
+    Ok(())
+}
+
+impl App {
+    pub fn workspace(&self, name: &str) -> Result<WorkspaceConfig> {
+        match self.workspaces.get(name) {
+            Some(workspace) => Ok(workspace.clone()),
+            None if self.workspaces.is_empty() => bail!("no workspace '{}' (none are configured)", name),
+            None => bail!(
+                "no workspace '{}' (available: {})",
+                name,
+                self.workspaces.keys().cloned().collect::<Vec<_>>().join(", ")
+            ),
+        }
+    }
+
+    /// Start a workspace's sources; returns how many were started.
+    pub fn start_workspace_sources(&mut self, workspace: &WorkspaceConfig) -> Result<usize> {
+        // Parse them all first so a bad entry starts nothing
+        let launches = workspace
+            .sources
+            .iter()
+            .map(WorkspaceSource::launch)
+            .collect::<Result<Vec<_>>>()?;
+        let count = launches.len();
+        for launch in launches {
+            match launch {
+                Launch::Source(source) => self.add_source(source),
+                Launch::Azure {
+                    app,
+                    revision,
+                    system_logs,
+                } => {
+                    if system_logs {
+                        self.add_azure_system_source(app.clone());
+                    }
+                    self.add_azure_source(
+                        app.name,
+                        app.resource_group,
+                        app.subscription_id,
+                        revision,
+                        system_logs,
+                    );
+                }
+            }
+        }
+        Ok(count)
+    }
+
+    /// Switch to a workspace's profile and theme and start its sources.
+    pub fn open_workspace(&mut self, name: &str) -> Result<()> {
+        let workspace = self.workspace(name)?;
+        if let Some(ref profile) = workspace.profile {
+            self.profile_index = self
+                .profiles
+                .iter()
+                .position(|p| &p.name == profile)
+                .with_context(|| format!("unknown profile '{}'", profile))?;
+        }
+        if let Some(ref theme) = workspace.theme {
+            self.theme_override = Some(Theme::by_name(theme, &self.custom_themes).with_context(|| format!("unknown theme '{}'", theme))?);
+        }
+        let count = self.start_workspace_sources(&workspace)?;
+        self.needs_clear = true;
+        self.show_toast(format!("Opened workspace {} ({} sources)", name, count), false);
+        Ok(())
+    }
+
+    /// The running sources (in Sources pane order), current profile and
+    /// theme as a workspace.
+    pub fn current_workspace(&self) -> WorkspaceConfig {
+        let mut sources: Vec<WorkspaceSource> = Vec::new();
+        for info in &self.sources {
+            if let Some(source) = self.source_specs.get(&info.id) {
+                if !sources.contains(source) {
+                    sources.push(source.clone());
+                }
+            }
+        }
+        WorkspaceConfig {
+            profile: Some(self.profile().name.clone()),
+            theme: Some(self.theme().name.clone()),
+            sources,
+        }
+    }
+
+    /// Save the running sources as `[workspaces.<name>]` in the config file.
+    pub fn save_workspace(&mut self, name: &str) -> Result<()> {
+        let name = name.trim();
+        if name.is_empty() {
+            bail!("save_workspace needs a name");
+        }
+        let Some(path) = self.config_path.clone() else {
+            bail!("no config file to save to");
+        };
+        let workspace = self.current_workspace();
+        if workspace.sources.is_empty() {
+            bail!("no sources to save");
+        }
+        config::save_workspace(&path, name, &workspace)?;
+        if let Some(ref mut watcher) = self.config_watcher {
+            watcher.mark_seen();
+        }
+        let count = workspace.sources.len();
+        self.workspaces.insert(name.to_string(), workspace);
+        self.show_toast(
+            format!("Saved workspace {} ({} sources) to {}", name, count, path.display()),
+            false,
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(toml: &str) -> WorkspaceSource {
+        toml::from_str(toml).unwrap()
+    }
+
+    #[test]
+    fn sources_round_trip_through_workspace_entries() {
+        for (kind, spec) in [
+            ("docker", "api"),
+            ("cmd", "tail -f /tmp/x.log"),
+            ("file", "/var/log/app.log"),
+            ("ssh", "deploy@web1:/var/log/nginx/error.log"),
+            ("ssh", "[::1]:journald:nginx,php-fpm"),
+            ("k8s", "prod/app=checkout"),
+            ("k8s", "prod/"),
+            ("syslog", "tcp://0.0.0.0:6514"),
+            ("http", "127.0.0.1:8088"),
+            ("otlp", "0.0.0.0:4318"),
+        ] {
+            let source = Source::parse(kind, spec).unwrap();
+            let entry = WorkspaceSource::from_source(&source);
+            assert_eq!(entry.kind().unwrap(), kind);
+            let Launch::Source(again) = entry.launch().unwrap() else {
+                panic!("{} became an azure source", spec);
+            };
+            assert_eq!(format!("{:?}", again), format!("{:?}", source), "{} {}", kind, spec);
+        }
+
+        let journal = entry("journald = [\"nginx\"]\npriority = \"warning\"");
+        let Launch::Source(Source::Journald(filter)) = journal.launch().unwrap() else {
+            panic!("not a journald source");
+        };
+        assert_eq!(filter.units, vec!["nginx"]);
+        assert_eq!(filter.priority.as_deref(), Some("warning"));
+    }
+
+    #[test]
+    fn bad_entries_are_rejected() {
+        let err = |toml: &str| entry(toml).launch().unwrap_err().to_string();
+        assert!(err("").contains("needs one of"));
+        assert!(err("docker = \"a\"\nfile = \"b\"").contains("more than one kind: docker, file"));
+        assert!(err("docker = \"a\"\nsince = \"-1h\"").contains("'since' only applies to journald"));
+        assert!(err("azure = \"checkout\"\nresource_group = \"rg\"").contains("resource_group and subscription"));
+        assert!(err("http = \"nope\"").contains("invalid listen address"));
+        assert!(toml::from_str::<WorkspaceSource>("dokcer = \"api\"").is_err());
+
+        let azure = entry("azure = \"checkout\"\nresource_group = \"rg\"\nsubscription = \"sub\"\nsystem_logs = true");
+        let Launch::Azure { app, system_logs, .. } = azure.launch().unwrap() else {
+            panic!("not an azure source");
+        };
+        assert_eq!(app.name, "checkout");
+        assert!(system_logs);
+        assert_eq!(WorkspaceSource::azure(&app, None, system_logs), azure);
+    }
+
+    #[tokio::test]
+    async fn save_workspace_writes_the_running_sources() {
+        let path = std::env::temp_dir().join(format!("logradar-workspace-{}.toml", std::process::id()));
+        std::fs::write(&path, "# mine\ndefault_profile = \"ops\"\n").unwrap();
+        let mut app = App::new(None);
+        let (tx, _rx) = tokio::sync::mpsc::channel(16);
+        app.set_tx(tx);
+        app.config_path = Some(path.clone());
+        assert!(app.save_workspace("checkout").is_err());
+
+        app.add_command_source("true".into());
+        app.add_source(Source::parse("journald", "nginx").unwrap());
+        // Same spec twice is saved once
+        app.add_command_source("true".into());
+        app.save_workspace("checkout").unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert!(content.starts_with("# mine\n"));
+        let cfg: crate::config::Config = toml::from_str(&content).unwrap();
+        let saved = &cfg.workspaces["checkout"];
+        assert_eq!(saved, &app.workspaces["checkout"]);
+        assert_eq!(saved.profile.as_deref(), Some("default"));
+        assert_eq!(saved.sources.len(), 2);
+        assert_eq!(saved.sources[0].cmd.as_deref(), Some("true"));
+        assert_eq!(saved.sources[1].journald, Some(vec!["nginx".to_string()]));
+        for handle in app.handles.values() {
+            handle.abort();
+        }
+    }
+}